@@ -10,7 +10,6 @@ pub enum ApiError {
     #[error("Not found: {0}")]
     NotFound(String),
     #[error("Bad request: {0}")]
-    #[allow(dead_code)]
     BadRequest(String),
     #[error("Forbidden: {0}")]
     Forbidden(String),
@@ -0,0 +1,43 @@
+use axum::{
+    async_trait,
+    body::{Body, Bytes},
+    extract::{FromRequest, Request},
+    Json,
+};
+use serde::de::DeserializeOwned;
+
+use crate::error::ApiError;
+
+/// JSON body that falls back to `T::default()` only when the body is empty.
+///
+/// Unlike `Option<Json<T>>`, which turns every rejection into `None`, a
+/// malformed body or a non-JSON content type is a 400.
+#[derive(Debug)]
+pub struct JsonOrDefault<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for JsonOrDefault<T>
+where
+    T: DeserializeOwned + Default,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let headers = req.headers().clone();
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(|e| ApiError::BadRequest(e.body_text()))?;
+        if bytes.is_empty() {
+            return Ok(Self(T::default()));
+        }
+
+        // Rebuild the request so `Json` checks the content type as usual
+        let mut req = Request::new(Body::from(bytes));
+        *req.headers_mut() = headers;
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|e| ApiError::BadRequest(e.body_text()))?;
+        Ok(Self(value))
+    }
+}
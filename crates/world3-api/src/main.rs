@@ -1,4 +1,5 @@
 mod error;
+mod extract;
 mod models;
 mod routes;
 mod state;
@@ -1,10 +1,7 @@
 use serde::{Deserialize, Serialize};
 use world3_core::{
-    model::state::{
-        AgricultureState, CapitalState, PollutionState, PopulationState, ResourceState, WorldState,
-    },
-    output::SimulationOutput,
-    ScenarioParams,
+    model::state::WorldState, output::SimulationOutput, Checkpoint, EventSpec, Flows,
+    Multipliers, ScenarioParams,
 };
 
 // ---------------------------------------------------------------------------
@@ -40,6 +37,78 @@ impl From<&Scenario> for ScenarioSummary {
     }
 }
 
+/// Optional body of `POST /scenarios/:id/run`.
+#[derive(Debug, Default, Deserialize)]
+pub struct RunRequest {
+    /// Resume from this checkpoint (with the scenario's current params)
+    /// instead of starting from the 1900 initial conditions.
+    pub checkpoint: Option<Checkpoint>,
+    /// Conditions to locate during the run; recorded in `SimulationOutput.events`
+    #[serde(default)]
+    pub events: Vec<EventSpec>,
+}
+
+/// Query of `POST /scenarios/:id/run`.
+#[derive(Debug, Default, Deserialize)]
+pub struct RunQuery {
+    /// On solver failure, return the states computed before it (with
+    /// `error` set on the output) instead of an error response.
+    #[serde(default)]
+    pub partial: bool,
+    /// Include each state's `flows`
+    #[serde(default)]
+    pub flows: bool,
+    /// Include each state's `multipliers`
+    #[serde(default)]
+    pub multipliers: bool,
+    /// Include each state's `inequality.groups`
+    #[serde(default)]
+    pub groups: bool,
+}
+
+impl RunQuery {
+    pub fn detail(&self) -> StateDetail {
+        StateDetail {
+            flows: self.flows,
+            multipliers: self.multipliers,
+            groups: self.groups,
+        }
+    }
+}
+
+/// Per-state blocks sent only on request. Together they are several times
+/// the size of the sector states; run totals stay in `SimulationOutput.ledger`.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct StateDetail {
+    #[serde(default)]
+    pub flows: bool,
+    #[serde(default)]
+    pub multipliers: bool,
+    #[serde(default)]
+    pub groups: bool,
+}
+
+impl StateDetail {
+    /// Clear the blocks not requested; cleared blocks are left out of the JSON.
+    pub fn apply(&self, state: &mut WorldState) {
+        if !self.flows {
+            state.flows = Flows::default();
+        }
+        if !self.multipliers {
+            state.multipliers = Multipliers::default();
+        }
+        if !self.groups {
+            state.inequality.groups.clear();
+        }
+    }
+}
+
+/// Query of `GET /scenarios/:id/checkpoint`.
+#[derive(Debug, Deserialize)]
+pub struct CheckpointQuery {
+    pub year: f64,
+}
+
 // ---------------------------------------------------------------------------
 // WebSocket messages
 // ---------------------------------------------------------------------------
@@ -50,10 +119,14 @@ pub enum WsClientMsg {
     StartSimulation {
         scenario_id: String,
         params: Option<ScenarioParams>,
+        #[serde(default)]
+        detail: StateDetail,
     },
     UpdateParams {
         scenario_id: String,
         params: ScenarioParams,
+        #[serde(default)]
+        detail: StateDetail,
     },
     StopSimulation,
 }
@@ -63,7 +136,7 @@ pub enum WsClientMsg {
 pub enum WsServerMsg {
     SimStep {
         year: f64,
-        state: WorldState,
+        state: Box<WorldState>,
     },
     SimComplete {
         scenario_id: String,
@@ -76,43 +149,3 @@ pub enum WsServerMsg {
         scenario_id: String,
     },
 }
-
-// ---------------------------------------------------------------------------
-// Initial conditions for 1900
-// ---------------------------------------------------------------------------
-
-/// World 3 initial conditions for year 1900.
-/// Values calibrated to broadly match Meadows 1972 standard run starting point.
-pub fn initial_conditions_1900() -> WorldState {
-    WorldState {
-        time: 1900.0,
-        population: PopulationState {
-            population: 1.6e9,
-            cohort_0_14: 0.60e9,
-            cohort_15_44: 0.65e9,
-            cohort_45_64: 0.27e9,
-            cohort_65_plus: 0.08e9,
-            ..Default::default()
-        },
-        capital: CapitalState {
-            industrial_capital: 0.2e12,
-            service_capital: 0.32e12,
-            ..Default::default()
-        },
-        agriculture: AgricultureState {
-            arable_land: 0.9e9,
-            potentially_arable_land: 2.3e9,
-            food_per_capita: 400.0,
-            ..Default::default()
-        },
-        resources: ResourceState {
-            nonrenewable_resources: 1.0,
-            fraction_remaining: 1.0,
-        },
-        pollution: PollutionState {
-            persistent_pollution: 0.05,
-            pollution_index: 0.05,
-            ..Default::default()
-        },
-    }
-}
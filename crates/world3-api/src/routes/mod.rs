@@ -36,6 +36,8 @@ pub fn build_router(state: AppState) -> Router {
         .route("/scenarios/:id", get(scenarios::get_scenario).delete(scenarios::delete_scenario))
         .route("/scenarios/:id/params", put(scenarios::update_params))
         .route("/scenarios/:id/run", post(scenarios::run_scenario))
+        .route("/scenarios/:id/checkpoint", get(scenarios::get_checkpoint))
+        .route("/scenarios/:id/elasticities", post(scenarios::compute_elasticities))
         // WebSocket
         .route("/ws", get(ws::ws_handler));
 
@@ -1,14 +1,19 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
-use world3_core::{output::SimulationOutput, solver::traits::OdeSolver, ScenarioParams};
+use world3_core::{
+    elasticities_with, initial_conditions_1900, output::SimulationOutput, CancelToken, Checkpoint,
+    ElasticitySpec, ElasticityTable, RunControl, ScenarioParams, SensitivityError, Simulation,
+    SolverError,
+};
 
 use crate::{
     error::ApiError,
-    models::{initial_conditions_1900, Scenario, ScenarioSummary},
+    extract::JsonOrDefault,
+    models::{CheckpointQuery, RunQuery, RunRequest, Scenario, ScenarioSummary},
     state::AppState,
 };
 
@@ -48,6 +53,7 @@ pub async fn create_scenario(
     State(state): State<Arc<AppState>>,
     Json(params): Json<ScenarioParams>,
 ) -> Result<Json<Scenario>, ApiError> {
+    params.validate_schedules().map_err(ApiError::BadRequest)?;
     let scenario = Scenario {
         params: params.clone(),
         is_preset: false,
@@ -84,6 +90,7 @@ pub async fn update_params(
     Path(id): Path<String>,
     Json(params): Json<ScenarioParams>,
 ) -> Result<Json<Scenario>, ApiError> {
+    params.validate_schedules().map_err(ApiError::BadRequest)?;
     let mut store = state.scenarios.write().await;
     let scenario = store
         .get_mut(&id)
@@ -119,9 +126,12 @@ pub async fn delete_scenario(
 pub async fn run_scenario(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
+    Query(query): Query<RunQuery>,
+    JsonOrDefault(request): JsonOrDefault<RunRequest>,
 ) -> Result<Json<SimulationOutput>, ApiError> {
+
     // Read params from store
-    let params = {
+    let mut params = {
         let store = state.scenarios.read().await;
         store
             .get(&id)
@@ -129,22 +139,42 @@ pub async fn run_scenario(
             .ok_or_else(|| ApiError::NotFound(format!("Scenario '{}' not found", id)))?
     };
 
+    let tables = Arc::clone(&state.tables);
+    let sim = match request.checkpoint {
+        Some(checkpoint) => {
+            // Output starts at the checkpoint year when it is after start_year
+            params.start_year = params.start_year.max(checkpoint.year());
+            Simulation::from_checkpoint(checkpoint, params.clone(), tables)
+        }
+        None => Simulation::new(initial_conditions_1900(), params.clone(), tables),
+    };
+    // Dropping this handler (client disconnect) cancels the blocking solve
+    let cancel = CancelToken::new();
+    let _guard = cancel.drop_guard();
+    let sim = sim
+        .and_then(|sim| sim.with_events(request.events))
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?
+        .with_control(RunControl::new().with_cancel(cancel));
+
     // Run simulation on blocking thread pool
-    let solver = Arc::clone(&state.solver);
-    let initial = initial_conditions_1900();
-    let result = tokio::task::spawn_blocking(move || solver.solve(initial, &params))
+    let result = tokio::task::spawn_blocking(move || sim.collect_partial())
         .await
         .map_err(|e| ApiError::Internal(anyhow::anyhow!("Task panicked: {}", e)))?;
 
-    let states = result.map_err(|e| ApiError::SimulationFailed(e.to_string()))?;
+    if !query.partial {
+        if let Some(e) = &result.error {
+            return Err(ApiError::SimulationFailed(e.to_string()));
+        }
+    }
 
     // Build output
-    let output = {
+    let mut output = {
         let store = state.scenarios.read().await;
-        let scenario_params = store.get(&id).map(|s| s.params.clone()).ok_or_else(|| {
+        let mut scenario_params = store.get(&id).map(|s| s.params.clone()).ok_or_else(|| {
             ApiError::NotFound(format!("Scenario '{}' not found after run", id))
         })?;
-        SimulationOutput::new(states, scenario_params)
+        scenario_params.start_year = params.start_year;
+        SimulationOutput::from_partial(result, scenario_params)
     };
 
     // Store last_output
@@ -155,5 +185,86 @@ pub async fn run_scenario(
         }
     }
 
+    let detail = query.detail();
+    output.states.iter_mut().for_each(|s| detail.apply(s));
     Ok(Json(output))
 }
+
+// ---------------------------------------------------------------------------
+// GET /api/v1/scenarios/:id/checkpoint?year=
+// ---------------------------------------------------------------------------
+
+pub async fn get_checkpoint(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<CheckpointQuery>,
+) -> Result<Json<Checkpoint>, ApiError> {
+    let params = {
+        let store = state.scenarios.read().await;
+        store
+            .get(&id)
+            .map(|s| s.params.clone())
+            .ok_or_else(|| ApiError::NotFound(format!("Scenario '{}' not found", id)))?
+    };
+
+    // Run the scenario up to the year and checkpoint the live simulation, so
+    // the checkpoint is an integration point with the solver's own state
+    let sim = Simulation::new(initial_conditions_1900(), params, Arc::clone(&state.tables))
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    if !(sim.time()..=sim.params().end_year).contains(&query.year) {
+        return Err(ApiError::BadRequest(format!(
+            "Checkpoint year {} outside the run ({:.1}–{:.1})",
+            query.year,
+            sim.time(),
+            sim.params().end_year
+        )));
+    }
+    // Dropping this handler (client disconnect) cancels the blocking solve
+    let cancel = CancelToken::new();
+    let _guard = cancel.drop_guard();
+    let mut sim = sim.with_control(RunControl::new().with_cancel(cancel));
+
+    let year = query.year;
+    let result = tokio::task::spawn_blocking(move || {
+        sim.run_until(year)?;
+        Ok::<_, SolverError>(sim.checkpoint())
+    })
+    .await
+    .map_err(|e| ApiError::Internal(anyhow::anyhow!("Task panicked: {}", e)))?;
+    result.map(Json).map_err(|e| ApiError::SimulationFailed(e.to_string()))
+}
+
+// ---------------------------------------------------------------------------
+// POST /api/v1/scenarios/:id/elasticities
+// ---------------------------------------------------------------------------
+
+pub async fn compute_elasticities(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    JsonOrDefault(spec): JsonOrDefault<ElasticitySpec>,
+) -> Result<Json<ElasticityTable>, ApiError> {
+    let params = {
+        let store = state.scenarios.read().await;
+        store
+            .get(&id)
+            .map(|s| s.params.clone())
+            .ok_or_else(|| ApiError::NotFound(format!("Scenario '{}' not found", id)))?
+    };
+
+    let tables = Arc::clone(&state.tables);
+    // Dropping this handler (client disconnect) cancels every pending run
+    let cancel = CancelToken::new();
+    let _guard = cancel.drop_guard();
+    let control = RunControl::new().with_cancel(cancel);
+    let result = tokio::task::spawn_blocking(move || {
+        elasticities_with(&initial_conditions_1900(), &params, &spec, tables, &control)
+    })
+    .await
+    .map_err(|e| ApiError::Internal(anyhow::anyhow!("Task panicked: {}", e)))?;
+
+    match result {
+        Ok(table) => Ok(Json(table)),
+        Err(e @ SensitivityError::InvalidSpec(_)) => Err(ApiError::BadRequest(e.to_string())),
+        Err(e @ SensitivityError::BaseRun(_)) => Err(ApiError::SimulationFailed(e.to_string())),
+    }
+}
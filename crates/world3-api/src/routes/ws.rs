@@ -12,10 +12,9 @@ use tokio::{
     task::JoinHandle,
     time::{sleep, Duration},
 };
-use world3_core::solver::traits::OdeSolver;
-
+use world3_core::{initial_conditions_1900, CancelToken, RunControl, Simulation, SolverError};
 use crate::{
-    models::{initial_conditions_1900, WsClientMsg, WsServerMsg},
+    models::{StateDetail, WsClientMsg, WsServerMsg},
     state::AppState,
 };
 
@@ -26,10 +25,25 @@ pub async fn ws_handler(
     ws.on_upgrade(move |socket| handle_socket(socket, state))
 }
 
+/// A running (or debounced) simulation. Aborting the tokio task does not
+/// stop a blocking thread, so the simulation also checks `cancel` before
+/// every integration step.
+struct SimTask {
+    handle: JoinHandle<()>,
+    cancel: CancelToken,
+}
+
+impl SimTask {
+    fn cancel(self) {
+        self.cancel.cancel();
+        self.handle.abort();
+    }
+}
+
 async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
     let (tx, mut rx) = mpsc::channel::<WsServerMsg>(256);
-    let mut sim_task: Option<JoinHandle<()>> = None;
-    let mut debounce_task: Option<JoinHandle<()>> = None;
+    let mut sim_task: Option<SimTask> = None;
+    let mut debounce_task: Option<SimTask> = None;
 
     loop {
         tokio::select! {
@@ -80,10 +94,10 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
 
     // Clean up
     if let Some(t) = sim_task {
-        t.abort();
+        t.cancel();
     }
     if let Some(t) = debounce_task {
-        t.abort();
+        t.cancel();
     }
 }
 
@@ -91,21 +105,25 @@ async fn handle_client_msg(
     msg: WsClientMsg,
     state: &Arc<AppState>,
     tx: &mpsc::Sender<WsServerMsg>,
-    sim_task: &mut Option<JoinHandle<()>>,
-    debounce_task: &mut Option<JoinHandle<()>>,
+    sim_task: &mut Option<SimTask>,
+    debounce_task: &mut Option<SimTask>,
 ) {
     match msg {
-        WsClientMsg::StartSimulation { scenario_id, params } => {
+        WsClientMsg::StartSimulation { scenario_id, params, detail } => {
             // Abort existing tasks
             if let Some(t) = sim_task.take() {
-                t.abort();
+                t.cancel();
             }
             if let Some(t) = debounce_task.take() {
-                t.abort();
+                t.cancel();
             }
 
             // Resolve params: use provided override or load from store
             let resolved_params = if let Some(p) = params {
+                if let Err(message) = p.validate_schedules() {
+                    let _ = tx.send(WsServerMsg::SimError { message }).await;
+                    return;
+                }
                 p
             } else {
                 let store = state.scenarios.read().await;
@@ -126,17 +144,24 @@ async fn handle_client_msg(
                 Arc::clone(state),
                 scenario_id,
                 resolved_params,
+                detail,
                 tx.clone(),
+                CancelToken::new(),
             ));
         }
 
-        WsClientMsg::UpdateParams { scenario_id, params } => {
+        WsClientMsg::UpdateParams { scenario_id, params, detail } => {
             // Abort existing
             if let Some(t) = sim_task.take() {
-                t.abort();
+                t.cancel();
             }
             if let Some(t) = debounce_task.take() {
-                t.abort();
+                t.cancel();
+            }
+
+            if let Err(message) = params.validate_schedules() {
+                let _ = tx.send(WsServerMsg::SimError { message }).await;
+                return;
             }
 
             // Store updated params
@@ -160,64 +185,77 @@ async fn handle_client_msg(
             let sid = scenario_id.clone();
             let p = params;
 
-            let debounce = tokio::spawn(async move {
+            let cancel = CancelToken::new();
+            let cancel2 = cancel.clone();
+
+            let handle = tokio::spawn(async move {
                 sleep(Duration::from_millis(50)).await;
-                drop(spawn_sim_task(state2, sid, p, tx2));
+                let _ = spawn_sim_task(state2, sid, p, detail, tx2, cancel2).handle.await;
             });
-            *debounce_task = Some(debounce);
+            *debounce_task = Some(SimTask { handle, cancel });
         }
 
         WsClientMsg::StopSimulation => {
             if let Some(t) = sim_task.take() {
-                t.abort();
+                t.cancel();
             }
             if let Some(t) = debounce_task.take() {
-                t.abort();
+                t.cancel();
             }
         }
     }
 }
 
+/// Step the simulation on the blocking pool, streaming each state to the
+/// client as soon as it is computed, with the requested `detail` blocks.
 fn spawn_sim_task(
     state: Arc<AppState>,
     scenario_id: String,
     params: world3_core::ScenarioParams,
+    detail: StateDetail,
     tx: mpsc::Sender<WsServerMsg>,
-) -> JoinHandle<()> {
-    tokio::spawn(async move {
-        let solver = Arc::clone(&state.solver);
+    cancel: CancelToken,
+) -> SimTask {
+    let token = cancel.clone();
+    let control = RunControl::new().with_cancel(cancel.clone());
+    let handle = tokio::task::spawn_blocking(move || {
         let initial = initial_conditions_1900();
+        let mut sim = match Simulation::new(initial, params, Arc::clone(&state.tables)) {
+            Ok(sim) => sim.with_control(control),
+            Err(e) => {
+                let _ = tx.blocking_send(WsServerMsg::SimError { message: e.to_string() });
+                return;
+            }
+        };
 
-        let result = tokio::task::spawn_blocking(move || solver.solve(initial, &params)).await;
-
-        match result {
-            Ok(Ok(states)) => {
-                let n = states.len();
-                for s in states {
-                    let year = s.time;
-                    if tx
-                        .send(WsServerMsg::SimStep { year, state: s })
-                        .await
-                        .is_err()
-                    {
-                        return;
-                    }
-                }
-                let _ = tx
-                    .send(WsServerMsg::SimComplete {
-                        scenario_id,
-                        total_steps: n,
-                    })
-                    .await;
+        let mut n = 0;
+        for mut s in sim.by_ref() {
+            if token.is_cancelled() {
+                return;
             }
-            Ok(Err(e)) => {
-                let _ = tx
-                    .send(WsServerMsg::SimError {
-                        message: e.to_string(),
-                    })
-                    .await;
+            detail.apply(&mut s);
+            let year = s.time;
+            if tx
+                .blocking_send(WsServerMsg::SimStep { year, state: Box::new(s) })
+                .is_err()
+            {
+                return;
             }
-            Err(_) => {} // Task was aborted — normal
+            n += 1;
         }
-    })
+
+        let msg = match sim.take_error() {
+            // Superseded by a newer run or stopped by the client
+            Some(SolverError::Cancelled { .. }) => return,
+            Some(e) => WsServerMsg::SimError {
+                message: e.to_string(),
+            },
+            None => WsServerMsg::SimComplete {
+                scenario_id,
+                total_steps: n,
+            },
+        };
+        let _ = tx.blocking_send(msg);
+    });
+    SimTask { handle, cancel }
 }
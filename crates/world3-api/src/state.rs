@@ -1,23 +1,20 @@
 use std::{collections::HashMap, sync::Arc};
 
 use tokio::sync::{broadcast, RwLock};
-use world3_core::{
-    lookup::tables::WorldLookupTables, Rk4Solver, ScenarioParams,
-};
+use world3_core::{lookup::tables::WorldLookupTables, ScenarioParams};
 
 use crate::models::Scenario;
 
 pub struct AppState {
-    pub solver: Arc<Rk4Solver>,
+    pub tables: Arc<WorldLookupTables>,
     pub scenarios: Arc<RwLock<HashMap<String, Scenario>>>,
     /// Phase 4 placeholder — ingestion broadcast
     pub _ingestion_tx: broadcast::Sender<()>,
 }
 
 pub fn init_app_state() -> AppState {
-    // 1. Load lookup tables and build solver
+    // 1. Load lookup tables (shared by every run)
     let tables = Arc::new(WorldLookupTables::load());
-    let solver = Arc::new(Rk4Solver::new(tables));
 
     // 2. Pre-populate with 3 preset scenarios
     let presets: Vec<ScenarioParams> = vec![
@@ -43,7 +40,7 @@ pub fn init_app_state() -> AppState {
     let (tx, _) = broadcast::channel(1);
 
     AppState {
-        solver,
+        tables,
         scenarios: Arc::new(RwLock::new(map)),
         _ingestion_tx: tx,
     }
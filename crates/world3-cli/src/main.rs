@@ -3,16 +3,10 @@ use clap::{Parser, Subcommand};
 use plotters::prelude::*;
 use std::path::{Path, PathBuf};
 use world3_core::{
-    model::{
-        params::ScenarioParams,
-        state::{
-            AgricultureState, CapitalState, PollutionState, PopulationState, ResourceState,
-            WorldState,
-        },
-    },
-    output::SimulationOutput,
-    solver::traits::OdeSolver,
-    Rk4Solver,
+    initial_conditions_1900, model::params::ScenarioParams, output::SimulationOutput,
+    simulation::StepMethod, Attribution, CalibrationReport, CalibrationSpec, Checkpoint,
+    ElasticitySpec, ElasticityTable, EquilibriumOptions, EventSpec, Linearization,
+    RegionalOutput, RegionalSpec, Schedule, Simulation, SolverKind, WorldState,
 };
 
 #[derive(Parser)]
@@ -22,6 +16,8 @@ struct Cli {
     command: Commands,
 }
 
+// Parsed once at startup, so the variant size difference doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     /// Run a simulation and output results
@@ -42,13 +38,157 @@ enum Commands {
         #[arg(long, default_value_t = 2100.0)]
         end: f64,
 
-        /// Time step (years)
+        /// Integration time step (years)
         #[arg(long, default_value_t = 1.0)]
         dt: f64,
 
+        /// Spacing of reported states (years); defaults to --dt
+        #[arg(long)]
+        output_interval: Option<f64>,
+
+        /// Integration method: rk4, rk45, euler (DYNAMO-compatible, DT=0.5)
+        #[arg(long, default_value = "rk4")]
+        solver: SolverKind,
+
         /// Output chart image (PNG) file path
         #[arg(long)]
         chart: Option<PathBuf>,
+
+        /// Resume from a checkpoint JSON file instead of 1900 initial conditions
+        #[arg(long)]
+        resume: Option<PathBuf>,
+
+        /// Write a checkpoint JSON file of the state at --checkpoint-year
+        #[arg(long, requires = "checkpoint_year")]
+        save_checkpoint: Option<PathBuf>,
+
+        /// Year to checkpoint (the run is stopped exactly at it)
+        #[arg(long, requires = "save_checkpoint")]
+        checkpoint_year: Option<f64>,
+
+        /// On solver failure, keep the states computed before it instead of
+        /// writing nothing
+        #[arg(long)]
+        partial: bool,
+
+        /// Locate an event during the run (repeatable): peak:VAR, trough:VAR,
+        /// above:VAR:THRESHOLD, below:VAR:THRESHOLD. THRESHOLD is a number,
+        /// a variable or params.FIELD
+        #[arg(long = "event")]
+        events: Vec<EventSpec>,
+
+        /// Like --event, but stop the run when the event fires
+        #[arg(long = "stop-at")]
+        stop_at: Vec<EventSpec>,
+
+        /// Vary a lever over time (repeatable): FIELD=step:YEAR:BEFORE:AFTER,
+        /// FIELD=ramp:START:END:FROM:TO or FIELD=piecewise:YEAR=VALUE,...
+        #[arg(long = "schedule", value_parser = parse_schedule)]
+        schedules: Vec<(String, Schedule)>,
+    },
+
+    /// Finite-difference elasticities of every output series to the
+    /// tunable parameters
+    Elasticities {
+        /// Preset scenario: bau, technology, stabilized
+        #[arg(long, default_value = "bau")]
+        preset: String,
+
+        /// Output CSV file path, one row per year, variable and parameter
+        /// (prints the --year table to stdout if omitted)
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Parameter to perturb (repeatable); all tunable parameters if omitted
+        #[arg(long = "parameter")]
+        parameters: Vec<String>,
+
+        /// Series to differentiate (repeatable); all series if omitted
+        #[arg(long = "variable")]
+        variables: Vec<String>,
+
+        /// Relative parameter perturbation
+        #[arg(long, default_value_t = 0.01)]
+        step: f64,
+
+        /// Year of the table printed to stdout
+        #[arg(long, default_value_t = 2100.0)]
+        year: f64,
+    },
+
+    /// Fit parameters of a preset to observed series
+    Calibrate {
+        /// Calibration spec JSON file: observed series, free parameters with
+        /// bounds, loss
+        #[arg(long)]
+        spec: PathBuf,
+
+        /// Preset scenario to start from: bau, technology, stabilized
+        #[arg(long, default_value = "bau")]
+        preset: String,
+
+        /// Write the fit report (including the fitted params) as JSON
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Eigenvalues along a run and the nearest steady state
+    Stability {
+        /// Preset scenario: bau, technology, stabilized
+        #[arg(long, default_value = "bau")]
+        preset: String,
+
+        /// Year of the trajectory state to linearize and start the
+        /// equilibrium search from; time is frozen at this year
+        #[arg(long, default_value_t = 2100.0)]
+        year: f64,
+
+        /// Hold a stock (sector.variable) fixed in the equilibrium search
+        /// (repeatable), e.g. resources.nonrenewable_resources
+        #[arg(long = "freeze")]
+        frozen: Vec<String>,
+
+        /// Write the linearization and equilibrium as JSON
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Attribute changes in life expectancy, fertility and land yield to
+    /// their multipliers
+    Attribution {
+        /// Preset scenario: bau, technology, stabilized
+        #[arg(long, default_value = "bau")]
+        preset: String,
+
+        /// Start of the interval
+        #[arg(long, default_value_t = 1900.0)]
+        from: f64,
+
+        /// End of the interval
+        #[arg(long, default_value_t = 2100.0)]
+        to: f64,
+
+        /// Write the attribution report as JSON
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Run several regions linked by trade and migration
+    Regions {
+        /// Regional spec JSON file: regions with their shares of the world
+        /// and parameter overrides, trade and migration; North/South if
+        /// omitted
+        #[arg(long)]
+        spec: Option<PathBuf>,
+
+        /// Preset scenario every region starts from: bau, technology,
+        /// stabilized
+        #[arg(long, default_value = "bau")]
+        preset: String,
+
+        /// Write every region's run as JSON
+        #[arg(long)]
+        output: Option<PathBuf>,
     },
 
     /// Validate BAU run against Meadows 1972 reference checkpoints
@@ -62,21 +202,92 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Simulate { preset, output, start, end, dt, chart } => {
+        Commands::Simulate {
+            preset,
+            output,
+            start,
+            end,
+            dt,
+            output_interval,
+            solver,
+            chart,
+            resume,
+            save_checkpoint,
+            checkpoint_year,
+            partial,
+            events,
+            stop_at,
+            schedules,
+        } => {
             let mut params = preset_params(&preset)?;
             params.start_year = start;
             params.end_year = end;
             params.time_step = dt;
+            params.output_interval = output_interval;
+            params.solver = solver;
+            params.schedules.extend(schedules);
+            params.validate_schedules().map_err(anyhow::Error::msg)?;
 
-            eprintln!("Running '{}' ({} → {}, dt={}yr)…", params.meta.name, start, end, dt);
+            eprintln!(
+                "Running '{}' ({} → {}, dt={}yr, solver={:?})…",
+                params.meta.name, start, end, dt, solver
+            );
 
-            let initial = initial_conditions_1900();
             let tables = std::sync::Arc::new(
                 world3_core::lookup::tables::WorldLookupTables::load(),
             );
-            let solver = Rk4Solver::new(tables);
-            let states = solver.solve(initial, &params)?;
-            let sim = SimulationOutput::new(states, params);
+            let resume = match resume {
+                Some(path) => {
+                    let checkpoint = read_checkpoint(&path)?;
+                    eprintln!("Resuming from {} ({:.1})", path.display(), checkpoint.year());
+                    Some(checkpoint)
+                }
+                None => None,
+            };
+            let start_sim = |params: ScenarioParams| match &resume {
+                Some(checkpoint) => Simulation::from_checkpoint(
+                    checkpoint.clone(),
+                    params,
+                    std::sync::Arc::clone(&tables),
+                ),
+                None => {
+                    Simulation::new(initial_conditions_1900(), params, std::sync::Arc::clone(&tables))
+                }
+            };
+            let sim = start_sim(params.clone())?;
+            // Output starts at the checkpoint year if it is after --start
+            params.start_year = sim.params().start_year;
+            let events = events.into_iter().chain(stop_at.into_iter().map(EventSpec::terminal));
+            let trajectory = sim.with_events(events.collect())?.collect_partial();
+            let trajectory = match trajectory.error {
+                Some(error) if !partial => return Err(error.into()),
+                _ => trajectory,
+            };
+            let sim = SimulationOutput::from_partial(trajectory, params);
+            if let Some(error) = &sim.error {
+                eprintln!("Solver failed, keeping partial trajectory: {}", error);
+            }
+
+            if let (Some(path), Some(year)) = (save_checkpoint, checkpoint_year) {
+                // Stop a second run exactly at `year`, so the checkpoint is an
+                // integration point with the solver's own state, not an
+                // (possibly interpolated) output state
+                let mut run = start_sim(sim.params.clone())?;
+                if !(run.time()..=run.params().end_year).contains(&year) {
+                    anyhow::bail!(
+                        "--checkpoint-year {} outside the run ({:.1}–{:.1})",
+                        year,
+                        run.time(),
+                        run.params().end_year
+                    );
+                }
+                run.run_until(year)
+                    .with_context(|| format!("Run failed before the checkpoint year {}", year))?;
+                let checkpoint = run.checkpoint();
+                std::fs::write(&path, serde_json::to_string_pretty(&checkpoint)?)
+                    .with_context(|| format!("Cannot write to {}", path.display()))?;
+                eprintln!("Wrote checkpoint {}", path.display());
+            }
 
             eprintln!(
                 "Completed {} steps. Final year: {:.0}",
@@ -91,12 +302,200 @@ fn main() -> Result<()> {
                 print_summary(&sim);
             }
 
+            for event in &sim.events {
+                eprintln!(
+                    "Event {}: year {:.2}, value {:.4e}{}",
+                    event.name,
+                    event.year,
+                    event.value,
+                    if event.terminal { " (run stopped)" } else { "" }
+                );
+            }
+
             if let Some(chart_path) = chart {
                 render_chart(&sim, &chart_path)?;
                 eprintln!("Wrote chart {}", chart_path.display());
             }
         }
 
+        Commands::Elasticities {
+            preset,
+            output,
+            parameters,
+            variables,
+            step,
+            year,
+        } => {
+            let params = preset_params(&preset)?;
+            let summary_only = variables.is_empty();
+            let spec = ElasticitySpec {
+                parameters,
+                variables,
+                relative_step: step,
+            };
+            eprintln!("Computing elasticities for '{}'…", params.meta.name);
+            let tables = std::sync::Arc::new(
+                world3_core::lookup::tables::WorldLookupTables::load(),
+            );
+            let table =
+                world3_core::elasticities(&initial_conditions_1900(), &params, &spec, tables)?;
+            eprintln!("Completed {} runs ({} failed)", table.runs, table.failed);
+
+            if let Some(path) = output {
+                write_elasticities_csv(&table, &path)?;
+                eprintln!("Wrote {}", path.display());
+            } else {
+                print_elasticities(&table, year, summary_only);
+            }
+        }
+
+        Commands::Calibrate {
+            spec,
+            preset,
+            output,
+        } => {
+            let params = preset_params(&preset)?;
+            let json = std::fs::read_to_string(&spec)
+                .with_context(|| format!("Cannot read {}", spec.display()))?;
+            let spec: CalibrationSpec = serde_json::from_str(&json)
+                .with_context(|| format!("Invalid calibration spec {}", spec.display()))?;
+            eprintln!(
+                "Calibrating {} parameter(s) of '{}' against {} series…",
+                spec.parameters.len(),
+                params.meta.name,
+                spec.observed.len()
+            );
+            let tables = std::sync::Arc::new(
+                world3_core::lookup::tables::WorldLookupTables::load(),
+            );
+            let report =
+                world3_core::calibrate(&initial_conditions_1900(), &params, &spec, tables)?;
+
+            print_calibration(&report);
+            if let Some(path) = output {
+                std::fs::write(&path, serde_json::to_string_pretty(&report)?)
+                    .with_context(|| format!("Cannot write to {}", path.display()))?;
+                eprintln!("Wrote {}", path.display());
+            }
+        }
+
+        Commands::Stability {
+            preset,
+            year,
+            frozen,
+            output,
+        } => {
+            let mut params = preset_params(&preset)?;
+            params.end_year = params.end_year.max(year);
+            let tables = std::sync::Arc::new(
+                world3_core::lookup::tables::WorldLookupTables::load(),
+            );
+            let sim = Simulation::new(
+                initial_conditions_1900(),
+                params.clone(),
+                std::sync::Arc::clone(&tables),
+            )?
+            .collect_states()?;
+            let sim = SimulationOutput::new(sim, params);
+            let state = sim
+                .state_at_year(year)
+                .with_context(|| format!("No output state at year {}", year))?;
+
+            let along = world3_core::linearize(state, &sim.params, &tables)?;
+            println!("Trajectory state at {:.0}:", state.time);
+            print_linearization(&along);
+
+            let options = EquilibriumOptions {
+                frozen,
+                ..EquilibriumOptions::default()
+            };
+            let equilibrium =
+                world3_core::find_equilibrium(state, &sim.params, &tables, &options)?;
+            println!();
+            println!(
+                "Equilibrium search ({}): residual {:.3e}/yr after {} steps",
+                if equilibrium.converged { "converged" } else { "did not converge" },
+                equilibrium.residual,
+                equilibrium.iterations
+            );
+            println!("{:<40}  {:>12}  {:>12}", "Stock", "Trajectory", "Equilibrium");
+            println!("{}", "-".repeat(68));
+            let (from, to) = (state.to_vec(), equilibrium.state.to_vec());
+            for (i, (sector, variable)) in WorldState::STOCK_NAMES.iter().enumerate() {
+                let name = format!("{}.{}", sector, variable);
+                println!("{:<40}  {:>12.4e}  {:>12.4e}", name, from[i], to[i]);
+            }
+            if equilibrium.converged {
+                println!();
+                if equilibrium.state.population.population < 1.0 {
+                    println!("The frozen system settles into collapse (population ≈ 0).");
+                }
+                println!("At the equilibrium:");
+                print_linearization(&equilibrium.linearization);
+            }
+
+            if let Some(path) = output {
+                let json =
+                    serde_json::json!({ "linearization": along, "equilibrium": equilibrium });
+                std::fs::write(&path, serde_json::to_string_pretty(&json)?)
+                    .with_context(|| format!("Cannot write to {}", path.display()))?;
+                eprintln!("Wrote {}", path.display());
+            }
+        }
+
+        Commands::Attribution {
+            preset,
+            from,
+            to,
+            output,
+        } => {
+            let mut params = preset_params(&preset)?;
+            params.end_year = params.end_year.max(to);
+            let tables = std::sync::Arc::new(
+                world3_core::lookup::tables::WorldLookupTables::load(),
+            );
+            let states = Simulation::new(initial_conditions_1900(), params.clone(), tables)?
+                .collect_states()?;
+            let sim = SimulationOutput::new(states, params);
+            let attribution = world3_core::attribute(&sim, from, to)?;
+            print_attribution(&attribution);
+
+            if let Some(path) = output {
+                std::fs::write(&path, serde_json::to_string_pretty(&attribution)?)
+                    .with_context(|| format!("Cannot write to {}", path.display()))?;
+                eprintln!("Wrote {}", path.display());
+            }
+        }
+
+        Commands::Regions {
+            spec,
+            preset,
+            output,
+        } => {
+            let params = preset_params(&preset)?;
+            let spec = match spec {
+                Some(path) => {
+                    let json = std::fs::read_to_string(&path)
+                        .with_context(|| format!("Cannot read {}", path.display()))?;
+                    serde_json::from_str(&json)
+                        .with_context(|| format!("Invalid regional spec {}", path.display()))?
+                }
+                None => RegionalSpec::north_south(),
+            };
+            let tables = std::sync::Arc::new(
+                world3_core::lookup::tables::WorldLookupTables::load(),
+            );
+            let regional =
+                world3_core::run_regions(&initial_conditions_1900(), &params, &spec, tables)?;
+
+            print_regions(&regional);
+            if let Some(path) = output {
+                std::fs::write(&path, serde_json::to_string_pretty(&regional)?)
+                    .with_context(|| format!("Cannot write to {}", path.display()))?;
+                eprintln!("Wrote {}", path.display());
+            }
+        }
+
         Commands::Validate => {
             validate()?;
         }
@@ -121,47 +520,18 @@ fn preset_params(name: &str) -> Result<ScenarioParams> {
     }
 }
 
-/// World 3 initial conditions for year 1900.
-/// Values calibrated to broadly match Meadows 1972 standard run starting point.
-fn initial_conditions_1900() -> WorldState {
-    WorldState {
-        time: 1900.0,
-        population: PopulationState {
-            population: 1.6e9,
-            // 1900 age structure: young population with small elderly cohort
-            cohort_0_14: 0.60e9,   // 37.5% — high fertility, high child mortality era
-            cohort_15_44: 0.65e9,  // 40.6%
-            cohort_45_64: 0.27e9,  // 16.9%
-            cohort_65_plus: 0.08e9, // 5.0% — small elderly cohort in 1900
-            ..Default::default()
-        },
-        capital: CapitalState {
-            industrial_capital: 0.2e12,  // 1975 USD
-            // Service capital pre-set to its ~1900 equilibrium.
-            // At industrial_output ≈ $133B and frac_to_services ≈ 0.12:
-            //   service_capital_eq = 133e9 × 0.12 / 0.05 ≈ 0.32e12
-            //   → sopc ≈ $200/yr → lem_health ≈ 0.76 → LE ≈ 32 yr ✓
-            service_capital: 0.32e12,
-            ..Default::default()
-        },
-        agriculture: AgricultureState {
-            arable_land: 0.9e9,            // hectares
-            potentially_arable_land: 2.3e9,
-            food_per_capita: 400.0,        // initial estimate; overwritten by agriculture sector
-            ..Default::default()
-        },
-        resources: ResourceState {
-            nonrenewable_resources: 1.0,   // 100% remaining in 1900
-            fraction_remaining: 1.0,
-        },
-        pollution: PollutionState {
-            // pollution_index = persistent_pollution (reference_stock = 1.0)
-            // Small in 1900; rises to ~1 by 1970, ~10–30 peak around 2030–2050 in BAU.
-            persistent_pollution: 0.05,
-            pollution_index: 0.05,
-            ..Default::default()
-        },
-    }
+/// Parse `FIELD=SCHEDULE` for `--schedule`.
+fn parse_schedule(s: &str) -> Result<(String, Schedule), String> {
+    let (field, schedule) = s
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid schedule '{}'. Use: FIELD=SCHEDULE", s))?;
+    Ok((field.to_string(), schedule.parse()?))
+}
+
+fn read_checkpoint(path: &Path) -> Result<Checkpoint> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("Cannot read {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("Invalid checkpoint {}", path.display()))
 }
 
 fn print_summary(sim: &SimulationOutput) {
@@ -171,7 +541,13 @@ fn print_summary(sim: &SimulationOutput) {
     );
     println!("{}", "-".repeat(64));
 
-    for state in sim.states.iter().step_by(10) {
+    // Every 10 years, whatever the output interval
+    let mut next_year = f64::NEG_INFINITY;
+    for state in &sim.states {
+        if state.time < next_year - 1e-6 {
+            continue;
+        }
+        next_year = state.time + 10.0;
         println!(
             "{:>6.0}  {:>12.2e}  {:>10.1}  {:>10.1}  {:>8.1}  {:>8.2}",
             state.time,
@@ -184,6 +560,188 @@ fn print_summary(sim: &SimulationOutput) {
     }
 }
 
+/// Every 25 years, one row per region.
+fn print_regions(regional: &RegionalOutput) {
+    println!(
+        "{:>6}  {:<12}  {:>12}  {:>10}  {:>10}  {:>8}  {:>12}  {:>12}",
+        "Year", "Region", "Population", "Food/cap", "Ind.Out/cap", "NNR%", "Food imports", "Migration"
+    );
+    println!("{}", "-".repeat(96));
+    let mut next_year = f64::NEG_INFINITY;
+    for (i, &year) in regional.timeline.iter().enumerate() {
+        if year < next_year - 1e-6 {
+            continue;
+        }
+        next_year = year + 25.0;
+        for region in &regional.regions {
+            let state = &region.states[i];
+            println!(
+                "{:>6.0}  {:<12}  {:>12.2e}  {:>10.1}  {:>10.1}  {:>8.1}  {:>12.2e}  {:>12.2e}",
+                year,
+                region.scenario_name,
+                state.population.population,
+                state.agriculture.food_per_capita,
+                state.capital.industrial_output_per_capita,
+                state.resources.nonrenewable_resources * 100.0,
+                state.trade.food_imports,
+                state.trade.net_migration,
+            );
+        }
+    }
+}
+
+/// Elasticities at `year`, one row per parameter: the summary variables, or
+/// every variable in the table.
+fn print_elasticities(table: &ElasticityTable, year: f64, summary_only: bool) {
+    let summary = [
+        "population.population",
+        "agriculture.food_per_capita",
+        "capital.industrial_output_per_capita",
+        "resources.fraction_remaining",
+        "pollution.pollution_index",
+    ];
+    let columns: Vec<&String> = table
+        .variables
+        .iter()
+        .filter(|v| !summary_only || summary.contains(&v.as_str()))
+        .collect();
+    println!("Elasticities at {:.0}", year);
+    print!("{:<30}", "Parameter");
+    for v in &columns {
+        let name: String = v.rsplit('.').next().unwrap_or(v).chars().take(12).collect();
+        print!("  {:>12}", name);
+    }
+    println!();
+    println!("{}", "-".repeat(30 + 14 * columns.len()));
+    for p in &table.parameters {
+        print!("{:<30}", p);
+        for v in &columns {
+            let e = table.elasticity(year, v, p).unwrap_or(f64::NAN);
+            print!("  {:>12.4}", e);
+        }
+        println!();
+    }
+}
+
+fn print_calibration(report: &CalibrationReport) {
+    println!(
+        "Loss {:.6e} → {:.6e} after {} runs ({})",
+        report.initial_loss,
+        report.loss,
+        report.evaluations,
+        if report.converged { "converged" } else { "evaluation limit reached" }
+    );
+    println!();
+    println!("{:<30}  {:>12}  {:>12}", "Parameter", "Initial", "Fitted");
+    println!("{}", "-".repeat(58));
+    for (field, value) in &report.parameters {
+        println!("{:<30}  {:>12.6}  {:>12.6}", field, report.initial[field], value);
+    }
+    println!();
+    println!("{:<40}  {:>12}  {:>12}  {:>8}", "Series", "Loss", "RMSE", "MAPE%");
+    println!("{}", "-".repeat(78));
+    for fit in &report.series {
+        println!(
+            "{:<40}  {:>12.4e}  {:>12.4e}  {:>8.2}",
+            fit.variable,
+            fit.loss,
+            fit.rmse,
+            fit.mape * 100.0
+        );
+    }
+}
+
+fn print_linearization(linearization: &Linearization) {
+    println!(
+        "{:>12}  {:>12}  {:>12}  {:>15}",
+        "Re [1/yr]", "Im [1/yr]", "Period [yr]", "Time const [yr]"
+    );
+    println!("{}", "-".repeat(57));
+    for e in &linearization.eigenvalues {
+        let period = e.period().map_or("-".to_string(), |p| format!("{:.1}", p));
+        let time_constant = if e.time_constant() < 1e6 {
+            format!("{:.1}", e.time_constant())
+        } else {
+            "-".to_string()
+        };
+        println!("{:>12.5}  {:>12.5}  {:>12}  {:>15}", e.re, e.im, period, time_constant);
+    }
+    let abscissa = linearization.spectral_abscissa();
+    println!(
+        "{}: slowest mode {} with rate {:.3e}/yr; {} oscillatory mode(s)",
+        if linearization.is_stable() { "Locally stable" } else { "Not locally stable" },
+        if abscissa < 0.0 { "decays" } else { "grows" },
+        abscissa,
+        linearization.oscillatory_modes().count()
+    );
+}
+
+fn print_attribution(attribution: &Attribution) {
+    println!("Attribution {:.0}–{:.0}", attribution.from, attribution.to);
+    for d in &attribution.decompositions {
+        let change = d.change();
+        println!();
+        println!("{}: {:.4} → {:.4} ({:+.4})", d.variable, d.start, d.end, change);
+        println!(
+            "{:<28}  {:>10}  {:>10}  {:>13}  {:>7}",
+            "Factor", "Start", "End", "Contribution", "Share%"
+        );
+        println!("{}", "-".repeat(76));
+        let share = |c: f64| {
+            if change.abs() <= 1e-9 * d.start.abs().max(1.0) {
+                "-".to_string()
+            } else if c == 0.0 {
+                "0.0".to_string()
+            } else {
+                format!("{:.1}", 100.0 * c / change)
+            }
+        };
+        for c in &d.contributions {
+            println!(
+                "{:<28}  {:>10.4}  {:>10.4}  {:>+13.4}  {:>7}",
+                c.factor,
+                c.start,
+                c.end,
+                c.contribution,
+                share(c.contribution)
+            );
+        }
+        if d.residual.abs() > 1e-9 * d.start.abs().max(1.0) {
+            println!(
+                "{:<28}  {:>10}  {:>10}  {:>+13.4}  {:>7}",
+                "(clamping)",
+                "",
+                "",
+                d.residual,
+                share(d.residual)
+            );
+        }
+    }
+}
+
+fn write_elasticities_csv(table: &ElasticityTable, path: &Path) -> Result<()> {
+    let mut wtr = csv::Writer::from_path(path)
+        .with_context(|| format!("Cannot write to {}", path.display()))?;
+
+    wtr.write_record(["year", "variable", "parameter", "elasticity", "derivative"])?;
+    for (t, year) in table.timeline.iter().enumerate() {
+        for (v, variable) in table.variables.iter().enumerate() {
+            for (p, parameter) in table.parameters.iter().enumerate() {
+                wtr.write_record(&[
+                    format!("{:.1}", year),
+                    variable.clone(),
+                    parameter.clone(),
+                    format!("{:.6}", table.elasticities[t][v][p]),
+                    format!("{:.6e}", table.derivatives[t][v][p]),
+                ])?;
+            }
+        }
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
 fn write_csv(sim: &SimulationOutput, path: &PathBuf) -> Result<()> {
     let mut wtr = csv::Writer::from_path(path)
         .with_context(|| format!("Cannot write to {}", path.display()))?;
@@ -204,13 +762,30 @@ fn write_csv(sim: &SimulationOutput, path: &PathBuf) -> Result<()> {
         "industrial_output",
         "industrial_output_per_capita",
         "service_output_per_capita",
+        "health_services_per_capita",
+        "education_services_per_capita",
         "arable_land",
         "food",
         "food_per_capita",
         "land_yield",
+        "land_fertility",
         "nnr_fraction",
         "persistent_pollution",
         "pollution_index",
+        "jobs",
+        "labor_force",
+        "unemployment_rate",
+        "capacity_utilization",
+        "carbon_emissions",
+        "co2_concentration",
+        "temperature_anomaly",
+        "energy_availability",
+        "renewable_share",
+        "ecological_footprint",
+        "biodiversity_intactness",
+        "human_welfare_index",
+        "gini",
+        "starving_fraction",
     ])?;
 
     for s in &sim.states {
@@ -230,13 +805,30 @@ fn write_csv(sim: &SimulationOutput, path: &PathBuf) -> Result<()> {
             format!("{:.4e}", s.capital.industrial_output),
             format!("{:.2}", s.capital.industrial_output_per_capita),
             format!("{:.2}", s.capital.service_output_per_capita),
+            format!("{:.2}", s.capital.health_services_per_capita),
+            format!("{:.2}", s.capital.education_services_per_capita),
             format!("{:.4e}", s.agriculture.arable_land),
             format!("{:.4e}", s.agriculture.food),
             format!("{:.2}", s.agriculture.food_per_capita),
             format!("{:.2}", s.agriculture.land_yield),
+            format!("{:.2}", s.agriculture.land_fertility),
             format!("{:.4}", s.resources.fraction_remaining),
             format!("{:.4e}", s.pollution.persistent_pollution),
             format!("{:.4}", s.pollution.pollution_index),
+            format!("{:.4e}", s.labor.jobs),
+            format!("{:.4e}", s.labor.labor_force),
+            format!("{:.4}", s.labor.unemployment_rate),
+            format!("{:.4}", s.labor.capacity_utilization),
+            format!("{:.4}", s.climate.carbon_emissions),
+            format!("{:.2}", s.climate.co2_concentration),
+            format!("{:.4}", s.climate.temperature_anomaly),
+            format!("{:.4}", s.energy.energy_availability),
+            format!("{:.4}", s.energy.renewable_share),
+            format!("{:.4}", s.biodiversity.ecological_footprint),
+            format!("{:.4}", s.biodiversity.intactness),
+            format!("{:.4}", s.welfare.human_welfare_index),
+            format!("{:.4}", s.inequality.gini),
+            format!("{:.4}", s.inequality.starving_fraction),
         ])?;
     }
 
@@ -339,9 +931,14 @@ fn validate() -> Result<()> {
     let params = ScenarioParams::bau();
     let initial = initial_conditions_1900();
     let tables = std::sync::Arc::new(world3_core::lookup::tables::WorldLookupTables::load());
-    let solver = Rk4Solver::new(tables);
-    let states = solver.solve(initial, &params)?;
-    let sim = SimulationOutput::new(states, params);
+    let peak: EventSpec = "peak:population.population".parse().map_err(anyhow::Error::msg)?;
+    let trajectory = Simulation::with_method(initial, params.clone(), tables, StepMethod::Rk4)?
+        .with_events(vec![peak])?
+        .collect_partial();
+    let sim = SimulationOutput::from_partial(trajectory, params);
+    if let Some(error) = &sim.error {
+        anyhow::bail!("BAU run failed: {}", error);
+    }
 
     let mut failures: Vec<String> = Vec::new();
 
@@ -365,17 +962,16 @@ fn validate() -> Result<()> {
         }
     }
 
-    // Check 3: Peak population somewhere in 2020–2060 and is 6B–12B
+    // Check 3: Peak population somewhere in 2000–2070 and is 6B–12B.
+    // The detector reports every local maximum (located by the solver,
+    // between output years); the global peak is the highest of them, or
+    // the highest output state if none was reported.
     let (peak_pop, peak_year) = sim
-        .states
+        .events
         .iter()
-        .fold((0.0_f64, 0.0_f64), |(mp, my), s| {
-            if s.population.population > mp {
-                (s.population.population, s.time)
-            } else {
-                (mp, my)
-            }
-        });
+        .map(|e| (e.value, e.year))
+        .chain(sim.states.iter().map(|s| (s.population.population, s.time)))
+        .fold((0.0, 0.0), |best, p| if p.0 > best.0 { p } else { best });
 
     if !(6.0e9..=12.0e9).contains(&peak_pop) || !(2000.0..=2070.0).contains(&peak_year) {
         failures.push(format!(
@@ -0,0 +1,268 @@
+//! Causal attribution of auxiliaries to their multipliers.
+//!
+//! Life expectancy, fertility and land yield are each a product of factors
+//! recorded in [`Multipliers`]:
+//!
+//! ```text
+//! life_expectancy = 20 × lem_food × lem_health × lem_crowding × lem_pollution
+//!                       × lem_climate
+//! fertility_rate  = desired_family_size × education_fertility × fp_multiplier
+//!                       × food_fertility
+//! land_yield      = 600 × land_fertility_ratio × yield_multiplier_capital
+//!                       × yield_multiplier_pollution × yield_multiplier_climate
+//!                       × agricultural_technology
+//! ```
+//!
+//! [`attribute`] splits the change of each between two years into one
+//! additive contribution per factor with the logarithmic mean Divisia index
+//! (LMDI): over a step from `a` to `b`, factor `m` contributes
+//! `L(Y_a, Y_b) × ln(m_b / m_a)`, where `L(x, y) = (y − x) / (ln y − ln x)`.
+//! The contributions of one step sum exactly to the change of the product;
+//! they are accumulated over every output step in the interval, so a factor
+//! that falls and recovers nets out. Whatever the product's change misses —
+//! the clamping of life expectancy and fertility — is reported as `residual`.
+//!
+//! [`Multipliers`]: crate::model::state::Multipliers
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::sectors::{agriculture::LAND_YIELD_1900, population::LIFE_EXPECTANCY_BASE};
+use crate::model::state::{Multipliers, WorldState};
+use crate::output::{extract_field, SimulationOutput};
+
+/// A variable defined as `base × Π factors`.
+struct Identity {
+    variable: &'static str,
+    base: f64,
+    factors: &'static [&'static str],
+}
+
+const IDENTITIES: [Identity; 3] = [
+    Identity {
+        variable: "population.life_expectancy",
+        base: LIFE_EXPECTANCY_BASE,
+        factors: &[
+            "lem_food",
+            "lem_health",
+            "lem_crowding",
+            "lem_pollution",
+            "lem_climate",
+        ],
+    },
+    Identity {
+        variable: "population.fertility_rate",
+        base: 1.0,
+        factors: &[
+            "desired_family_size",
+            "education_fertility",
+            "fp_multiplier",
+            "food_fertility",
+        ],
+    },
+    Identity {
+        variable: "agriculture.land_yield",
+        base: LAND_YIELD_1900,
+        factors: &[
+            "land_fertility_ratio",
+            "yield_multiplier_capital",
+            "yield_multiplier_pollution",
+            "yield_multiplier_climate",
+            "agricultural_technology",
+        ],
+    },
+];
+
+/// One factor's share of a change, in the units of the decomposed variable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FactorContribution {
+    /// `Multipliers` field name
+    pub factor: String,
+    /// Factor value at `from` and `to`
+    pub start: f64,
+    pub end: f64,
+    pub contribution: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Decomposition {
+    /// `extract_series` path of the decomposed variable
+    pub variable: String,
+    /// Recorded value at `from` and `to`
+    pub start: f64,
+    pub end: f64,
+    pub contributions: Vec<FactorContribution>,
+    /// Change not explained by the factors (clamping)
+    pub residual: f64,
+}
+
+impl Decomposition {
+    pub fn change(&self) -> f64 {
+        self.end - self.start
+    }
+
+    /// The factor with the largest contribution in magnitude.
+    pub fn dominant(&self) -> Option<&FactorContribution> {
+        self.contributions
+            .iter()
+            .filter(|c| c.contribution.is_finite())
+            .max_by(|a, b| a.contribution.abs().total_cmp(&b.contribution.abs()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attribution {
+    /// Output years the interval was snapped to
+    pub from: f64,
+    pub to: f64,
+    /// Life expectancy, fertility rate and land yield
+    pub decompositions: Vec<Decomposition>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AttributionError {
+    #[error("Year {0} is outside the run ({1}–{2})")]
+    YearOutOfRange(f64, f64, f64),
+    #[error("Start year {0} is after end year {1}")]
+    InvalidInterval(f64, f64),
+    #[error("Run has no recorded multipliers")]
+    NoMultipliers,
+}
+
+/// Decompose the changes of life expectancy, fertility and land yield
+/// between the output states nearest `from` and `to`.
+pub fn attribute(
+    output: &SimulationOutput,
+    from: f64,
+    to: f64,
+) -> Result<Attribution, AttributionError> {
+    let (first, last) = match (output.timeline.first(), output.timeline.last()) {
+        (Some(&first), Some(&last)) => (first, last),
+        _ => return Err(AttributionError::NoMultipliers),
+    };
+    for year in [from, to] {
+        if !(first..=last).contains(&year) {
+            return Err(AttributionError::YearOutOfRange(year, first, last));
+        }
+    }
+    if from > to {
+        return Err(AttributionError::InvalidInterval(from, to));
+    }
+    let (i0, i1) = (nearest(&output.timeline, from), nearest(&output.timeline, to));
+    let states = &output.states[i0..=i1];
+    if states.iter().any(|s| s.multipliers == Multipliers::default()) {
+        return Err(AttributionError::NoMultipliers);
+    }
+
+    let decompositions = IDENTITIES
+        .iter()
+        .map(|identity| decompose(identity, states))
+        .collect();
+    Ok(Attribution {
+        from: output.timeline[i0],
+        to: output.timeline[i1],
+        decompositions,
+    })
+}
+
+fn decompose(identity: &Identity, states: &[WorldState]) -> Decomposition {
+    let factor = |s: &WorldState, name: &str| s.multipliers.get(name).unwrap_or(f64::NAN);
+    let product = |s: &WorldState| {
+        identity.base * identity.factors.iter().map(|f| factor(s, f)).product::<f64>()
+    };
+    let value = |s: &WorldState| extract_field(s, identity.variable).unwrap_or(f64::NAN);
+
+    let mut contributions = vec![0.0; identity.factors.len()];
+    for pair in states.windows(2) {
+        let (a, b) = (&pair[0], &pair[1]);
+        let weight = log_mean(product(a), product(b));
+        for (total, name) in contributions.iter_mut().zip(identity.factors) {
+            let (ma, mb) = (factor(a, name), factor(b, name));
+            *total += if ma == mb {
+                0.0
+            } else {
+                weight * (mb / ma).ln()
+            };
+        }
+    }
+
+    let (start, end) = (&states[0], &states[states.len() - 1]);
+    let explained: f64 = contributions.iter().sum();
+    Decomposition {
+        variable: identity.variable.to_string(),
+        start: value(start),
+        end: value(end),
+        residual: value(end) - value(start) - explained,
+        contributions: identity
+            .factors
+            .iter()
+            .zip(contributions)
+            .map(|(name, contribution)| FactorContribution {
+                factor: name.to_string(),
+                start: factor(start, name),
+                end: factor(end, name),
+                contribution,
+            })
+            .collect(),
+    }
+}
+
+/// Logarithmic mean of two positive numbers; `NaN` otherwise.
+fn log_mean(x: f64, y: f64) -> f64 {
+    if !(x > 0.0 && y > 0.0) {
+        f64::NAN
+    } else if (x - y).abs() <= 1e-12 * x.max(y) {
+        x
+    } else {
+        (y - x) / (y.ln() - x.ln())
+    }
+}
+
+fn nearest(timeline: &[f64], year: f64) -> usize {
+    timeline
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| (*a - year).abs().total_cmp(&(*b - year).abs()))
+        .map_or(0, |(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::lookup::tables::WorldLookupTables;
+    use crate::model::{initial::initial_conditions_1900, params::ScenarioParams};
+    use crate::simulation::Simulation;
+
+    #[test]
+    fn test_contributions_sum_to_change() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let params = ScenarioParams::bau();
+        let states = Simulation::new(initial_conditions_1900(), params.clone(), tables)
+            .unwrap()
+            .collect_states()
+            .unwrap();
+        let output = SimulationOutput::new(states, params);
+
+        let attribution = attribute(&output, 1900.0, 2100.0).unwrap();
+        assert_eq!(attribution.decompositions.len(), 3);
+        for d in &attribution.decompositions {
+            let explained: f64 = d.contributions.iter().map(|c| c.contribution).sum();
+            assert!((explained + d.residual - d.change()).abs() < 1e-9 * d.start.abs().max(1.0));
+        }
+        // Land yield is never clamped, so the factors explain all of it
+        let land_yield = &attribution.decompositions[2];
+        assert!(land_yield.residual.abs() < 1e-6 * land_yield.start);
+
+        // Health services drove the rise in life expectancy up to 1970
+        let early = attribute(&output, 1900.0, 1970.0).unwrap();
+        let life_expectancy = &early.decompositions[0];
+        assert!(life_expectancy.change() > 0.0);
+        assert_eq!(life_expectancy.dominant().unwrap().factor, "lem_health");
+
+        assert!(matches!(
+            attribute(&output, 1800.0, 1900.0),
+            Err(AttributionError::YearOutOfRange(..))
+        ));
+    }
+}
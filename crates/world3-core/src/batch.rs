@@ -0,0 +1,128 @@
+//! Batch solving: many parameter sets on worker threads.
+//!
+//! Sweeps, ensembles and calibration need hundreds or thousands of runs
+//! from the same initial state. [`solve_many`] spreads them over the
+//! available cores, sharing one copy of the lookup tables. Results come
+//! back in input order, and a failing run only fails its own entry.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::control::RunControl;
+use crate::lookup::tables::WorldLookupTables;
+use crate::model::{params::ScenarioParams, state::WorldState};
+use crate::output::SimulationOutput;
+use crate::simulation::Simulation;
+use crate::solver::SolverError;
+
+/// Run every parameter set from `initial`, each with its own
+/// `params.solver`, on one worker thread per available core.
+///
+/// `results[i]` belongs to `params[i]`.
+pub fn solve_many(
+    initial: &WorldState,
+    params: &[ScenarioParams],
+) -> Vec<Result<SimulationOutput, SolverError>> {
+    solve_many_with(initial, params, Arc::new(WorldLookupTables::load()), default_threads())
+}
+
+/// [`solve_many`] with caller-provided tables and thread count.
+pub fn solve_many_with(
+    initial: &WorldState,
+    params: &[ScenarioParams],
+    tables: Arc<WorldLookupTables>,
+    threads: usize,
+) -> Vec<Result<SimulationOutput, SolverError>> {
+    run_parallel(params, threads, |p| solve_one(initial, p, Arc::clone(&tables)))
+}
+
+/// Default worker count: one per available core.
+pub(crate) fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Map `f` over `items` on `threads` workers, keeping input order.
+pub(crate) fn run_parallel<T: Sync, R: Send>(
+    items: &[T],
+    threads: usize,
+    f: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let results: Vec<Mutex<Option<R>>> = items.iter().map(|_| Mutex::new(None)).collect();
+    // Work queue: each worker claims the next unclaimed index
+    let next = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, items.len().max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else { break };
+                let result = f(item);
+                *results[i].lock().unwrap() = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|slot| slot.into_inner().unwrap().expect("every index is claimed once"))
+        .collect()
+}
+
+pub(crate) fn solve_one(
+    initial: &WorldState,
+    params: &ScenarioParams,
+    tables: Arc<WorldLookupTables>,
+) -> Result<SimulationOutput, SolverError> {
+    solve_one_with(initial, params, tables, &RunControl::default())
+}
+
+/// [`solve_one`] under `control`, so a shared token stops the whole batch.
+pub(crate) fn solve_one_with(
+    initial: &WorldState,
+    params: &ScenarioParams,
+    tables: Arc<WorldLookupTables>,
+    control: &RunControl,
+) -> Result<SimulationOutput, SolverError> {
+    let states = Simulation::new(initial.clone(), params.clone(), tables)?
+        .with_control(control.clone())
+        .collect_states()?;
+    Ok(SimulationOutput::new(states, params.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::initial::initial_conditions_1900;
+    use crate::model::params::SolverKind;
+
+    #[test]
+    fn test_solve_many_keeps_order_and_isolates_errors() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let broken = ScenarioParams {
+            industrial_depreciation_rate: -1.0,
+            ..ScenarioParams::bau()
+        };
+        let params = vec![
+            ScenarioParams::bau(),
+            broken,
+            ScenarioParams::stabilized_world(),
+            ScenarioParams {
+                solver: SolverKind::Rk45,
+                ..ScenarioParams::comprehensive_technology()
+            },
+        ];
+        let initial = initial_conditions_1900();
+        let results = solve_many_with(&initial, &params, Arc::clone(&tables), 3);
+
+        assert_eq!(results.len(), params.len());
+        assert!(matches!(results[1], Err(SolverError::Diverged { .. })));
+        for i in [0, 2, 3] {
+            let expected = solve_one(&initial, &params[i], Arc::clone(&tables)).unwrap();
+            let output = results[i].as_ref().unwrap();
+            assert_eq!(output.scenario_id, params[i].meta.id);
+            let (a, b) = (output.states.last().unwrap(), expected.states.last().unwrap());
+            assert_eq!(a.to_vec(), b.to_vec());
+        }
+    }
+}
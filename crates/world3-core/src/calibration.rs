@@ -0,0 +1,484 @@
+//! Parameter calibration against observed series.
+//!
+//! A [`CalibrationSpec`] lists observed `(year, value)` points per
+//! `extract_series` path, the free `ScenarioParams` fields with their bounds,
+//! and a [`Loss`]. [`calibrate`] fits the free fields with a bounded
+//! Nelder–Mead search from the base scenario's values and returns a
+//! [`CalibrationReport`] comparing the fitted run with the observations.
+//!
+//! The search works in unit coordinates (`0` = `min`, `1` = `max`), so
+//! parameters of very different magnitudes get comparable steps. Failed
+//! runs score an infinite loss.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::batch::{default_threads, run_parallel, solve_one};
+use crate::lookup::tables::WorldLookupTables;
+use crate::model::{params::ScenarioParams, state::WorldState};
+use crate::output::{series_paths, SimulationOutput};
+use crate::solver::traits::SolverError;
+
+/// Observations of one variable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObservedSeries {
+    /// `[year, value]` points
+    pub points: Vec<(f64, f64)>,
+    /// Weight of the series in the loss
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bounds {
+    pub min: f64,
+    pub max: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Loss {
+    /// Weighted sum of squared errors, each series scaled by the mean
+    /// magnitude of its observations so populations and indices compare
+    #[default]
+    Sse,
+    /// Weighted sum of squared log errors `ln(simulated / observed)²` —
+    /// relative errors, for series spanning orders of magnitude
+    Log,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationSpec {
+    /// Observed series by `extract_series` path
+    pub observed: BTreeMap<String, ObservedSeries>,
+    /// Free parameters (`ScenarioParams::TUNABLE` fields) and their bounds
+    pub parameters: BTreeMap<String, Bounds>,
+    #[serde(default)]
+    pub loss: Loss,
+    /// Stop after this many model runs
+    #[serde(default = "default_max_evaluations")]
+    pub max_evaluations: usize,
+    /// Converged when the simplex spans less than this in loss and in unit
+    /// coordinates
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+}
+
+fn default_max_evaluations() -> usize {
+    200
+}
+
+fn default_tolerance() -> f64 {
+    1e-4
+}
+
+/// Fitted run against one observed series.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesFit {
+    pub variable: String,
+    pub weight: f64,
+    pub years: Vec<f64>,
+    pub observed: Vec<f64>,
+    pub simulated: Vec<f64>,
+    /// Contribution to the total loss
+    pub loss: f64,
+    /// Root mean squared error, in the variable's units
+    pub rmse: f64,
+    /// Mean absolute percentage error, as a fraction
+    pub mape: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationReport {
+    /// Fitted values of the free parameters
+    pub parameters: BTreeMap<String, f64>,
+    /// Starting values (the base scenario's, clamped into bounds)
+    pub initial: BTreeMap<String, f64>,
+    pub initial_loss: f64,
+    pub loss: f64,
+    /// Model runs performed
+    pub evaluations: usize,
+    /// Whether the search met `tolerance` before `max_evaluations`
+    pub converged: bool,
+    /// Fitted run against each observed series
+    pub series: Vec<SeriesFit>,
+    /// Base scenario with the fitted values
+    pub params: ScenarioParams,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CalibrationError {
+    #[error("Invalid calibration: {0}")]
+    InvalidSpec(String),
+    #[error("Fitted run failed: {0}")]
+    Solver(#[from] SolverError),
+}
+
+/// Fit `spec.parameters` of `base` to `spec.observed`.
+pub fn calibrate(
+    initial: &WorldState,
+    base: &ScenarioParams,
+    spec: &CalibrationSpec,
+    tables: Arc<WorldLookupTables>,
+) -> Result<CalibrationReport, CalibrationError> {
+    let problem = Problem::new(initial, base, spec, tables)?;
+    let start: Vec<f64> = problem
+        .bounds
+        .iter()
+        .zip(&problem.fields)
+        .map(|(b, field)| {
+            let value = base.value(field).expect("parameters are checked against TUNABLE").clamp(b.min, b.max);
+            (value - b.min) / (b.max - b.min)
+        })
+        .collect();
+
+    let evaluate = |points: &[Vec<f64>]| {
+        run_parallel(points, default_threads(), |x| {
+            solve_one(initial, &problem.params(x), Arc::clone(&problem.tables))
+                .map_or(f64::INFINITY, |output| problem.loss(&output))
+        })
+    };
+    let optimum = nelder_mead(&start, 0.1, spec.max_evaluations, spec.tolerance, evaluate);
+
+    let params = problem.params(&optimum.x);
+    let output = solve_one(initial, &params, Arc::clone(&problem.tables))?;
+    let values = |x: &[f64]| -> BTreeMap<String, f64> {
+        let p = problem.params(x);
+        problem.fields.iter().map(|f| (f.clone(), p.value(f).expect("parameters are checked against TUNABLE"))).collect()
+    };
+    Ok(CalibrationReport {
+        parameters: values(&optimum.x),
+        initial: values(&start),
+        initial_loss: optimum.initial_f,
+        loss: optimum.f,
+        evaluations: optimum.evaluations + 1,
+        converged: optimum.converged,
+        series: problem.fits(&output),
+        params,
+    })
+}
+
+/// Validated spec: maps unit coordinates to parameters and runs to losses.
+struct Problem<'a> {
+    base: &'a ScenarioParams,
+    fields: Vec<String>,
+    bounds: Vec<Bounds>,
+    /// `(variable, series, scale)`; `scale` normalizes SSE residuals
+    observed: Vec<(&'a String, &'a ObservedSeries, f64)>,
+    loss: Loss,
+    tables: Arc<WorldLookupTables>,
+}
+
+impl<'a> Problem<'a> {
+    fn new(
+        initial: &WorldState,
+        base: &'a ScenarioParams,
+        spec: &'a CalibrationSpec,
+        tables: Arc<WorldLookupTables>,
+    ) -> Result<Self, CalibrationError> {
+        let invalid = |msg: String| Err(CalibrationError::InvalidSpec(msg));
+        if spec.parameters.is_empty() {
+            return invalid("no free parameters".into());
+        }
+        for (field, b) in &spec.parameters {
+            if !ScenarioParams::TUNABLE.contains(&field.as_str()) {
+                return invalid(format!("'{}' is not a tunable parameter", field));
+            }
+            if base.schedules.contains_key(field) {
+                return invalid(format!("'{}' follows a schedule", field));
+            }
+            if !(b.min.is_finite() && b.max.is_finite() && b.min < b.max) {
+                return invalid(format!("bounds of '{}' must be finite with min < max", field));
+            }
+        }
+        if spec.observed.is_empty() {
+            return invalid("no observed series".into());
+        }
+        let known = series_paths();
+        let (first, last) = (initial.time.max(base.start_year), base.end_year);
+        let mut observed = Vec::new();
+        for (variable, series) in &spec.observed {
+            if !known.contains(variable) {
+                return invalid(format!("unknown variable '{}'", variable));
+            }
+            if series.points.is_empty() || series.weight.is_nan() || series.weight < 0.0 {
+                return invalid(format!("'{}' needs points and a weight >= 0", variable));
+            }
+            for &(year, value) in &series.points {
+                if !(first..=last).contains(&year) || !value.is_finite() {
+                    return invalid(format!(
+                        "'{}' point ({}, {}) is outside {}–{} or not finite",
+                        variable, year, value, first, last
+                    ));
+                }
+                if spec.loss == Loss::Log && value <= 0.0 {
+                    return invalid(format!(
+                        "log loss needs positive observations of '{}'",
+                        variable
+                    ));
+                }
+            }
+            let n = series.points.len() as f64;
+            let scale = series.points.iter().map(|p| p.1.abs()).sum::<f64>() / n;
+            observed.push((variable, series, if scale > 0.0 { scale } else { 1.0 }));
+        }
+        if spec.max_evaluations <= spec.parameters.len() {
+            return invalid("max_evaluations must exceed the number of parameters".into());
+        }
+        Ok(Self {
+            base,
+            fields: spec.parameters.keys().cloned().collect(),
+            bounds: spec.parameters.values().copied().collect(),
+            observed,
+            loss: spec.loss,
+            tables,
+        })
+    }
+
+    fn params(&self, x: &[f64]) -> ScenarioParams {
+        let mut params = self.base.clone();
+        for ((field, b), u) in self.fields.iter().zip(&self.bounds).zip(x) {
+            params.set_value(field, b.min + u * (b.max - b.min));
+        }
+        params
+    }
+
+    /// Observed and simulated values of one series.
+    fn compare(&self, output: &SimulationOutput, i: usize) -> (Vec<f64>, Vec<f64>) {
+        let (variable, series, _) = self.observed[i];
+        let values = output.extract_series(variable);
+        series
+            .points
+            .iter()
+            .map(|&(year, observed)| (observed, interpolate(&output.timeline, &values, year)))
+            .unzip()
+    }
+
+    fn series_loss(&self, observed: &[f64], simulated: &[f64], i: usize) -> f64 {
+        let (_, series, scale) = self.observed[i];
+        let sum: f64 = observed
+            .iter()
+            .zip(simulated)
+            .map(|(&o, &s)| match self.loss {
+                Loss::Sse => ((s - o) / scale).powi(2),
+                Loss::Log if s > 0.0 => (s / o).ln().powi(2),
+                Loss::Log => f64::INFINITY,
+            })
+            .sum();
+        // Runs that end early leave NaN gaps
+        let sum = if sum.is_nan() { f64::INFINITY } else { sum };
+        series.weight * sum
+    }
+
+    fn loss(&self, output: &SimulationOutput) -> f64 {
+        (0..self.observed.len())
+            .map(|i| {
+                let (observed, simulated) = self.compare(output, i);
+                self.series_loss(&observed, &simulated, i)
+            })
+            .sum()
+    }
+
+    fn fits(&self, output: &SimulationOutput) -> Vec<SeriesFit> {
+        (0..self.observed.len())
+            .map(|i| {
+                let (variable, series, _) = self.observed[i];
+                let (observed, simulated) = self.compare(output, i);
+                let n = observed.len() as f64;
+                let errors = observed.iter().zip(&simulated).map(|(o, s)| (s - o, o));
+                SeriesFit {
+                    variable: variable.clone(),
+                    weight: series.weight,
+                    years: series.points.iter().map(|p| p.0).collect(),
+                    loss: self.series_loss(&observed, &simulated, i),
+                    rmse: (errors.clone().map(|(e, _)| e * e).sum::<f64>() / n).sqrt(),
+                    mape: errors.map(|(e, o)| (e / o).abs()).sum::<f64>() / n,
+                    observed,
+                    simulated,
+                }
+            })
+            .collect()
+    }
+}
+
+/// `values` at `year`, linear between output years.
+fn interpolate(timeline: &[f64], values: &[f64], year: f64) -> f64 {
+    let i = timeline.partition_point(|&t| t < year);
+    match (i.checked_sub(1), timeline.get(i)) {
+        (_, Some(&t)) if t == year => values[i],
+        (Some(j), Some(&t1)) => {
+            let (t0, v0, v1) = (timeline[j], values[j], values[i]);
+            v0 + (v1 - v0) * (year - t0) / (t1 - t0)
+        }
+        _ => f64::NAN,
+    }
+}
+
+struct Optimum {
+    x: Vec<f64>,
+    f: f64,
+    initial_f: f64,
+    evaluations: usize,
+    converged: bool,
+}
+
+/// Nelder–Mead on the unit cube, projecting trial points onto its faces.
+/// `evaluate` scores a batch of points, so the initial simplex and shrink
+/// steps can run in parallel.
+fn nelder_mead(
+    start: &[f64],
+    step: f64,
+    max_evaluations: usize,
+    tolerance: f64,
+    evaluate: impl Fn(&[Vec<f64>]) -> Vec<f64>,
+) -> Optimum {
+    let n = start.len();
+    let evaluate_one = |x: &Vec<f64>| evaluate(std::slice::from_ref(x))[0];
+    let mut points = vec![start.to_vec()];
+    for i in 0..n {
+        let mut x = start.to_vec();
+        x[i] += if x[i] + step <= 1.0 { step } else { -step };
+        points.push(x);
+    }
+    let values = evaluate(&points);
+    let initial_f = values[0];
+    let mut simplex: Vec<(Vec<f64>, f64)> = points.into_iter().zip(values).collect();
+    let mut evaluations = n + 1;
+    let mut converged = false;
+
+    loop {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let best = &simplex[0];
+        let f_spread = simplex[n].1 - best.1;
+        let x_spread = simplex[1..]
+            .iter()
+            .flat_map(|(x, _)| x.iter().zip(&best.0).map(|(a, b)| (a - b).abs()))
+            .fold(0.0, f64::max);
+        if f_spread <= tolerance && x_spread <= tolerance {
+            converged = true;
+            break;
+        }
+        if evaluations >= max_evaluations {
+            break;
+        }
+
+        // Move the worst point through the centroid of the others
+        let worst = simplex[n].0.clone();
+        let centroid: Vec<f64> = (0..n)
+            .map(|j| simplex[..n].iter().map(|(x, _)| x[j]).sum::<f64>() / n as f64)
+            .collect();
+        let towards = |t: f64| -> Vec<f64> {
+            centroid.iter().zip(&worst).map(|(c, w)| (c + t * (c - w)).clamp(0.0, 1.0)).collect()
+        };
+        let reflected = towards(1.0);
+        let f_reflected = evaluate_one(&reflected);
+        evaluations += 1;
+        if f_reflected < simplex[0].1 {
+            let expanded = towards(2.0);
+            let f_expanded = evaluate_one(&expanded);
+            evaluations += 1;
+            simplex[n] = if f_expanded < f_reflected {
+                (expanded, f_expanded)
+            } else {
+                (reflected, f_reflected)
+            };
+        } else if f_reflected < simplex[n - 1].1 {
+            simplex[n] = (reflected, f_reflected);
+        } else {
+            // Outside contraction if the reflection improved on the worst
+            // point, inside otherwise
+            let contracted = towards(if f_reflected < simplex[n].1 { 0.5 } else { -0.5 });
+            let f_contracted = evaluate_one(&contracted);
+            evaluations += 1;
+            if f_contracted < f_reflected.min(simplex[n].1) {
+                simplex[n] = (contracted, f_contracted);
+            } else {
+                // Shrink towards the best point
+                let best = simplex[0].0.clone();
+                let points: Vec<Vec<f64>> = simplex[1..]
+                    .iter()
+                    .map(|(x, _)| best.iter().zip(x).map(|(b, v)| b + 0.5 * (v - b)).collect())
+                    .collect();
+                let values = evaluate(&points);
+                evaluations += n;
+                for (slot, point) in simplex[1..].iter_mut().zip(points.into_iter().zip(values)) {
+                    *slot = point;
+                }
+            }
+        }
+    }
+
+    let (x, f) = simplex.swap_remove(0);
+    Optimum {
+        x,
+        f,
+        initial_f,
+        evaluations,
+        converged,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::initial::initial_conditions_1900;
+
+    #[test]
+    fn test_nelder_mead_finds_interior_and_boundary_minima() {
+        // Minimum at (0.3, 0.7) inside the cube; (1.4, 0.5) is clamped to x = 1
+        for (target, expected) in [([0.3, 0.7], [0.3, 0.7]), ([1.4, 0.5], [1.0, 0.5])] {
+            let f = |x: &Vec<f64>| (x[0] - target[0]).powi(2) + 10.0 * (x[1] - target[1]).powi(2);
+            let optimum = nelder_mead(&[0.5, 0.5], 0.1, 500, 1e-8, |points: &[Vec<f64>]| {
+                points.iter().map(f).collect()
+            });
+            assert!(optimum.converged);
+            assert!((optimum.x[0] - expected[0]).abs() < 1e-3, "{:?}", optimum.x);
+            assert!((optimum.x[1] - expected[1]).abs() < 1e-3, "{:?}", optimum.x);
+        }
+    }
+
+    #[test]
+    fn test_calibration_recovers_investment_rate() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let initial = initial_conditions_1900();
+        let base = ScenarioParams::bau();
+        let mut truth = base.clone();
+        truth.investment_rate = 0.135;
+        let observed = solve_one(&initial, &truth, Arc::clone(&tables)).unwrap();
+        let points = |path: &str| ObservedSeries {
+            points: (1900..=2020)
+                .step_by(10)
+                .map(|year| {
+                    let s = observed.state_at_year(year as f64).unwrap();
+                    (year as f64, crate::output::extract_field(s, path).unwrap())
+                })
+                .collect(),
+            weight: 1.0,
+        };
+        let spec = CalibrationSpec {
+            observed: [
+                ("population.population".to_string(), points("population.population")),
+                ("capital.industrial_output".to_string(), points("capital.industrial_output")),
+            ]
+            .into(),
+            parameters: [("investment_rate".to_string(), Bounds { min: 0.08, max: 0.16 })].into(),
+            loss: Loss::Log,
+            max_evaluations: 100,
+            tolerance: 1e-6,
+        };
+        let report = calibrate(&initial, &base, &spec, tables).unwrap();
+
+        assert!(report.converged);
+        assert!((report.parameters["investment_rate"] - 0.135).abs() < 1e-3);
+        assert_eq!(report.initial["investment_rate"], 0.15);
+        assert!(report.loss < 1e-4 && report.loss < report.initial_loss);
+        assert_eq!(report.params.investment_rate, report.parameters["investment_rate"]);
+        assert!(report.series.iter().all(|s| s.mape < 0.01));
+    }
+}
@@ -0,0 +1,34 @@
+//! Simulation checkpoints.
+//!
+//! A `Checkpoint` captures everything needed to continue a run from a given
+//! year: the full `WorldState` plus the solver's internal state. Resuming
+//! from a checkpoint with *different* parameters branches a scenario
+//! ("what if policy changes in 2030") without recomputing 1900–2030.
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::state::WorldState;
+
+/// State of a live run ([`Simulation::checkpoint`]) at an integration point.
+///
+/// Recorded output states may be interpolated and carry no solver state, so
+/// checkpoints are not built from them. The parameters are not part of a
+/// checkpoint: [`Simulation::from_checkpoint`] takes the ones to resume with.
+///
+/// [`Simulation::checkpoint`]: crate::simulation::Simulation::checkpoint
+/// [`Simulation::from_checkpoint`]: crate::simulation::Simulation::from_checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Full state at the checkpoint year (stocks and auxiliaries)
+    pub state: WorldState,
+    /// RK45 trial step size at the checkpoint, if the run was adaptive
+    #[serde(default)]
+    pub step_size: Option<f64>,
+}
+
+impl Checkpoint {
+    /// Year of the checkpoint.
+    pub fn year(&self) -> f64 {
+        self.state.time
+    }
+}
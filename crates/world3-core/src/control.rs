@@ -0,0 +1,102 @@
+//! Cooperative cancellation and progress reporting for long runs.
+//!
+//! A [`RunControl`] is handed to [`Simulation::with_control`](crate::Simulation::with_control)
+//! or [`OdeSolver::solve_with`](crate::OdeSolver::solve_with). Before every
+//! integration step the simulation checks its [`CancelToken`] and stops with
+//! [`SolverError::Cancelled`](crate::SolverError::Cancelled) once it is set;
+//! after every accepted step it reports a [`Progress`].
+//!
+//! Cancelling is the only way to stop a run on a blocking thread: aborting
+//! the task that awaits it does not.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Shared flag that asks a run to stop. Clones observe the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Guard that cancels the token when dropped, e.g. together with the
+    /// future of a request whose client went away.
+    pub fn drop_guard(&self) -> CancelGuard {
+        CancelGuard(self.clone())
+    }
+}
+
+/// Cancels its token on drop; see [`CancelToken::drop_guard`].
+#[derive(Debug)]
+pub struct CancelGuard(CancelToken);
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// Position of a run after an accepted step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    /// Simulation year reached
+    pub year: f64,
+    /// Share of the run done, from the initial year to the end year (0–1)
+    pub fraction: f64,
+}
+
+/// Callback receiving [`Progress`] reports, on the thread running the solve.
+pub type ProgressFn = Arc<dyn Fn(Progress) + Send + Sync>;
+
+/// Cancellation token and progress callback for a run; both optional.
+#[derive(Clone, Default)]
+pub struct RunControl {
+    pub cancel: Option<CancelToken>,
+    pub progress: Option<ProgressFn>,
+}
+
+impl RunControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_cancel(mut self, token: CancelToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    pub fn with_progress(mut self, progress: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(CancelToken::is_cancelled)
+    }
+
+    pub(crate) fn report(&self, progress: Progress) {
+        if let Some(f) = &self.progress {
+            f(progress);
+        }
+    }
+}
+
+impl fmt::Debug for RunControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RunControl")
+            .field("cancel", &self.cancel)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
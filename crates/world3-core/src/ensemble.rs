@@ -0,0 +1,323 @@
+//! Monte Carlo ensembles.
+//!
+//! An [`EnsembleSpec`] names the parameters to vary and their
+//! [`Distribution`]s, and optionally perturbs the lookup tables. Every run
+//! starts from the same base `ScenarioParams` with its own sample; the runs
+//! are spread over worker threads and summarized as percentile bands
+//! (5/25/50/75/95) per variable over time.
+//!
+//! Samples are drawn up front from `seed`, so an ensemble is reproducible
+//! regardless of the thread count.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::batch::{default_threads, run_parallel};
+use crate::lookup::tables::WorldLookupTables;
+use crate::model::{params::ScenarioParams, state::WorldState};
+use crate::output::SimulationOutput;
+use crate::rng::Rng;
+use crate::simulation::Simulation;
+
+/// Distribution a parameter is sampled from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Distribution {
+    Uniform { min: f64, max: f64 },
+    Normal { mean: f64, std_dev: f64 },
+    Triangular { min: f64, mode: f64, max: f64 },
+}
+
+impl Distribution {
+    pub(crate) fn sample(&self, rng: &mut Rng) -> f64 {
+        match *self {
+            Distribution::Uniform { min, max } => min + (max - min) * rng.next_f64(),
+            Distribution::Normal { mean, std_dev } => mean + std_dev * rng.normal(),
+            Distribution::Triangular { min, mode, max } => {
+                // Inverse CDF
+                let u = rng.next_f64();
+                let split = (mode - min) / (max - min);
+                if u < split {
+                    min + (u * (max - min) * (mode - min)).sqrt()
+                } else {
+                    max - ((1.0 - u) * (max - min) * (max - mode)).sqrt()
+                }
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let ok = match *self {
+            Distribution::Uniform { min, max } => min.is_finite() && max.is_finite() && min <= max,
+            Distribution::Normal { mean, std_dev } => mean.is_finite() && std_dev >= 0.0,
+            Distribution::Triangular { min, mode, max } => {
+                min.is_finite() && max.is_finite() && min <= mode && mode <= max && min < max
+            }
+        };
+        if ok {
+            Ok(())
+        } else {
+            Err(format!("invalid distribution {:?}", self))
+        }
+    }
+}
+
+/// Random scaling of lookup-table outputs, one factor per table and run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TablePerturbation {
+    /// Standard deviation of the log scale factor (0.1 ≈ ±10%)
+    pub sigma: f64,
+    /// Tables to perturb, by name; all tables when empty
+    #[serde(default)]
+    pub tables: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnsembleSpec {
+    /// Number of runs
+    pub runs: usize,
+    #[serde(default)]
+    pub seed: u64,
+    /// Sampled parameters, by `ScenarioParams` field name
+    #[serde(default)]
+    pub parameters: BTreeMap<String, Distribution>,
+    /// Optional lookup-table perturbation
+    #[serde(default)]
+    pub tables: Option<TablePerturbation>,
+    /// `extract_series` paths to summarize
+    #[serde(default = "default_variables")]
+    pub variables: Vec<String>,
+    /// Return every run alongside the bands
+    #[serde(default = "default_keep_runs")]
+    pub keep_runs: bool,
+}
+
+fn default_variables() -> Vec<String> {
+    [
+        "population.population",
+        "population.life_expectancy",
+        "capital.industrial_output_per_capita",
+        "agriculture.food_per_capita",
+        "resources.fraction_remaining",
+        "pollution.pollution_index",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+fn default_keep_runs() -> bool {
+    true
+}
+
+impl EnsembleSpec {
+    pub fn new(runs: usize, seed: u64) -> Self {
+        Self {
+            runs,
+            seed,
+            parameters: BTreeMap::new(),
+            tables: None,
+            variables: default_variables(),
+            keep_runs: true,
+        }
+    }
+
+    /// Sample `field` from `distribution`.
+    pub fn vary(mut self, field: impl Into<String>, distribution: Distribution) -> Self {
+        self.parameters.insert(field.into(), distribution);
+        self
+    }
+
+    /// Scale lookup tables by random log-normal factors.
+    pub fn perturb_tables(mut self, perturbation: TablePerturbation) -> Self {
+        self.tables = Some(perturbation);
+        self
+    }
+}
+
+/// Percentiles of one variable across the ensemble, per output year.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PercentileBands {
+    pub p5: Vec<f64>,
+    pub p25: Vec<f64>,
+    pub p50: Vec<f64>,
+    pub p75: Vec<f64>,
+    pub p95: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnsembleOutput {
+    pub timeline: Vec<f64>,
+    /// Percentile bands per variable, over the successful runs
+    pub bands: BTreeMap<String, PercentileBands>,
+    /// Values drawn for each run: parameters by field name, table factors
+    /// by table name
+    pub samples: Vec<BTreeMap<String, f64>>,
+    /// Every run in sample order (empty unless `keep_runs`); failed runs
+    /// carry their partial states and `error`
+    pub runs: Vec<SimulationOutput>,
+    /// Number of runs that hit a solver error
+    pub failed: usize,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EnsembleError {
+    #[error("Invalid ensemble: {0}")]
+    InvalidSpec(String),
+}
+
+/// Run the ensemble on one worker thread per available core.
+pub fn run_ensemble(
+    initial: &WorldState,
+    base: &ScenarioParams,
+    spec: &EnsembleSpec,
+    tables: Arc<WorldLookupTables>,
+) -> Result<EnsembleOutput, EnsembleError> {
+    let invalid = |msg: String| EnsembleError::InvalidSpec(msg);
+    let mut probe = base.clone();
+    for (field, distribution) in &spec.parameters {
+        if !probe.set_value(field, 0.0) {
+            return Err(invalid(format!("'{}' is not a tunable parameter", field)));
+        }
+        distribution.validate().map_err(|e| invalid(format!("{}: {}", field, e)))?;
+    }
+    let mut table_names: Vec<String> = Vec::new();
+    if let Some(perturbation) = &spec.tables {
+        if perturbation.sigma.is_nan() || perturbation.sigma < 0.0 {
+            return Err(invalid(format!("table sigma must be >= 0, got {}", perturbation.sigma)));
+        }
+        let known: Vec<String> = (*tables).clone().iter_mut().map(|t| t.name.clone()).collect();
+        for name in &perturbation.tables {
+            if !known.contains(name) {
+                return Err(invalid(format!("unknown lookup table '{}'", name)));
+            }
+        }
+        table_names = if perturbation.tables.is_empty() {
+            known
+        } else {
+            perturbation.tables.clone()
+        };
+    }
+
+    // Draw every sample before running, so results don't depend on scheduling
+    let mut rng = Rng::new(spec.seed);
+    let samples: Vec<BTreeMap<String, f64>> = (0..spec.runs)
+        .map(|_| {
+            let mut sample: BTreeMap<String, f64> = spec
+                .parameters
+                .iter()
+                .map(|(field, d)| (field.clone(), d.sample(&mut rng)))
+                .collect();
+            if let Some(perturbation) = &spec.tables {
+                for name in &table_names {
+                    sample.insert(name.clone(), (perturbation.sigma * rng.normal()).exp());
+                }
+            }
+            sample
+        })
+        .collect();
+
+    let outputs = run_parallel(&samples, default_threads(), |sample| {
+        let mut params = base.clone();
+        let mut run_tables = spec.tables.as_ref().map(|_| (*tables).clone());
+        for (name, value) in sample {
+            if params.set_value(name, *value) {
+                continue;
+            }
+            if let Some(table) = run_tables
+                .as_mut()
+                .and_then(|t| t.iter_mut().find(|t| &t.name == name))
+            {
+                table.y.iter_mut().for_each(|y| *y *= value);
+            }
+        }
+        let run_tables = run_tables.map_or_else(|| Arc::clone(&tables), Arc::new);
+        let partial = match Simulation::new(initial.clone(), params.clone(), run_tables) {
+            Ok(sim) => sim.collect_partial(),
+            Err(e) => e.into(),
+        };
+        SimulationOutput::from_partial(partial, params)
+    });
+
+    let ok: Vec<&SimulationOutput> = outputs.iter().filter(|o| o.error.is_none()).collect();
+    let timeline = ok.first().map(|o| o.timeline.clone()).unwrap_or_default();
+    let bands = spec
+        .variables
+        .iter()
+        .map(|path| {
+            let series: Vec<Vec<f64>> = ok.iter().map(|o| o.extract_series(path)).collect();
+            (path.clone(), percentile_bands(&series, timeline.len()))
+        })
+        .collect();
+
+    Ok(EnsembleOutput {
+        timeline,
+        bands,
+        samples,
+        failed: outputs.len() - ok.len(),
+        runs: if spec.keep_runs { outputs } else { Vec::new() },
+    })
+}
+
+fn percentile_bands(series: &[Vec<f64>], len: usize) -> PercentileBands {
+    let mut bands = PercentileBands::default();
+    for i in 0..len {
+        let mut values: Vec<f64> =
+            series.iter().filter_map(|s| s.get(i).copied()).filter(|v| v.is_finite()).collect();
+        values.sort_by(f64::total_cmp);
+        bands.p5.push(percentile(&values, 0.05));
+        bands.p25.push(percentile(&values, 0.25));
+        bands.p50.push(percentile(&values, 0.50));
+        bands.p75.push(percentile(&values, 0.75));
+        bands.p95.push(percentile(&values, 0.95));
+    }
+    bands
+}
+
+/// Percentile `q` of sorted `values`, interpolating between order statistics.
+fn percentile(values: &[f64], q: f64) -> f64 {
+    if values.is_empty() {
+        return f64::NAN;
+    }
+    let pos = q * (values.len() - 1) as f64;
+    let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+    values[lo] + (values[hi] - values[lo]) * (pos - lo as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::initial::initial_conditions_1900;
+
+    #[test]
+    fn test_ensemble_bands_are_ordered_and_reproducible() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let spec = EnsembleSpec::new(24, 7)
+            .vary("pollution_control", Distribution::Uniform { min: 0.0, max: 0.6 })
+            .vary(
+                "investment_rate",
+                Distribution::Triangular { min: 0.13, mode: 0.15, max: 0.18 },
+            )
+            .perturb_tables(TablePerturbation {
+                sigma: 0.05,
+                tables: vec!["life_exp_multiplier_food".into()],
+            });
+        let initial = initial_conditions_1900();
+        let base = ScenarioParams::bau();
+        let a = run_ensemble(&initial, &base, &spec, Arc::clone(&tables)).unwrap();
+        let b = run_ensemble(&initial, &base, &spec, tables).unwrap();
+
+        assert_eq!(a.runs.len(), 24);
+        assert_eq!(a.samples, b.samples);
+        let pop = &a.bands["population.population"];
+        assert_eq!(pop.p50, b.bands["population.population"].p50);
+        assert_eq!(pop.p50.len(), a.timeline.len());
+        for i in 0..a.timeline.len() {
+            assert!(pop.p5[i] <= pop.p25[i] && pop.p25[i] <= pop.p50[i]);
+            assert!(pop.p50[i] <= pop.p75[i] && pop.p75[i] <= pop.p95[i]);
+        }
+        // Parameters vary, so the 2100 band has width
+        assert!(pop.p95[200] > pop.p5[200]);
+    }
+}
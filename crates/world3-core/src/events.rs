@@ -0,0 +1,265 @@
+//! Event detection during integration.
+//!
+//! Callers register [`EventSpec`]s on a [`Simulation`](crate::Simulation).
+//! After every accepted step each condition is evaluated at the new state;
+//! when it changes sign inside the step, the crossing year is located by
+//! bisection on the step's dense-output interpolant, so it does not depend
+//! on the output grid. Peaks and troughs are detected the same way on the
+//! variable's slope.
+//!
+//! Variables are named by `SimulationOutput::extract_series` paths
+//! (e.g. `"population.population"`); thresholds may be a number, another
+//! variable, or a tunable scenario parameter
+//! (`"params.subsistence_food_per_capita"`).
+//! In a regional run they are prefixed with the region, e.g.
+//! `"South/agriculture.food_per_capita"`, or summed over the regions without
+//! one (see [`crate::region`]).
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::params::ScenarioParams;
+
+/// Width of the bracket around a located event [years]
+const YEAR_TOL: f64 = 1e-6;
+
+/// A condition to watch for during a run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventSpec {
+    /// Label reported on the recorded [`Event`]
+    pub name: String,
+    pub condition: EventCondition,
+    /// Stop the run at the first occurrence
+    #[serde(default)]
+    pub terminal: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EventCondition {
+    /// `variable` rises above `threshold`
+    Above { variable: String, threshold: Operand },
+    /// `variable` falls below `threshold`
+    Below { variable: String, threshold: Operand },
+    /// `variable` reaches a local maximum
+    Peak { variable: String },
+    /// `variable` reaches a local minimum
+    Trough { variable: String },
+}
+
+/// Right-hand side of a threshold condition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Operand {
+    Value(f64),
+    /// A state variable path, or `params.<field>` for a scenario parameter
+    Variable(String),
+}
+
+/// An event located during a run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub name: String,
+    /// Interpolated year of the crossing (or extremum)
+    pub year: f64,
+    /// Value of the watched variable at `year`
+    pub value: f64,
+    /// Whether the event stopped the run
+    pub terminal: bool,
+}
+
+impl EventSpec {
+    pub fn new(name: impl Into<String>, condition: EventCondition) -> Self {
+        Self {
+            name: name.into(),
+            condition,
+            terminal: false,
+        }
+    }
+
+    /// Stop the run when the event fires.
+    pub fn terminal(mut self) -> Self {
+        self.terminal = true;
+        self
+    }
+
+    fn variable(&self) -> &str {
+        match &self.condition {
+            EventCondition::Above { variable, .. }
+            | EventCondition::Below { variable, .. }
+            | EventCondition::Peak { variable }
+            | EventCondition::Trough { variable } => variable,
+        }
+    }
+}
+
+/// Parse `KIND:VARIABLE[:THRESHOLD]`, e.g. `peak:population.population` or
+/// `below:agriculture.food_per_capita:params.subsistence_food_per_capita`.
+/// The spec string itself becomes the event name.
+impl std::str::FromStr for EventSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        let operand = |t: &str| match t.parse::<f64>() {
+            Ok(v) => Operand::Value(v),
+            Err(_) => Operand::Variable(t.to_string()),
+        };
+        let condition = match parts.as_slice() {
+            ["above", variable, threshold] => EventCondition::Above {
+                variable: variable.to_string(),
+                threshold: operand(threshold),
+            },
+            ["below", variable, threshold] => EventCondition::Below {
+                variable: variable.to_string(),
+                threshold: operand(threshold),
+            },
+            ["peak", variable] => EventCondition::Peak {
+                variable: variable.to_string(),
+            },
+            ["trough", variable] => EventCondition::Trough {
+                variable: variable.to_string(),
+            },
+            _ => {
+                return Err(format!(
+                    "Invalid event '{}'. Use: above:VAR:THRESHOLD, below:VAR:THRESHOLD, peak:VAR, trough:VAR",
+                    s
+                ))
+            }
+        };
+        Ok(EventSpec::new(s, condition))
+    }
+}
+
+/// Reads a variable path off a state of the watched system.
+type Observe<'a, S> = &'a dyn Fn(&S, &str) -> Option<f64>;
+
+/// Resolved threshold: parameters are looked up once, variables per state.
+enum Threshold {
+    Constant(f64),
+    Variable(String),
+}
+
+/// Tracks one event across steps.
+pub(crate) struct EventDetector {
+    spec: EventSpec,
+    threshold: Option<Threshold>,
+    /// Event function at the last accepted point
+    last: Option<f64>,
+}
+
+impl EventDetector {
+    /// `known` tells whether the watched system has a variable path.
+    pub fn new(
+        spec: EventSpec,
+        params: &ScenarioParams,
+        known: &dyn Fn(&str) -> bool,
+    ) -> Result<Self, String> {
+        if !known(spec.variable()) {
+            return Err(format!("unknown variable '{}' in event '{}'", spec.variable(), spec.name));
+        }
+        let threshold = match &spec.condition {
+            EventCondition::Above { threshold, .. } | EventCondition::Below { threshold, .. } => {
+                Some(match threshold {
+                    Operand::Value(v) => Threshold::Constant(*v),
+                    Operand::Variable(path) => match path.strip_prefix("params.") {
+                        Some(field) => Threshold::Constant(params.value(field).ok_or_else(
+                            || format!("unknown parameter '{}' in event '{}'", field, spec.name),
+                        )?),
+                        None if known(path) => {
+                            Threshold::Variable(path.clone())
+                        }
+                        None => {
+                            return Err(format!(
+                                "unknown variable '{}' in event '{}'",
+                                path, spec.name
+                            ))
+                        }
+                    },
+                })
+            }
+            _ => None,
+        };
+        Ok(Self {
+            spec,
+            threshold,
+            last: None,
+        })
+    }
+
+    fn watched<S>(&self, state: &S, observe: Observe<S>) -> f64 {
+        observe(state, self.spec.variable()).unwrap_or(f64::NAN)
+    }
+
+    /// Event function at `t`. For thresholds this is `variable − threshold`;
+    /// for extrema it is the slope of the variable, by finite differences on
+    /// `at` within `[t0, t1]`.
+    fn value<S>(&self, t: f64, t0: f64, t1: f64, at: &dyn Fn(f64) -> S, observe: Observe<S>) -> f64 {
+        match &self.threshold {
+            Some(threshold) => {
+                let state = at(t);
+                let bound = match threshold {
+                    Threshold::Constant(v) => *v,
+                    Threshold::Variable(path) => observe(&state, path).unwrap_or(f64::NAN),
+                };
+                self.watched(&state, observe) - bound
+            }
+            None => {
+                let delta = 1e-4 * (t1 - t0);
+                let (a, b) = ((t - delta).max(t0), (t + delta).min(t1));
+                (self.watched(&at(b), observe) - self.watched(&at(a), observe)) / (b - a)
+            }
+        }
+    }
+
+    /// Whether the event function has crossed from `before` to `after`.
+    fn crossed(&self, before: f64, after: f64) -> bool {
+        match self.spec.condition {
+            EventCondition::Above { .. } => before <= 0.0 && after > 0.0,
+            EventCondition::Below { .. } => before >= 0.0 && after < 0.0,
+            EventCondition::Peak { .. } => before > 0.0 && after <= 0.0,
+            EventCondition::Trough { .. } => before < 0.0 && after >= 0.0,
+        }
+    }
+
+    /// Check the accepted step `[t0, t1]`, where `at(t)` evaluates the
+    /// step's interpolant (with auxiliaries) and `observe` reads a variable
+    /// path off a state. Returns the located event.
+    pub fn check<S>(
+        &mut self,
+        t0: f64,
+        t1: f64,
+        at: &dyn Fn(f64) -> S,
+        observe: Observe<S>,
+    ) -> Option<Event> {
+        let before = match self.last {
+            Some(v) => v,
+            None => self.value(t0, t0, t1, at, observe),
+        };
+        let after = self.value(t1, t0, t1, at, observe);
+        self.last = Some(after);
+        if !self.crossed(before, after) {
+            return None;
+        }
+
+        // Bisect on the interpolant: `lo` not yet crossed, `hi` crossed
+        let (mut lo, mut hi) = (t0, t1);
+        if self.crossed(before, self.value(t0, t0, t1, at, observe)) {
+            // Crossed exactly at the step boundary
+            hi = t0;
+        }
+        while hi - lo > YEAR_TOL {
+            let mid = 0.5 * (lo + hi);
+            if self.crossed(before, self.value(mid, t0, t1, at, observe)) {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+        Some(Event {
+            name: self.spec.name.clone(),
+            year: hi,
+            value: self.watched(&at(hi), observe),
+            terminal: self.spec.terminal,
+        })
+    }
+}
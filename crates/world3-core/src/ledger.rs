@@ -0,0 +1,125 @@
+//! Flow ledger: cumulative stock flows over a run.
+//!
+//! Each output state carries its instantaneous [`Flows`]. The ledger
+//! integrates them over the output grid with the trapezoidal rule, giving
+//! e.g. total births or total land lost to erosion between two years, and
+//! checks that every stock's change matches its integrated net flow.
+//!
+//! The integral is only as accurate as the output grid: on a coarse
+//! `output_interval` the residuals reflect quadrature error, not a leak in
+//! the model.
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::state::{Flows, WorldState};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FlowLedger {
+    /// Integral of each flow from the first output state, one per state
+    /// [stock units]
+    pub cumulative: Vec<Flows>,
+    /// Stock accounting over the whole run, one per stock in
+    /// [`WorldState::to_vec`] order
+    pub balance: Vec<StockBalance>,
+}
+
+/// Change of one stock over a run against its integrated net flow.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockBalance {
+    pub sector: String,
+    pub variable: String,
+    /// Last value minus first value
+    pub change: f64,
+    /// Integrated inflows minus outflows
+    pub net_flow: f64,
+    /// `change - net_flow`
+    pub residual: f64,
+}
+
+impl FlowLedger {
+    pub fn from_states(states: &[WorldState]) -> Self {
+        let mut cumulative = Vec::with_capacity(states.len());
+        let mut total = vec![0.0; Flows::N];
+        for (i, state) in states.iter().enumerate() {
+            if i > 0 {
+                let prev = &states[i - 1];
+                let h = state.time - prev.time;
+                let (a, b) = (prev.flows.to_vec(), state.flows.to_vec());
+                for k in 0..Flows::N {
+                    total[k] += 0.5 * h * (a[k] + b[k]);
+                }
+            }
+            cumulative.push(Flows::from_vec(&total));
+        }
+
+        let balance = match (states.first(), states.last(), cumulative.last()) {
+            (Some(first), Some(last), Some(flows)) => {
+                let (y0, y1) = (first.to_vec(), last.to_vec());
+                WorldState::STOCK_NAMES
+                    .iter()
+                    .zip(flows.net())
+                    .enumerate()
+                    .map(|(i, ((sector, variable), net_flow))| StockBalance {
+                        sector: sector.to_string(),
+                        variable: variable.to_string(),
+                        change: y1[i] - y0[i],
+                        net_flow,
+                        residual: y1[i] - y0[i] - net_flow,
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+
+        Self {
+            cumulative,
+            balance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::lookup::tables::WorldLookupTables;
+    use crate::model::initial::initial_conditions_1900;
+    use crate::model::params::{ScenarioParams, SolverKind};
+    use crate::output::SimulationOutput;
+    use crate::simulation::{Simulation, StepMethod};
+
+    #[test]
+    fn test_stock_changes_match_integrated_flows() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let params = ScenarioParams {
+            solver: SolverKind::Rk45,
+            output_interval: Some(0.05),
+            ..ScenarioParams::bau()
+        };
+        // Tolerances pinned tighter than the defaults: the RMS error norm
+        // loosens with every stock added, and the interpolated output states
+        // would drift from the integrated ones with it
+        let method = StepMethod::Rk45 { rtol: 1e-7, atol: 1e-10, max_step: 5.0, min_step: 1e-6 };
+        let states =
+            Simulation::with_method(initial_conditions_1900(), params.clone(), tables, method)
+                .unwrap()
+                .collect_states()
+                .unwrap();
+        let output = SimulationOutput::new(states, params);
+
+        for b in &output.ledger.balance {
+            let scale = b.change.abs().max(b.net_flow.abs()).max(1e-12);
+            assert!(
+                b.residual.abs() / scale < 1e-3,
+                "{}.{}: change {:.4e}, net flow {:.4e}",
+                b.sector,
+                b.variable,
+                b.change,
+                b.net_flow
+            );
+        }
+        let century = output.cumulative_flows(2000.0, 2100.0).unwrap();
+        assert!(century.land_erosion > 0.0);
+        assert!(century.births > century.deaths_0_14);
+    }
+}
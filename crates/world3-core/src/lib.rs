@@ -1,10 +1,57 @@
+pub mod attribution;
+pub mod batch;
+pub mod calibration;
+pub mod checkpoint;
+pub mod control;
+pub mod ensemble;
+pub mod events;
+pub mod ledger;
 pub mod lookup;
 pub mod model;
 pub mod output;
+pub mod region;
+mod rng;
+pub mod sensitivity;
+pub mod simulation;
 pub mod solver;
+pub mod stability;
+#[cfg(test)]
+mod test_util;
 
-pub use model::params::{ParameterDescriptor, ScenarioParams};
-pub use model::state::WorldState;
+pub use attribution::{attribute, Attribution, AttributionError, Decomposition, FactorContribution};
+pub use batch::{solve_many, solve_many_with};
+pub use calibration::{
+    calibrate, Bounds, CalibrationError, CalibrationReport, CalibrationSpec, Loss, ObservedSeries,
+    SeriesFit,
+};
+pub use checkpoint::Checkpoint;
+pub use control::{CancelGuard, CancelToken, Progress, RunControl};
+pub use ensemble::{
+    run_ensemble, Distribution, EnsembleError, EnsembleOutput, EnsembleSpec, PercentileBands,
+    TablePerturbation,
+};
+pub use events::{Event, EventCondition, EventSpec, Operand};
+pub use ledger::{FlowLedger, StockBalance};
+pub use model::initial::initial_conditions_1900;
+pub use model::params::{ParameterDescriptor, RegionScale, ScenarioParams, SolverKind};
+pub use model::schedule::Schedule;
+pub use model::state::{Flows, Multipliers, TradeState, WorldState};
 pub use output::SimulationOutput;
+pub use region::{
+    regional_simulation, run_regions, RegionError, RegionSpec, RegionalOutput, RegionalSpec,
+    Regions, TradeSpec,
+};
+pub use sensitivity::{
+    elasticities, elasticities_with, morris, sobol, ElasticitySpec, ElasticityTable, Metric,
+    MorrisIndex, MorrisSpec, SensitivityError, SensitivityResult, SobolIndex, SobolSpec,
+};
+pub use simulation::{Simulation, StepMethod, System, World};
+pub use solver::euler::EulerSolver;
 pub use solver::rk4::Rk4Solver;
-pub use solver::traits::{OdeSolver, SolverError};
+pub use solver::rk45::Rk45Solver;
+pub use solver::build_solver;
+pub use solver::traits::{OdeSolver, PartialTrajectory, SolverError};
+pub use stability::{
+    find_equilibrium, jacobian, linearize, Eigenvalue, Equilibrium, EquilibriumOptions,
+    Linearization, StabilityError,
+};
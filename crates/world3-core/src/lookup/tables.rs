@@ -9,6 +9,7 @@
 use super::LookupTable;
 
 /// All lookup tables used in the World 3 model, loaded once at startup.
+#[derive(Debug, Clone)]
 pub struct WorldLookupTables {
     // --- Population sector ---
     /// Life expectancy multiplier from food (LEMF)
@@ -17,7 +18,7 @@ pub struct WorldLookupTables {
     pub life_exp_multiplier_food: LookupTable,
 
     /// Life expectancy multiplier from health services (LMHS)
-    /// x: effective health services per capita (normalized to 1940 baseline)
+    /// x: effective health services per capita [1975 USD / person / year]
     /// y: multiplier on life expectancy
     pub life_exp_multiplier_health: LookupTable,
 
@@ -41,9 +42,14 @@ pub struct WorldLookupTables {
     /// y: multiplier on fertility
     pub family_planning_multiplier: LookupTable,
 
+    /// Family size multiplier from education (no World 3 counterpart)
+    /// x: education services per capita [1975 USD / person / year]
+    /// y: multiplier on desired family size
+    pub education_family_size_multiplier: LookupTable,
+
     /// Fraction of services for health (FSH)
-    /// x: effective services per capita (normalized)
-    /// y: fraction devoted to health
+    /// x: service output per capita (normalized to 1 at 1970)
+    /// y: fraction devoted to health; the rest funds education
     pub fraction_services_health: LookupTable,
 
     // --- Capital / Industrial sector ---
@@ -62,16 +68,37 @@ pub struct WorldLookupTables {
     /// y: fraction [0..1]
     pub industrial_fraction_to_services: LookupTable,
 
+    // --- Labor sector ---
     /// Jobs per industrial capital unit (JPICU)
-    /// x: industrial output per capita (normalized)
-    /// y: jobs per unit capital
+    /// x: industrial output per capita [1975 USD / person / year]
+    /// y: jobs per 1975 USD of industrial capital
     pub jobs_per_capital: LookupTable,
 
+    /// Jobs per service capital unit (JPSCU)
+    /// x: service output per capita [1975 USD / person / year]
+    /// y: jobs per 1975 USD of service capital
+    pub jobs_per_service_capital: LookupTable,
+
+    /// Jobs per hectare in agriculture (JPH)
+    /// x: agricultural inputs per hectare [1975 USD / hectare / year]
+    /// y: jobs per hectare of arable land
+    pub jobs_per_hectare: LookupTable,
+
     /// Labor force participation (LFP) by age structure
     /// x: fraction population age 15-64
     /// y: labor force fraction
     pub labor_force_participation: LookupTable,
 
+    /// Capacity utilization fraction (CUF)
+    /// x: perceived labor utilization fraction (jobs / labor force)
+    /// y: fraction of capital that can be manned [0..1]
+    pub capacity_utilization: LookupTable,
+
+    /// Labor productivity from education (no World 3 counterpart)
+    /// x: education services per capita [1975 USD / person / year]
+    /// y: output per worker; divides the jobs per unit capital
+    pub labor_productivity_education: LookupTable,
+
     // --- Agriculture sector ---
     /// Land yield multiplier from capital (LYMC)
     /// x: agricultural inputs per hectare (normalized)
@@ -93,6 +120,21 @@ pub struct WorldLookupTables {
     /// y: development cost multiplier
     pub land_development_cost: LookupTable,
 
+    /// Fraction of agricultural inputs allocated to land maintenance (FALM)
+    /// x: food ratio (food per capita / subsistence food)
+    /// y: fraction of inputs [0..1]
+    pub fraction_inputs_to_land_maintenance: LookupTable,
+
+    /// Land fertility regeneration time (LFRT)
+    /// x: fraction of inputs allocated to land maintenance
+    /// y: regeneration time [years]
+    pub land_fertility_regeneration_time: LookupTable,
+
+    /// Land fertility degradation rate (LFDR)
+    /// x: persistent pollution index (1970 = 1)
+    /// y: fraction of land fertility lost per year
+    pub land_fertility_degradation: LookupTable,
+
     /// Food ratio needed for full fertility (FRNF)
     /// x: food per capita / subsistence food
     /// y: fertility fraction
@@ -119,6 +161,60 @@ pub struct WorldLookupTables {
     /// x: persistent pollution index (1970 = 1)
     /// y: assimilation time [years]
     pub pollution_assimilation_time: LookupTable,
+
+    // --- Climate sector ---
+    /// Land yield multiplier from warming (no World 3 counterpart)
+    /// x: surface temperature anomaly [K]
+    /// y: multiplier on land yield
+    pub climate_yield_multiplier: LookupTable,
+
+    /// Life expectancy multiplier from warming (no World 3 counterpart)
+    /// x: surface temperature anomaly [K]
+    /// y: multiplier on life expectancy
+    pub climate_life_expectancy_multiplier: LookupTable,
+
+    // --- Energy sector ---
+    /// Fossil energy return on investment (no World 3 counterpart)
+    /// x: fraction of non-renewable resources remaining
+    /// y: EROI [dimensionless]
+    pub fossil_eroi: LookupTable,
+
+    /// Fraction of industrial output invested in energy capital
+    /// x: energy availability (net supply / demand)
+    /// y: fraction of industrial output
+    pub energy_investment_fraction: LookupTable,
+
+    /// Fraction of potential industrial output the energy supply sustains
+    /// x: energy availability (net supply / demand)
+    /// y: multiplier on industrial output
+    pub energy_output_multiplier: LookupTable,
+
+    // --- Biodiversity sector ---
+    /// Urban-industrial land per capita (World 3 UILPCT)
+    /// x: industrial output per capita [1975 USD/person/yr]
+    /// y: urban-industrial land [hectares/person]
+    pub urban_land_per_capita: LookupTable,
+
+    /// Biodiversity intactness multiplier from pollution (no World 3 counterpart)
+    /// x: persistent pollution index
+    /// y: multiplier on indicated intactness
+    pub biodiversity_pollution_multiplier: LookupTable,
+
+    // --- Welfare sector ---
+    /// GDP per capita from industrial output per capita (World3-03 GDPPC)
+    /// x: industrial output per capita [1975 USD/person/yr]
+    /// y: GDP per capita [1990 USD/person/yr]
+    pub gdp_per_capita: LookupTable,
+
+    /// Life expectancy index (World3-03 LEI)
+    /// x: life expectancy [years]
+    /// y: index [0..1]
+    pub life_expectancy_index: LookupTable,
+
+    /// Education index (World3-03 EI)
+    /// x: GDP per capita [1990 USD/person/yr]
+    /// y: index [0..1]
+    pub education_index: LookupTable,
 }
 
 impl WorldLookupTables {
@@ -139,16 +235,18 @@ impl WorldLookupTables {
             ),
 
             // Life expectancy multiplier from health services
-            // x: service output per capita [1975 USD/person/yr]
+            // x: health services per capita [1975 USD/person/yr]
             //
             // Calibrated accounting for the ~20-year service-capital lag:
             //   actual sopc ≈ equilibrium_sopc / 1.6 due to the depreciation time constant.
-            //   1900: sopc ≈ $200 (initialised at equilibrium) → lem = 0.76, LE ≈ 32 yr ✓
-            //   1970: actual sopc ≈ $500 (lagged from eq $780) → lem ≈ 1.34, LE ≈ 53 yr ✓
+            //   1900: sopc ≈ $200, 34% to health ≈ $68 → lem = 0.76, LE ≈ 32 yr ✓
+            //   1970: sopc ≈ $500, 40% to health ≈ $200 → lem ≈ 1.34, LE ≈ 53 yr ✓
+            // The x points are sopc = 0, 200, …, 1000 times the health fraction there,
+            // so the default split reproduces the curve on total service output.
             // Values < 1 mean poor health services actively reduce life expectancy below BASE.
             life_exp_multiplier_health: LookupTable::new(
                 "life_exp_multiplier_health",
-                vec![0.0, 200.0, 400.0, 600.0, 800.0, 1000.0],
+                vec![0.0, 68.0, 152.0, 252.0, 368.0, 500.0],
                 vec![0.50, 0.76, 1.15, 1.55, 1.78, 2.00],
             ),
 
@@ -188,7 +286,19 @@ impl WorldLookupTables {
                 vec![1.0, 0.90, 0.75, 0.55, 0.40],
             ),
 
+            // Education services and family size
+            // x: education services per capita [1975 USD/person/yr]
+            //
+            // Neutral up to twice the 1970 level (≈ $300/person/yr); beyond it
+            // schooling, above all of women, lowers desired family size by up to 15%.
+            education_family_size_multiplier: LookupTable::new(
+                "education_family_size_multiplier",
+                vec![0.0, 600.0, 1200.0, 2400.0, 4800.0],
+                vec![1.0, 1.0, 0.95, 0.90, 0.85],
+            ),
+
             // Fraction of services for health
+            // x: service output per capita / $500 (1970)
             fraction_services_health: LookupTable::new(
                 "fraction_services_health",
                 vec![0.0, 0.5, 1.0, 1.5, 2.0],
@@ -225,12 +335,40 @@ impl WorldLookupTables {
                 vec![0.30, 0.25, 0.20, 0.15, 0.12],
             ),
 
-            // Jobs per unit of industrial capital
-            // x: industrial output per capita normalized
+            // Jobs per unit of industrial capital — production becomes more
+            // capital-intensive as output per capita rises
+            // x: industrial output per capita [1975 USD/person/yr]
+            //
+            // World 3 JPICU with x scaled ×3 and y ÷3, since this model carries about
+            // three times World 3's capital per capita: IOPC ≈ $300 in 1970 gives
+            // ≈ 1.6e8 industrial jobs.
             jobs_per_capital: LookupTable::new(
                 "jobs_per_capital",
-                vec![0.0, 0.5, 1.0, 2.0, 3.0, 4.0],
-                vec![0.0007, 0.0014, 0.0017, 0.0018, 0.0019, 0.002],
+                vec![150.0, 600.0, 1050.0, 1500.0, 1950.0, 2400.0],
+                vec![1.2e-4, 6.0e-5, 4.0e-5, 3.0e-5, 2.3e-5, 2.0e-5],
+            ),
+
+            // Jobs per unit of service capital
+            // x: service output per capita [1975 USD/person/yr]
+            //
+            // World 3 JPSCU with x scaled ×4 and y ÷4 (SOPC ≈ $500 in 1970 here):
+            // ≈ 3.6e8 service jobs in 1970.
+            jobs_per_service_capital: LookupTable::new(
+                "jobs_per_service_capital",
+                vec![200.0, 800.0, 1400.0, 2000.0, 2600.0, 3200.0],
+                vec![2.8e-4, 1.5e-4, 8.8e-5, 5.0e-5, 3.8e-5, 3.8e-5],
+            ),
+
+            // Jobs per hectare of arable land
+            // x: agricultural inputs per hectare [1975 USD/ha/yr]
+            //
+            // World 3 JPH with x scaled ×2. Inputs start at ≈ $13/ha in 1900 (≈ 0.5
+            // jobs/ha) and pass $60/ha by 1910, after which farms sit at the
+            // mechanized floor of 0.2 jobs/ha: ≈ 30% of all jobs in 1970.
+            jobs_per_hectare: LookupTable::new(
+                "jobs_per_hectare",
+                vec![4.0, 12.0, 20.0, 28.0, 36.0, 44.0, 52.0, 60.0],
+                vec![2.0, 0.5, 0.4, 0.3, 0.27, 0.24, 0.2, 0.2],
             ),
 
             // Labor force participation
@@ -240,6 +378,26 @@ impl WorldLookupTables {
                 vec![0.50, 0.55, 0.60, 0.65],
             ),
 
+            // Capacity utilization — capital goes idle only when jobs outnumber
+            // workers (World 3 CUFT)
+            // x: perceived labor utilization fraction (jobs / labor force)
+            capacity_utilization: LookupTable::new(
+                "capacity_utilization",
+                vec![1.0, 3.0, 5.0, 7.0, 9.0, 11.0],
+                vec![1.0, 0.9, 0.7, 0.3, 0.1, 0.1],
+            ),
+
+            // Labor productivity from education
+            // x: education services per capita [1975 USD/person/yr]
+            //
+            // Neutral up to the 1970 level; a better-schooled workforce mans up to
+            // 15% more capital per worker.
+            labor_productivity_education: LookupTable::new(
+                "labor_productivity_education",
+                vec![0.0, 300.0, 1000.0, 2000.0, 4000.0],
+                vec![1.0, 1.0, 1.05, 1.10, 1.15],
+            ),
+
             // Land yield multiplier from capital inputs
             // x: agricultural inputs per hectare (normalized to 1 at 1970)
             land_yield_multiplier_capital: LookupTable::new(
@@ -272,6 +430,35 @@ impl WorldLookupTables {
                 vec![100.0, 117.0, 137.0, 161.0, 192.0, 232.0, 282.0, 344.0, 418.0, 507.0, 616.0],
             ),
 
+            // Land maintenance — a well-fed world can afford to spend part of its
+            // inputs on keeping the soil fertile
+            // Source: Meadows 1974, FALMT
+            // x: food per capita / subsistence food per capita
+            fraction_inputs_to_land_maintenance: LookupTable::new(
+                "fraction_inputs_to_land_maintenance",
+                vec![0.0, 1.0, 2.0, 3.0, 4.0],
+                vec![0.0, 0.04, 0.07, 0.09, 0.10],
+            ),
+
+            // Land fertility regeneration time
+            // Source: Meadows 1974, LFRTT
+            // x: fraction of inputs allocated to land maintenance
+            land_fertility_regeneration_time: LookupTable::new(
+                "land_fertility_regeneration_time",
+                vec![0.0, 0.02, 0.04, 0.06, 0.08, 0.10],
+                vec![20.0, 13.0, 8.0, 4.0, 2.0, 2.0],
+            ),
+
+            // Land fertility degradation from pollution
+            // World3 LFDRT, with the x axis stretched to this model's pollution
+            // index, which runs several times higher than World3's PPOLX
+            // x: persistent pollution index
+            land_fertility_degradation: LookupTable::new(
+                "land_fertility_degradation",
+                vec![0.0, 10.0, 20.0, 30.0, 60.0],
+                vec![0.0, 0.01, 0.03, 0.05, 0.10],
+            ),
+
             // Food ratio effect on fertility
             // x: food per capita / subsistence food per capita
             food_fertility_multiplier: LookupTable::new(
@@ -318,6 +505,142 @@ impl WorldLookupTables {
                 vec![0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 60.0],
                 vec![20.0, 45.0, 90.0, 150.0, 220.0, 320.0, 480.0],
             ),
+
+            // Land yield damage from warming — heat stress, drought and shifting
+            // growing seasons; negligible up to 1 K, about −15% at 4 K
+            // x: surface temperature anomaly [K]
+            climate_yield_multiplier: LookupTable::new(
+                "climate_yield_multiplier",
+                vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+                vec![1.0, 1.0, 0.97, 0.92, 0.85, 0.76, 0.65],
+            ),
+
+            // Life expectancy damage from warming — heat mortality and disease;
+            // negligible up to 1 K, about −6% at 4 K
+            // x: surface temperature anomaly [K]
+            climate_life_expectancy_multiplier: LookupTable::new(
+                "climate_life_expectancy_multiplier",
+                vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+                vec![1.0, 1.0, 0.99, 0.97, 0.94, 0.90, 0.85],
+            ),
+
+            // Fossil EROI — ≈ 30 for the easy early deposits, falling steeply
+            // as the remaining ones get deeper and poorer
+            // x: fraction of non-renewable resources remaining
+            fossil_eroi: LookupTable::new(
+                "fossil_eroi",
+                vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0],
+                vec![1.5, 3.0, 6.0, 12.0, 20.0, 30.0],
+            ),
+
+            // Energy investment — 4% of output at balance, more under shortage,
+            // none once supply is well ahead of demand
+            // x: energy availability
+            energy_investment_fraction: LookupTable::new(
+                "energy_investment_fraction",
+                vec![0.8, 0.9, 1.0, 1.1, 1.2, 1.4],
+                vec![0.08, 0.06, 0.04, 0.025, 0.015, 0.0],
+            ),
+
+            // Output under energy shortage — concave, since the least valuable
+            // uses are cut first
+            // x: energy availability
+            energy_output_multiplier: LookupTable::new(
+                "energy_output_multiplier",
+                vec![0.0, 0.25, 0.5, 0.75, 1.0],
+                vec![0.0, 0.35, 0.62, 0.84, 1.0],
+            ),
+
+            // Urban-industrial land per capita
+            // Source: Meadows 1974, UILPCT
+            // x: industrial output per capita [1975 USD/person/yr]
+            urban_land_per_capita: LookupTable::new(
+                "urban_land_per_capita",
+                vec![0.0, 200.0, 400.0, 600.0, 800.0, 1000.0, 1200.0, 1400.0, 1600.0],
+                vec![0.005, 0.008, 0.015, 0.025, 0.04, 0.055, 0.07, 0.08, 0.09],
+            ),
+
+            // Biodiversity loss to pollution — toxic and nutrient loads; −4% at
+            // an index of 10, −30% at 200
+            // x: persistent pollution index
+            biodiversity_pollution_multiplier: LookupTable::new(
+                "biodiversity_pollution_multiplier",
+                vec![0.0, 10.0, 50.0, 100.0, 200.0],
+                vec![1.0, 0.96, 0.90, 0.82, 0.70],
+            ),
+
+            // GDP per capita
+            // Source: Meadows, Randers & Meadows 2004, World3-03 GDPPC
+            // x: industrial output per capita [1975 USD/person/yr]
+            gdp_per_capita: LookupTable::new(
+                "gdp_per_capita",
+                vec![0.0, 200.0, 400.0, 600.0, 800.0, 1000.0],
+                vec![120.0, 600.0, 1200.0, 1800.0, 2500.0, 3200.0],
+            ),
+
+            // Life expectancy index — linear from 25 to 85 years
+            // Source: World3-03 LEI
+            // x: life expectancy [years]
+            life_expectancy_index: LookupTable::new(
+                "life_expectancy_index",
+                vec![25.0, 35.0, 45.0, 55.0, 65.0, 75.0, 85.0],
+                vec![0.0, 0.16, 0.33, 0.50, 0.67, 0.84, 1.0],
+            ),
+
+            // Education index — saturates with income
+            // Source: World3-03 EI
+            // x: GDP per capita [1990 USD/person/yr]
+            education_index: LookupTable::new(
+                "education_index",
+                vec![0.0, 1000.0, 2000.0, 3000.0, 4000.0, 5000.0, 6000.0, 7000.0],
+                vec![0.0, 0.81, 0.88, 0.92, 0.95, 0.98, 0.99, 1.0],
+            ),
         }
     }
+
+    /// Every table, for perturbation or inspection by name.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut LookupTable> {
+        [
+            &mut self.life_exp_multiplier_food,
+            &mut self.life_exp_multiplier_health,
+            &mut self.life_exp_multiplier_crowding,
+            &mut self.life_exp_multiplier_pollution,
+            &mut self.desired_family_size,
+            &mut self.family_planning_multiplier,
+            &mut self.education_family_size_multiplier,
+            &mut self.fraction_services_health,
+            &mut self.capital_output_ratio_resources,
+            &mut self.industrial_fraction_to_agriculture,
+            &mut self.industrial_fraction_to_services,
+            &mut self.jobs_per_capital,
+            &mut self.jobs_per_service_capital,
+            &mut self.jobs_per_hectare,
+            &mut self.labor_force_participation,
+            &mut self.capacity_utilization,
+            &mut self.labor_productivity_education,
+            &mut self.land_yield_multiplier_capital,
+            &mut self.land_yield_multiplier_pollution,
+            &mut self.land_erosion_multiplier,
+            &mut self.land_development_cost,
+            &mut self.fraction_inputs_to_land_maintenance,
+            &mut self.land_fertility_regeneration_time,
+            &mut self.land_fertility_degradation,
+            &mut self.food_fertility_multiplier,
+            &mut self.capital_fraction_resource_extraction,
+            &mut self.pollution_generation_industry,
+            &mut self.pollution_generation_agriculture,
+            &mut self.pollution_assimilation_time,
+            &mut self.climate_yield_multiplier,
+            &mut self.climate_life_expectancy_multiplier,
+            &mut self.fossil_eroi,
+            &mut self.energy_investment_fraction,
+            &mut self.energy_output_multiplier,
+            &mut self.urban_land_per_capita,
+            &mut self.biodiversity_pollution_multiplier,
+            &mut self.gdp_per_capita,
+            &mut self.life_expectancy_index,
+            &mut self.education_index,
+        ]
+        .into_iter()
+    }
 }
@@ -1,13 +1,21 @@
 //! Top-level derivative function: `dy/dt = f(t, y, params)`.
 //!
-//! This function computes the rate of change for all 10 ODE stocks.
+//! This function computes the rate of change for all 19 ODE stocks.
 //! Sector computation order is fixed to satisfy dependencies:
 //!
 //!   1. Resources (other sectors need fraction_remaining for cost multiplier)
-//!   2. Capital    (depends on resource fraction; produces industrial_output)
-//!   3. Agriculture (depends on industrial_output for inputs; depends on pollution)
-//!   4. Population  (depends on food, services, pollution)
-//!   5. Pollution   (depends on industrial_output, agricultural_inputs)
+//!   2. Energy supply (depends on resource fraction for fossil EROI)
+//!   3. Capital    (depends on resource fraction and energy supply; produces industrial_output)
+//!   4. Energy     (depends on industrial_output for investment)
+//!   5. Agriculture (depends on industrial_output for inputs; depends on pollution)
+//!   6. Population  (depends on food, services, pollution)
+//!   7. Pollution   (depends on industrial_output, agricultural_inputs)
+//!   8. Labor       (depends on capital, land and inputs for jobs; cohorts for labor force)
+//!   9. Climate     (depends on fossil energy for emissions)
+//!  10. Biodiversity (depends on land use and pollution)
+//!
+//! Welfare has no stocks and feeds nothing back, so only `compute_auxiliaries`
+//! runs it, last.
 //!
 //! The function takes a `&WorldState` (read-only) and produces a new
 //! `WorldState` that represents the derivatives (stocks are rates of change).
@@ -16,8 +24,11 @@
 use crate::lookup::tables::WorldLookupTables;
 use crate::model::{
     params::ScenarioParams,
-    sectors::{agriculture, capital, pollution, population, resources},
-    state::WorldState,
+    sectors::{
+        agriculture, biodiversity, capital, climate, energy, labor, pollution, population,
+        resources, welfare,
+    },
+    state::{Flows, WorldState},
 };
 
 /// Compute `dy/dt` for the full World 3 state vector.
@@ -25,13 +36,33 @@ use crate::model::{
 /// Returns a `WorldState` where all stock fields hold *rates of change*
 /// (units: [stock_unit / year]), not values. The `time` field is unused.
 ///
-/// Auxiliary fields on the returned state are zeroed — only the 10 ODE
-/// stocks (cohorts, capitals, arable land, resources, pollution) carry data.
+/// Auxiliary fields on the returned state are zeroed — only the 19 ODE
+/// stocks (cohorts, capitals, arable land and its fertility, resources,
+/// pollution, perceived labor utilization, carbon, temperature, energy
+/// capital and biodiversity) carry data.
 pub fn derivatives(
     state: &WorldState,
     params: &ScenarioParams,
     tables: &WorldLookupTables,
 ) -> WorldState {
+    evaluate(state, params, tables).0
+}
+
+/// Every stock in- and outflow at `state` — the rates [`derivatives`]
+/// combines into `dy/dt`.
+pub fn flows(state: &WorldState, params: &ScenarioParams, tables: &WorldLookupTables) -> Flows {
+    evaluate(state, params, tables).1
+}
+
+/// One pass over the sectors, returning both the derivatives and the flows.
+pub(crate) fn evaluate(
+    state: &WorldState,
+    params: &ScenarioParams,
+    tables: &WorldLookupTables,
+) -> (WorldState, Flows) {
+    // Levers in effect at this time (scheduled ones resolved)
+    let params = &*params.at(state.time);
+
     // Work on a mutable copy so sectors can fill in auxiliary fields
     let mut s = state.clone();
 
@@ -40,20 +71,38 @@ pub fn derivatives(
     resources::compute_resource_auxiliaries(&mut s, tables);
     let d_nnr = resources::resource_derivative(&s, params, tables);
 
-    // --- Step 2: Capital ---
+    // --- Step 2: Energy supply ---
+    // (capital sector checks demand against it)
+    energy::compute_energy_supply(&mut s, tables);
+
+    // --- Step 3: Capital ---
     let cap_deriv = capital::capital_derivatives(&mut s, params, tables);
 
-    // --- Step 3: Agriculture ---
+    // --- Step 4: Energy ---
+    // (must run before climate, which emits from fossil energy)
+    let energy_deriv = energy::energy_derivatives(&mut s, params);
+
+    // --- Step 5: Agriculture ---
     // (food_per_capita is needed by population and must be current)
     let agri_deriv = agriculture::agriculture_derivatives(&mut s, params, tables);
 
-    // --- Step 4: Pollution ---
+    // --- Step 6: Pollution ---
     // (pollution_index must be updated before population uses it)
     let d_pollution = pollution::pollution_derivative(&mut s, params, tables);
 
-    // --- Step 5: Population ---
+    // --- Step 7: Population ---
     let pop_deriv = population::population_derivatives(&mut s, params, tables);
 
+    // --- Step 8: Labor ---
+    let d_perceived_utilization = labor::labor_derivative(&mut s, tables);
+
+    // --- Step 9: Climate ---
+    // (damages read the temperature stock, so only emissions need this pass)
+    let climate_deriv = climate::climate_derivatives(&mut s, params);
+
+    // --- Step 10: Biodiversity ---
+    let bio_deriv = biodiversity::biodiversity_derivative(&mut s, params, tables);
+
     // --- Build derivative state ---
     let mut d = WorldState::zero_derivative(state.time);
 
@@ -67,10 +116,82 @@ pub fn derivatives(
 
     d.agriculture.arable_land = agri_deriv.d_arable_land;
     d.agriculture.potentially_arable_land = agri_deriv.d_potentially_arable_land;
+    d.agriculture.land_fertility = agri_deriv.d_land_fertility;
 
     d.resources.nonrenewable_resources = d_nnr;
 
     d.pollution.persistent_pollution = d_pollution;
 
-    d
+    d.labor.perceived_utilization = d_perceived_utilization;
+
+    d.climate.atmospheric_carbon = climate_deriv.d_atmospheric_carbon;
+    d.climate.sink_carbon = climate_deriv.d_sink_carbon;
+    d.climate.temperature_anomaly = climate_deriv.d_temperature_anomaly;
+    d.climate.deep_ocean_temperature = climate_deriv.d_deep_ocean_temperature;
+
+    d.energy.fossil_capital = energy_deriv.d_fossil_capital;
+    d.energy.renewable_capital = energy_deriv.d_renewable_capital;
+
+    d.biodiversity.intactness = bio_deriv.d_intactness;
+
+    let flows = Flows {
+        births: pop_deriv.births,
+        deaths_0_14: pop_deriv.deaths[0],
+        deaths_15_44: pop_deriv.deaths[1],
+        deaths_45_64: pop_deriv.deaths[2],
+        deaths_65_plus: pop_deriv.deaths[3],
+        aging_0_to_15: pop_deriv.aging[0],
+        aging_15_to_45: pop_deriv.aging[1],
+        aging_45_to_65: pop_deriv.aging[2],
+        net_migration: pop_deriv.net_migration,
+        industrial_investment: cap_deriv.industrial_investment,
+        industrial_depreciation: cap_deriv.industrial_depreciation,
+        service_investment: cap_deriv.service_investment,
+        service_depreciation: cap_deriv.service_depreciation,
+        land_development: agri_deriv.land_development,
+        land_erosion: agri_deriv.land_erosion,
+        land_fertility_degradation: agri_deriv.fertility_degradation,
+        land_fertility_regeneration: agri_deriv.fertility_regeneration,
+        resource_extraction: -d_nnr,
+        pollution_generation: s.pollution.generation_rate,
+        pollution_assimilation: s.pollution.assimilation_rate,
+        labor_utilization_adjustment: d_perceived_utilization,
+        carbon_emissions: climate_deriv.carbon_emissions,
+        carbon_uptake: climate_deriv.carbon_uptake,
+        surface_warming: climate_deriv.d_temperature_anomaly,
+        deep_ocean_warming: climate_deriv.d_deep_ocean_temperature,
+        fossil_energy_investment: energy_deriv.fossil_investment,
+        fossil_energy_depreciation: energy_deriv.fossil_depreciation,
+        renewable_energy_investment: energy_deriv.renewable_investment,
+        renewable_energy_depreciation: energy_deriv.renewable_depreciation,
+        biodiversity_loss: bio_deriv.loss,
+        biodiversity_recovery: bio_deriv.recovery,
+    };
+
+    (d, flows)
+}
+
+/// Recompute every auxiliary field of `state` from its stocks.
+///
+/// Runs the sectors in the same order as [`derivatives`], so the stored
+/// trajectory carries populated `food_per_capita`, `industrial_output`,
+/// `pollution_index`, `life_expectancy`, etc. The derivative structs are
+/// discarded.
+pub fn compute_auxiliaries(
+    state: &mut WorldState,
+    params: &ScenarioParams,
+    tables: &WorldLookupTables,
+) {
+    let params = &*params.at(state.time);
+    resources::compute_resource_auxiliaries(state, tables);
+    energy::compute_energy_supply(state, tables);
+    capital::capital_derivatives(state, params, tables);
+    energy::energy_derivatives(state, params);
+    agriculture::agriculture_derivatives(state, params, tables);
+    pollution::pollution_derivative(state, params, tables);
+    population::population_derivatives(state, params, tables);
+    labor::labor_derivative(state, tables);
+    climate::climate_derivatives(state, params);
+    biodiversity::biodiversity_derivative(state, params, tables);
+    welfare::compute_welfare(state, tables);
 }
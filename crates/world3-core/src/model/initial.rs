@@ -0,0 +1,87 @@
+//! Initial conditions for the World 3 standard run.
+
+use crate::model::sectors::agriculture::LAND_YIELD_1900;
+use crate::model::state::{
+    AgricultureState, BiodiversityState, CapitalState, ClimateState, EnergyState, Flows,
+    InequalityState, LaborState, Multipliers, PollutionState, PopulationState, ResourceState,
+    TradeState, WelfareState, WorldState,
+};
+
+/// World 3 initial conditions for year 1900.
+/// Values calibrated to broadly match Meadows 1972 standard run starting point.
+pub fn initial_conditions_1900() -> WorldState {
+    WorldState {
+        time: 1900.0,
+        population: PopulationState {
+            population: 1.6e9,
+            // 1900 age structure: young population with small elderly cohort
+            cohort_0_14: 0.60e9,   // 37.5% — high fertility, high child mortality era
+            cohort_15_44: 0.65e9,  // 40.6%
+            cohort_45_64: 0.27e9,  // 16.9%
+            cohort_65_plus: 0.08e9, // 5.0% — small elderly cohort in 1900
+            ..Default::default()
+        },
+        capital: CapitalState {
+            industrial_capital: 0.2e12,  // 1975 USD
+            // Service capital pre-set to its ~1900 equilibrium.
+            // At industrial_output ≈ $133B and frac_to_services ≈ 0.12:
+            //   service_capital_eq = 133e9 × 0.12 / 0.05 ≈ 0.32e12
+            //   → sopc ≈ $200/yr → lem_health ≈ 0.76 → LE ≈ 32 yr ✓
+            service_capital: 0.32e12,
+            ..Default::default()
+        },
+        agriculture: AgricultureState {
+            arable_land: 0.9e9,            // hectares
+            potentially_arable_land: 2.3e9,
+            land_fertility: LAND_YIELD_1900, // undamaged soil
+            food_per_capita: 400.0,        // initial estimate; overwritten by agriculture sector
+            ..Default::default()
+        },
+        resources: ResourceState {
+            nonrenewable_resources: 1.0,   // 100% remaining in 1900
+            fraction_remaining: 1.0,
+        },
+        pollution: PollutionState {
+            // pollution_index = persistent_pollution (reference_stock = 1.0)
+            // Small in 1900; rises to ~1 by 1970, ~10–30 peak around 2030–2050 in BAU.
+            persistent_pollution: 0.05,
+            pollution_index: 0.05,
+            ..Default::default()
+        },
+        labor: LaborState {
+            // Jobs ≈ labor force in 1900 (5.6e8 / 4.9e8); the brief early excess
+            // idles < 1% of capacity before utilization falls to ≈ 0.6 by 1920
+            perceived_utilization: 1.0,
+            ..Default::default()
+        },
+        climate: ClimateState {
+            // 296 ppm; the sink has taken up ≈ 40 GtC of land-use and early
+            // fossil carbon, so uptake roughly matches 1900 emissions
+            atmospheric_carbon: 628.7,
+            sink_carbon: 40.0,
+            // Surface ≈ 0.1 K above preindustrial, deep ocean not yet warmed
+            temperature_anomaly: 0.1,
+            deep_ocean_temperature: 0.0,
+            ..Default::default()
+        },
+        energy: EnergyState {
+            // Coal-era supply a little ahead of the ≈ 27 EJ/yr 1900 output needs:
+            //   5.7e10 / 2e9 × (1 − 1/30) + 2.7e9 / 4e9 × 0.9 ≈ 28 EJ/yr → availability ≈ 1.05
+            fossil_capital: 5.7e10,
+            renewable_capital: 2.7e9,
+            ..Default::default()
+        },
+        biodiversity: BiodiversityState {
+            // At its indicated level: 28% of the convertible land already
+            // farmed or built on → 1 − 0.35 × 0.28 ≈ 0.90
+            intactness: 0.90,
+            ..Default::default()
+        },
+        welfare: WelfareState::default(),
+        inequality: InequalityState::default(),
+        trade: TradeState::default(),
+        // Filled in once auxiliaries are computed
+        flows: Flows::default(),
+        multipliers: Multipliers::default(),
+    }
+}
@@ -1,4 +1,6 @@
 pub mod params;
+pub mod schedule;
 pub mod sectors;
 pub mod state;
 pub mod derivatives;
+pub mod initial;
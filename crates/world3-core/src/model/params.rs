@@ -1,8 +1,13 @@
 //! Scenario parameters — the "policy levers" exposed as sliders in the UI.
 //! Each field has documented units, range, and default value.
 
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::model::schedule::Schedule;
+
 /// All adjustable parameters for a simulation scenario.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScenarioParams {
@@ -15,6 +20,10 @@ pub struct ScenarioParams {
     pub family_planning_efficacy: f64,
     /// Health services investment multiplier [0.5..3.0, default 1.0]
     pub health_investment_multiplier: f64,
+    /// Shift of the share of service output spent on health; the rest funds
+    /// education [−0.3..0.3, default 0.0]
+    #[serde(default)]
+    pub health_share_shift: f64,
 
     // ---- Capital / technology ----
     /// Industrial capital depreciation rate [yr⁻¹, 0.02..0.10, default 0.05]
@@ -23,7 +32,8 @@ pub struct ScenarioParams {
     pub service_depreciation_rate: f64,
     /// Technology progress rate (TFP growth multiplier) [0..0.03, default 0.002]
     pub technology_growth_rate: f64,
-    /// Fraction of industrial output reinvested in industry [0..0.4, default 0.12]
+    /// Fraction of industrial output reinvested in industrial and energy capital
+    /// [0..0.4, default 0.15]; energy takes `frac_to_energy` of it
     pub investment_rate: f64,
 
     // ---- Agriculture ----
@@ -44,13 +54,118 @@ pub struct ScenarioParams {
     /// Pollution control policy strength [0..1, default 0.0]
     pub pollution_control: f64,
 
+    // ---- Climate ----
+    /// Annual decline of the carbon emitted per unit of fossil energy after 1970
+    /// [yr⁻¹, 0..0.05, default 0.01]
+    #[serde(default = "default_carbon_intensity_decline")]
+    pub carbon_intensity_decline: f64,
+    /// Fraction of CO2 emissions captured at the source [0..1, default 0.0]
+    #[serde(default)]
+    pub carbon_capture: f64,
+
+    // ---- Energy ----
+    /// Annual growth of the renewable share of energy investment after 1970
+    /// [yr⁻¹, 0..0.05, default 0.005]
+    #[serde(default = "default_energy_transition_rate")]
+    pub energy_transition_rate: f64,
+
+    // ---- Distribution ----
+    /// Gini coefficient of income between the income groups [0..0.7, default 0.0]
+    #[serde(default)]
+    pub income_inequality: f64,
+    /// Number of equal-sized income groups the population is split into
+    /// [default 5, quintiles]
+    #[serde(default = "default_income_groups")]
+    pub income_groups: u32,
+
+    // ---- Region ----
+    /// Share of the world this run covers; 1.0 for the whole world outside
+    /// regional runs (see [`crate::region`])
+    #[serde(default)]
+    pub region: RegionScale,
+
     // ---- Solver configuration ----
     /// Simulation start year [default 1900.0]
     pub start_year: f64,
     /// Simulation end year [default 2100.0]
     pub end_year: f64,
-    /// Time step [years, default 1.0]
+    /// Integration time step [years, default 1.0]
     pub time_step: f64,
+    /// Spacing of the reported states [years, default: `time_step`]
+    #[serde(default)]
+    pub output_interval: Option<f64>,
+    /// Integration method [default rk4]
+    #[serde(default)]
+    pub solver: SolverKind,
+
+    // ---- Policy schedules ----
+    /// Time-varying levers, by field name; a scheduled lever ignores its
+    /// constant value [default none]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub schedules: BTreeMap<String, Schedule>,
+}
+
+/// Size of a region relative to the world, scaling the sectors' world-sized
+/// references.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RegionScale {
+    /// Share of the world population; scales the 1970 population behind the
+    /// service allocation [0..1, default 1.0]
+    pub population: f64,
+    /// Share of the world's land; scales potentially arable land, crowding,
+    /// biocapacity and the pollution index [0..1, default 1.0]
+    pub land: f64,
+    /// Share of the world's 1900 nonrenewable resources; the resource stock
+    /// stays normalized to the region's own endowment [0..1, default 1.0]
+    pub resources: f64,
+}
+
+impl Default for RegionScale {
+    fn default() -> Self {
+        Self {
+            population: 1.0,
+            land: 1.0,
+            resources: 1.0,
+        }
+    }
+}
+
+/// Integration method used to run a scenario.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SolverKind {
+    /// Fixed-step 4th-order Runge-Kutta at `time_step`
+    #[default]
+    Rk4,
+    /// Adaptive Dormand–Prince 5(4), reported on the output grid
+    Rk45,
+    /// DYNAMO-compatible explicit Euler at DT = 0.5, reported on the output grid
+    Euler,
+}
+
+fn default_carbon_intensity_decline() -> f64 {
+    0.01
+}
+
+fn default_energy_transition_rate() -> f64 {
+    0.005
+}
+
+fn default_income_groups() -> u32 {
+    5
+}
+
+impl std::str::FromStr for SolverKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rk4" => Ok(Self::Rk4),
+            "rk45" => Ok(Self::Rk45),
+            "euler" => Ok(Self::Euler),
+            other => Err(format!("Unknown solver '{}'. Use: rk4, rk45, euler", other)),
+        }
+    }
 }
 
 impl Default for ScenarioParams {
@@ -60,20 +175,187 @@ impl Default for ScenarioParams {
             family_planning_year: 2000.0,
             family_planning_efficacy: 0.75,
             health_investment_multiplier: 1.0,
+            health_share_shift: 0.0,
             industrial_depreciation_rate: 0.05,
             service_depreciation_rate: 0.05,
             technology_growth_rate: 0.002,
-            investment_rate: 0.12,
+            investment_rate: 0.15,
             agricultural_technology: 1.0,
             land_protection_fraction: 0.0,
             subsistence_food_per_capita: 230.0,
             resource_efficiency: 1.0,
             initial_nnr_fraction: 1.0,
             pollution_control: 0.0,
+            carbon_intensity_decline: default_carbon_intensity_decline(),
+            carbon_capture: 0.0,
+            energy_transition_rate: default_energy_transition_rate(),
+            income_inequality: 0.0,
+            income_groups: default_income_groups(),
+            region: RegionScale::default(),
             start_year: 1900.0,
             end_year: 2100.0,
             time_step: 1.0,
+            output_interval: None,
+            solver: SolverKind::default(),
+            schedules: BTreeMap::new(),
+        }
+    }
+}
+
+impl ScenarioParams {
+    /// Levers that accept a [`Schedule`].
+    pub const SCHEDULABLE: [&'static str; 16] = [
+        "family_planning_efficacy",
+        "health_investment_multiplier",
+        "health_share_shift",
+        "industrial_depreciation_rate",
+        "service_depreciation_rate",
+        "technology_growth_rate",
+        "investment_rate",
+        "agricultural_technology",
+        "land_protection_fraction",
+        "subsistence_food_per_capita",
+        "resource_efficiency",
+        "pollution_control",
+        "carbon_intensity_decline",
+        "carbon_capture",
+        "energy_transition_rate",
+        "income_inequality",
+    ];
+
+    /// Fields `set_value` accepts: the levers plus `family_planning_year`.
+    pub const TUNABLE: [&'static str; 17] = [
+        "family_planning_year",
+        "family_planning_efficacy",
+        "health_investment_multiplier",
+        "health_share_shift",
+        "industrial_depreciation_rate",
+        "service_depreciation_rate",
+        "technology_growth_rate",
+        "investment_rate",
+        "agricultural_technology",
+        "land_protection_fraction",
+        "subsistence_food_per_capita",
+        "resource_efficiency",
+        "pollution_control",
+        "carbon_intensity_decline",
+        "carbon_capture",
+        "energy_transition_rate",
+        "income_inequality",
+    ];
+
+    /// Schedulable lever by field name. Returns `None` for any other field.
+    pub fn lever(&self, field: &str) -> Option<f64> {
+        Some(match field {
+            "family_planning_efficacy" => self.family_planning_efficacy,
+            "health_investment_multiplier" => self.health_investment_multiplier,
+            "health_share_shift" => self.health_share_shift,
+            "industrial_depreciation_rate" => self.industrial_depreciation_rate,
+            "service_depreciation_rate" => self.service_depreciation_rate,
+            "technology_growth_rate" => self.technology_growth_rate,
+            "investment_rate" => self.investment_rate,
+            "agricultural_technology" => self.agricultural_technology,
+            "land_protection_fraction" => self.land_protection_fraction,
+            "subsistence_food_per_capita" => self.subsistence_food_per_capita,
+            "resource_efficiency" => self.resource_efficiency,
+            "pollution_control" => self.pollution_control,
+            "carbon_intensity_decline" => self.carbon_intensity_decline,
+            "carbon_capture" => self.carbon_capture,
+            "energy_transition_rate" => self.energy_transition_rate,
+            "income_inequality" => self.income_inequality,
+            _ => return None,
+        })
+    }
+
+    /// Any tunable parameter — a lever or `family_planning_year` — by field
+    /// name (e.g. `"subsistence_food_per_capita"`). Returns `None` for other
+    /// fields.
+    pub fn value(&self, field: &str) -> Option<f64> {
+        match field {
+            "family_planning_year" => Some(self.family_planning_year),
+            _ => self.lever(field),
+        }
+    }
+
+    /// Set a schedulable lever by field name. Returns `false` for any other
+    /// field.
+    pub fn set_lever(&mut self, field: &str, value: f64) -> bool {
+        let lever = match field {
+            "family_planning_efficacy" => &mut self.family_planning_efficacy,
+            "health_investment_multiplier" => &mut self.health_investment_multiplier,
+            "health_share_shift" => &mut self.health_share_shift,
+            "industrial_depreciation_rate" => &mut self.industrial_depreciation_rate,
+            "service_depreciation_rate" => &mut self.service_depreciation_rate,
+            "technology_growth_rate" => &mut self.technology_growth_rate,
+            "investment_rate" => &mut self.investment_rate,
+            "agricultural_technology" => &mut self.agricultural_technology,
+            "land_protection_fraction" => &mut self.land_protection_fraction,
+            "subsistence_food_per_capita" => &mut self.subsistence_food_per_capita,
+            "resource_efficiency" => &mut self.resource_efficiency,
+            "pollution_control" => &mut self.pollution_control,
+            "carbon_intensity_decline" => &mut self.carbon_intensity_decline,
+            "carbon_capture" => &mut self.carbon_capture,
+            "energy_transition_rate" => &mut self.energy_transition_rate,
+            "income_inequality" => &mut self.income_inequality,
+            _ => return false,
+        };
+        *lever = value;
+        true
+    }
+
+    /// Set any tunable parameter — a lever or `family_planning_year` — by
+    /// field name. Returns `false` for other fields.
+    pub fn set_value(&mut self, field: &str, value: f64) -> bool {
+        match field {
+            "family_planning_year" => {
+                self.family_planning_year = value;
+                true
+            }
+            _ => self.set_lever(field, value),
+        }
+    }
+
+    /// Check that every schedule targets a schedulable lever and can be
+    /// evaluated.
+    pub fn validate_schedules(&self) -> Result<(), String> {
+        for (field, schedule) in &self.schedules {
+            if !Self::SCHEDULABLE.contains(&field.as_str()) {
+                return Err(format!(
+                    "'{}' cannot be scheduled. Schedulable levers: {}",
+                    field,
+                    Self::SCHEDULABLE.join(", ")
+                ));
+            }
+            schedule.validate().map_err(|e| format!("schedule for '{}': {}", field, e))?;
         }
+        Ok(())
+    }
+
+    /// Parameters in effect in `year`: scheduled levers take their scheduled
+    /// value. Borrows `self` when nothing is scheduled.
+    ///
+    /// Runs on every derivative evaluation, so the owned copy takes only the
+    /// plain numeric fields; its `meta` and `schedules` are left empty rather
+    /// than cloned.
+    pub fn at(&self, year: f64) -> Cow<'_, ScenarioParams> {
+        if self.schedules.is_empty() {
+            return Cow::Borrowed(self);
+        }
+        let mut params = ScenarioParams {
+            meta: ScenarioMeta {
+                id: String::new(),
+                name: String::new(),
+                description: String::new(),
+                color_hex: String::new(),
+                created_at: String::new(),
+            },
+            schedules: BTreeMap::new(),
+            ..*self
+        };
+        for (field, schedule) in &self.schedules {
+            params.set_lever(field, schedule.value_at(year));
+        }
+        Cow::Owned(params)
     }
 }
 
@@ -171,9 +453,15 @@ pub struct ParameterDescriptor {
     /// Sector group for UI grouping
     pub sector: String,
     pub description: String,
+    /// Whether the lever accepts a time-varying schedule
+    /// (`ScenarioParams::schedules`)
+    pub schedulable: bool,
 }
 
 /// Return the full parameter schema for the API.
+///
+/// Levers marked `schedulable` may instead be given a [`Schedule`] in
+/// `ScenarioParams::schedules`.
 pub fn parameter_descriptors() -> Vec<ParameterDescriptor> {
     vec![
         ParameterDescriptor {
@@ -183,6 +471,7 @@ pub fn parameter_descriptors() -> Vec<ParameterDescriptor> {
             min: 1950.0, max: 2100.0, default: 2000.0, step: 5.0,
             sector: "population".into(),
             description: "Year at which family planning reaches full effectiveness.".into(),
+            schedulable: false,
         },
         ParameterDescriptor {
             field: "family_planning_efficacy".into(),
@@ -191,6 +480,7 @@ pub fn parameter_descriptors() -> Vec<ParameterDescriptor> {
             min: 0.0, max: 1.0, default: 0.75, step: 0.05,
             sector: "population".into(),
             description: "Maximum reduction in desired family size from family planning programs.".into(),
+            schedulable: true,
         },
         ParameterDescriptor {
             field: "health_investment_multiplier".into(),
@@ -199,6 +489,16 @@ pub fn parameter_descriptors() -> Vec<ParameterDescriptor> {
             min: 0.5, max: 3.0, default: 1.0, step: 0.1,
             sector: "population".into(),
             description: "Scales health services spending, affecting life expectancy.".into(),
+            schedulable: true,
+        },
+        ParameterDescriptor {
+            field: "health_share_shift".into(),
+            label: "Health vs. Education".into(),
+            unit: "fraction".into(),
+            min: -0.3, max: 0.3, default: 0.0, step: 0.05,
+            sector: "population".into(),
+            description: "Shifts service output from education (negative) to health (positive).".into(),
+            schedulable: true,
         },
         ParameterDescriptor {
             field: "industrial_depreciation_rate".into(),
@@ -207,6 +507,7 @@ pub fn parameter_descriptors() -> Vec<ParameterDescriptor> {
             min: 0.02, max: 0.10, default: 0.05, step: 0.005,
             sector: "capital".into(),
             description: "Annual fraction of industrial capital that wears out.".into(),
+            schedulable: true,
         },
         ParameterDescriptor {
             field: "technology_growth_rate".into(),
@@ -215,14 +516,16 @@ pub fn parameter_descriptors() -> Vec<ParameterDescriptor> {
             min: 0.0, max: 0.03, default: 0.002, step: 0.001,
             sector: "capital".into(),
             description: "Annual improvement in industrial output per unit capital.".into(),
+            schedulable: true,
         },
         ParameterDescriptor {
             field: "investment_rate".into(),
             label: "Investment Rate".into(),
             unit: "fraction".into(),
-            min: 0.0, max: 0.4, default: 0.12, step: 0.01,
+            min: 0.0, max: 0.4, default: 0.15, step: 0.01,
             sector: "capital".into(),
-            description: "Fraction of industrial output reinvested in industrial capital.".into(),
+            description: "Fraction of industrial output reinvested in industrial and energy capital.".into(),
+            schedulable: true,
         },
         ParameterDescriptor {
             field: "agricultural_technology".into(),
@@ -231,6 +534,7 @@ pub fn parameter_descriptors() -> Vec<ParameterDescriptor> {
             min: 0.5, max: 3.0, default: 1.0, step: 0.1,
             sector: "agriculture".into(),
             description: "Multiplier on land yield — represents crop improvements, irrigation.".into(),
+            schedulable: true,
         },
         ParameterDescriptor {
             field: "land_protection_fraction".into(),
@@ -239,6 +543,7 @@ pub fn parameter_descriptors() -> Vec<ParameterDescriptor> {
             min: 0.0, max: 0.5, default: 0.0, step: 0.05,
             sector: "agriculture".into(),
             description: "Fraction of arable land protected from degradation and overuse.".into(),
+            schedulable: true,
         },
         ParameterDescriptor {
             field: "resource_efficiency".into(),
@@ -247,6 +552,7 @@ pub fn parameter_descriptors() -> Vec<ParameterDescriptor> {
             min: 1.0, max: 5.0, default: 1.0, step: 0.25,
             sector: "resources".into(),
             description: "Reduces resource use per unit of industrial output.".into(),
+            schedulable: true,
         },
         ParameterDescriptor {
             field: "pollution_control".into(),
@@ -255,6 +561,67 @@ pub fn parameter_descriptors() -> Vec<ParameterDescriptor> {
             min: 0.0, max: 1.0, default: 0.0, step: 0.05,
             sector: "pollution".into(),
             description: "Fraction by which pollution generation is reduced per unit output.".into(),
+            schedulable: true,
+        },
+        ParameterDescriptor {
+            field: "carbon_intensity_decline".into(),
+            label: "Decarbonization Rate".into(),
+            unit: "yr⁻¹".into(),
+            min: 0.0, max: 0.05, default: 0.01, step: 0.005,
+            sector: "climate".into(),
+            description: "Annual decline in CO2 emitted per unit of fossil energy after 1970.".into(),
+            schedulable: true,
+        },
+        ParameterDescriptor {
+            field: "carbon_capture".into(),
+            label: "Carbon Capture".into(),
+            unit: "fraction".into(),
+            min: 0.0, max: 1.0, default: 0.0, step: 0.05,
+            sector: "climate".into(),
+            description: "Fraction of CO2 emissions captured and stored at the source.".into(),
+            schedulable: true,
+        },
+        ParameterDescriptor {
+            field: "energy_transition_rate".into(),
+            label: "Energy Transition Speed".into(),
+            unit: "yr⁻¹".into(),
+            min: 0.0, max: 0.05, default: 0.005, step: 0.005,
+            sector: "energy".into(),
+            description: "Annual growth of the renewable share of energy investment after 1970.".into(),
+            schedulable: true,
+        },
+        ParameterDescriptor {
+            field: "income_inequality".into(),
+            label: "Income Inequality".into(),
+            unit: "Gini".into(),
+            min: 0.0, max: 0.7, default: 0.0, step: 0.05,
+            sector: "population".into(),
+            description: "Gini coefficient of income between income groups; the poorest eat and live less.".into(),
+            schedulable: true,
         },
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_and_set_value_cover_the_same_fields() {
+        let mut params = ScenarioParams::bau();
+        for (i, field) in ScenarioParams::TUNABLE.iter().enumerate() {
+            assert!(params.set_value(field, i as f64 + 0.5), "{}", field);
+            assert_eq!(params.value(field), Some(i as f64 + 0.5), "{}", field);
+            assert_eq!(
+                params.lever(field).is_some(),
+                ScenarioParams::SCHEDULABLE.contains(field),
+                "{}",
+                field
+            );
+        }
+        for field in ["investment_rte", "end_year", "income_groups", "meta"] {
+            assert_eq!(params.value(field), None);
+            assert!(!params.set_value(field, 1.0));
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Time-varying policy levers.
+//!
+//! A [`Schedule`] replaces a constant lever in `ScenarioParams` with a
+//! function of the simulation year, e.g. "pollution_control 0 until 2025,
+//! ramping to 0.8 by 2040". Schedules are keyed by field name in
+//! `ScenarioParams::schedules` and resolved by `ScenarioParams::at` before
+//! every derivative evaluation, so the sectors keep reading plain fields.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Schedule {
+    /// `before` until `year`, `after` from `year` on
+    Step { year: f64, before: f64, after: f64 },
+    /// `from` until `start`, linear to `to` at `end`, `to` afterwards
+    Ramp { start: f64, end: f64, from: f64, to: f64 },
+    /// Linear interpolation between `[year, value]` points (sorted by
+    /// year), flat outside them
+    Piecewise { points: Vec<(f64, f64)> },
+}
+
+impl Schedule {
+    /// Lever value in `year`. Never panics; schedules that fail
+    /// [`Schedule::validate`] give meaningless values (NaN for an empty
+    /// piecewise curve).
+    pub fn value_at(&self, year: f64) -> f64 {
+        match self {
+            Schedule::Step { year: at, before, after } => {
+                if year < *at {
+                    *before
+                } else {
+                    *after
+                }
+            }
+            Schedule::Ramp { start, end, from, to } => {
+                if year <= *start {
+                    *from
+                } else if year >= *end {
+                    *to
+                } else {
+                    from + (to - from) * (year - start) / (end - start)
+                }
+            }
+            Schedule::Piecewise { points } => {
+                let (Some(&first), Some(&last)) = (points.first(), points.last()) else {
+                    return f64::NAN;
+                };
+                if year <= first.0 {
+                    return first.1;
+                }
+                if year >= last.0 {
+                    return last.1;
+                }
+                // Clamped so unsorted points cannot index out of bounds
+                let i = points.partition_point(|p| p.0 <= year).clamp(1, points.len() - 1);
+                let ((x0, y0), (x1, y1)) = (points[i - 1], points[i]);
+                y0 + (y1 - y0) * (year - x0) / (x1 - x0)
+            }
+        }
+    }
+
+    /// Reject schedules `value_at` cannot evaluate.
+    pub fn validate(&self) -> Result<(), String> {
+        let finite = |values: &[f64]| values.iter().all(|v| v.is_finite());
+        match self {
+            Schedule::Step { year, before, after } if !finite(&[*year, *before, *after]) => {
+                Err("step schedule values must be finite".into())
+            }
+            Schedule::Ramp { start, end, from, to } => {
+                if !finite(&[*start, *end, *from, *to]) {
+                    Err("ramp schedule values must be finite".into())
+                } else if end <= start {
+                    Err(format!("ramp end {end} must be after start {start}"))
+                } else {
+                    Ok(())
+                }
+            }
+            Schedule::Piecewise { points } => {
+                if points.is_empty() {
+                    Err("piecewise schedule needs at least one point".into())
+                } else if !points.iter().all(|(x, y)| finite(&[*x, *y])) {
+                    Err("piecewise schedule values must be finite".into())
+                } else if points.windows(2).any(|w| w[1].0 <= w[0].0) {
+                    Err("piecewise schedule years must be strictly increasing".into())
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Parse `step:YEAR:BEFORE:AFTER`, `ramp:START:END:FROM:TO` or
+/// `piecewise:YEAR=VALUE,YEAR=VALUE,…`.
+impl std::str::FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let usage = || {
+            format!(
+                "Invalid schedule '{}'. Use: step:YEAR:BEFORE:AFTER, ramp:START:END:FROM:TO, \
+                 piecewise:YEAR=VALUE,YEAR=VALUE,...",
+                s
+            )
+        };
+        let num = |t: &str| t.trim().parse::<f64>().map_err(|_| usage());
+        let parts: Vec<&str> = s.split(':').collect();
+        let schedule = match parts.as_slice() {
+            ["step", year, before, after] => Schedule::Step {
+                year: num(year)?,
+                before: num(before)?,
+                after: num(after)?,
+            },
+            ["ramp", start, end, from, to] => Schedule::Ramp {
+                start: num(start)?,
+                end: num(end)?,
+                from: num(from)?,
+                to: num(to)?,
+            },
+            ["piecewise", points] => Schedule::Piecewise {
+                points: points
+                    .split(',')
+                    .map(|p| {
+                        let (year, value) = p.split_once('=').ok_or_else(usage)?;
+                        Ok((num(year)?, num(value)?))
+                    })
+                    .collect::<Result<_, String>>()?,
+            },
+            _ => return Err(usage()),
+        };
+        schedule.validate()?;
+        Ok(schedule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_values() {
+        let ramp: Schedule = "ramp:2025:2040:0:0.8".parse().unwrap();
+        assert_eq!(ramp.value_at(2000.0), 0.0);
+        assert!((ramp.value_at(2032.5) - 0.4).abs() < 1e-12);
+        assert_eq!(ramp.value_at(2100.0), 0.8);
+
+        let step: Schedule = "step:2030:0.12:0.2".parse().unwrap();
+        assert_eq!(step.value_at(2029.9), 0.12);
+        assert_eq!(step.value_at(2030.0), 0.2);
+
+        let curve: Schedule = "piecewise:2000=1,2050=3,2100=2".parse().unwrap();
+        assert_eq!(curve.value_at(1950.0), 1.0);
+        assert!((curve.value_at(2025.0) - 2.0).abs() < 1e-12);
+        assert!((curve.value_at(2075.0) - 2.5).abs() < 1e-12);
+        assert_eq!(curve.value_at(2150.0), 2.0);
+
+        assert!(Schedule::Piecewise { points: vec![] }.value_at(2000.0).is_nan());
+        let unsorted = Schedule::Piecewise { points: vec![(2050.0, 1.0), (2000.0, 2.0)] };
+        assert!(unsorted.value_at(2025.0).is_finite());
+
+        assert!("piecewise:2050=1,2000=2".parse::<Schedule>().is_err());
+        assert!("ramp:2040:2025:0:1".parse::<Schedule>().is_err());
+    }
+
+    #[test]
+    fn test_scheduled_lever_takes_effect_at_its_start() {
+        use crate::model::{initial::initial_conditions_1900, params::ScenarioParams};
+        use crate::simulation::Simulation;
+        use crate::test_util::{run, tables};
+
+        let mut params = ScenarioParams::bau();
+        let bau = run(params.clone()).states;
+        params
+            .schedules
+            .insert("pollution_control".into(), "ramp:2025:2040:0:0.8".parse().unwrap());
+        let scheduled = run(params.clone()).states;
+        let resolved = params.at(2032.5);
+        assert!((resolved.pollution_control - 0.4).abs() < 1e-12);
+        assert!(resolved.schedules.is_empty() && resolved.meta.name.is_empty());
+
+        // Identical until the ramp starts, less pollution once it is in place
+        assert_eq!(scheduled[125].to_vec(), bau[125].to_vec());
+        let pollution = |s: &[crate::WorldState]| s[160].pollution.persistent_pollution;
+        assert!(pollution(&scheduled) < pollution(&bau));
+
+        params.schedules.insert("end_year".into(), "step:2000:2100:2050".parse().unwrap());
+        let result = Simulation::new(initial_conditions_1900(), params, tables());
+        assert!(matches!(result, Err(crate::SolverError::InvalidInitial(_))));
+
+        // Rejected before the constructor evaluates it
+        let mut params = ScenarioParams::bau();
+        params.schedules.insert("pollution_control".into(), Schedule::Piecewise { points: vec![] });
+        let result = Simulation::new(initial_conditions_1900(), params, tables());
+        assert!(matches!(result, Err(crate::SolverError::InvalidInitial(_))));
+    }
+}
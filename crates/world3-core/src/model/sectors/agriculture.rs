@@ -1,29 +1,45 @@
 //! Agricultural sector.
 //!
 //! Food production depends on the amount of arable land under cultivation
-//! and the yield per hectare. Yield is enhanced by capital inputs (fertilizer,
-//! machinery) and degraded by pollution. Arable land stock changes as
-//! new land is developed (from potentially-arable reserves) and existing
-//! land degrades.
+//! and the yield per hectare. Yield is the land's fertility, enhanced by
+//! capital inputs (fertilizer, machinery) and degraded by pollution and
+//! warming. Arable land stock changes as new land is developed (from
+//! potentially-arable reserves) and existing land degrades.
+//!
+//! Land fertility is a stock, as in World3: pollution and intensive farming
+//! wear it down, and it regenerates toward its inherent level, faster the
+//! more of the inputs go to land maintenance. Soil damage outlasts its cause.
 
 use crate::lookup::tables::WorldLookupTables;
 use crate::model::{params::ScenarioParams, state::WorldState};
 
-/// Base land yield in 1900 [kg / hectare / year]
-const LAND_YIELD_1900: f64 = 600.0;
-/// Total potential arable land area [hectares] (estimate based on FAO)
+/// Base land yield in 1900, and the inherent land fertility the soil
+/// regenerates toward [kg / hectare / year]
+pub(crate) const LAND_YIELD_1900: f64 = 600.0;
+/// Total potential arable land area of the world [hectares] (estimate based on FAO)
 const TOTAL_POTENTIAL_ARABLE: f64 = 3.2e9;
 /// Land development time [years] — delay between investment decision and land available
 const LAND_DEVELOPMENT_TIME: f64 = 10.0;
 /// Normal land erosion fraction [yr⁻¹] — fraction of land that degrades under normal use
 const LAND_EROSION_RATE: f64 = 0.002;
+/// Fertility lost per year for each unit the inputs raise the yield multiplier
+/// above 1 [yr⁻¹] — intensive farming mines the soil
+const FARMING_DEGRADATION_RATE: f64 = 0.002;
 
 pub struct AgricultureDerivatives {
     pub d_arable_land: f64,
     pub d_potentially_arable_land: f64,
+    pub d_land_fertility: f64,
+    // Flows behind the derivatives [hectares / year]
+    pub land_development: f64,
+    pub land_erosion: f64,
+    // [kg / hectare / year per year]
+    pub fertility_degradation: f64,
+    pub fertility_regeneration: f64,
 }
 
-/// Compute agricultural derivatives and update auxiliary fields on `state.agriculture`.
+/// Compute agricultural derivatives and update auxiliary fields on
+/// `state.agriculture` and the agriculture multipliers.
 pub fn agriculture_derivatives(
     state: &mut WorldState,
     params: &ScenarioParams,
@@ -40,29 +56,62 @@ pub fn agriculture_derivatives(
     };
     let frac_to_agri = tables.industrial_fraction_to_agriculture.eval(food_ratio);
     let agri_output_total = state.capital.industrial_output * frac_to_agri;
+    state.multipliers.frac_to_agriculture = frac_to_agri;
 
     let arable = state.agriculture.arable_land.max(1.0);
     let agri_inputs_per_ha = agri_output_total / arable;
     state.agriculture.agricultural_inputs_per_hectare = agri_inputs_per_ha;
 
     // ---- Land yield ----
-    let yield_multiplier_capital = tables
-        .land_yield_multiplier_capital
-        .eval(agri_inputs_per_ha);
     let yield_multiplier_pollution = tables
         .land_yield_multiplier_pollution
         .eval(state.pollution.pollution_index);
-
-    let land_yield = LAND_YIELD_1900
-        * yield_multiplier_capital
+    let yield_multiplier_climate = tables
+        .climate_yield_multiplier
+        .eval(state.climate.temperature_anomaly);
+    let land_fertility = state.agriculture.land_fertility.max(0.0);
+    let yield_before_inputs = land_fertility
         * yield_multiplier_pollution
+        * yield_multiplier_climate
         * params.agricultural_technology;
+
+    // ---- Land maintenance ----
+    // Part of the inputs keeps the soil fertile instead of raising this year's
+    // yield. How much depends on the food the land would give with all of them,
+    // which, unlike the food per capita above, is known from this pass's stocks.
+    let potential_food = arable
+        * yield_before_inputs
+        * tables.land_yield_multiplier_capital.eval(agri_inputs_per_ha);
+    let potential_food_ratio = if params.subsistence_food_per_capita > 0.0 {
+        (potential_food + state.trade.food_imports).max(0.0)
+            / pop
+            / params.subsistence_food_per_capita
+    } else {
+        1.0
+    };
+    let frac_maintenance = tables
+        .fraction_inputs_to_land_maintenance
+        .eval(potential_food_ratio);
+    state.multipliers.frac_to_land_maintenance = frac_maintenance;
+
+    let yield_multiplier_capital = tables
+        .land_yield_multiplier_capital
+        .eval(agri_inputs_per_ha * (1.0 - frac_maintenance));
+
+    state.multipliers.yield_multiplier_capital = yield_multiplier_capital;
+    state.multipliers.yield_multiplier_pollution = yield_multiplier_pollution;
+    state.multipliers.yield_multiplier_climate = yield_multiplier_climate;
+    state.multipliers.agricultural_technology = params.agricultural_technology;
+    state.multipliers.land_fertility_ratio = land_fertility / LAND_YIELD_1900;
+
+    let land_yield = yield_before_inputs * yield_multiplier_capital;
     state.agriculture.land_yield = land_yield;
 
     // ---- Food production ----
     let food = arable * land_yield;
     state.agriculture.food = food;
-    state.agriculture.food_per_capita = food / pop;
+    // Consumption includes the net food imports of a regional run
+    state.agriculture.food_per_capita = (food + state.trade.food_imports).max(0.0) / pop;
 
     // ---- Land development ----
     // New land is developed when food pressure is high and potentially-arable land exists
@@ -70,10 +119,11 @@ pub fn agriculture_derivatives(
 
     // Development cost rises as better land is used up
     let land_fraction_developed =
-        1.0 - potentially_arable / TOTAL_POTENTIAL_ARABLE.max(1.0);
+        1.0 - potentially_arable / (TOTAL_POTENTIAL_ARABLE * params.region.land).max(1.0);
     let dev_cost_multiplier = tables
         .land_development_cost
         .eval(land_fraction_developed.clamp(0.0, 1.0));
+    state.multipliers.land_development_cost = dev_cost_multiplier;
 
     // Food pressure: if food < subsistence, invest more in land development
     let land_development_desired =
@@ -90,11 +140,58 @@ pub fn agriculture_derivatives(
         1.0
     };
     let erosion_mult = tables.land_erosion_multiplier.eval(land_yield_ratio);
+    state.multipliers.erosion_multiplier = erosion_mult;
     let protected_fraction = params.land_protection_fraction.clamp(0.0, 0.5);
     let erosion_rate = arable * LAND_EROSION_RATE * erosion_mult * (1.0 - protected_fraction);
 
+    // ---- Land fertility ----
+    let degradation_rate = tables
+        .land_fertility_degradation
+        .eval(state.pollution.pollution_index)
+        + FARMING_DEGRADATION_RATE * (yield_multiplier_capital - 1.0).max(0.0);
+    let fertility_degradation = land_fertility * degradation_rate;
+    let regeneration_time = tables
+        .land_fertility_regeneration_time
+        .eval(frac_maintenance);
+    state.multipliers.fertility_regeneration_time = regeneration_time;
+    let fertility_regeneration = (LAND_YIELD_1900 - land_fertility) / regeneration_time;
+
     AgricultureDerivatives {
         d_arable_land: land_development_rate - erosion_rate,
         d_potentially_arable_land: -land_development_rate,
+        d_land_fertility: fertility_regeneration - fertility_degradation,
+        land_development: land_development_rate,
+        land_erosion: erosion_rate,
+        fertility_degradation,
+        fertility_regeneration,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::initial::initial_conditions_1900;
+
+    #[test]
+    fn test_soil_damage_outlasts_pollution() {
+        let tables = WorldLookupTables::load();
+        let params = ScenarioParams::bau();
+
+        // Heavy pollution wears the soil down
+        let mut polluted = initial_conditions_1900();
+        polluted.pollution.pollution_index = 60.0;
+        let d = agriculture_derivatives(&mut polluted, &params, &tables);
+        assert!(d.fertility_degradation > 0.09 * LAND_YIELD_1900);
+        assert!(d.d_land_fertility < 0.0);
+
+        // Once it is gone, damaged land still yields less and only slowly recovers
+        let mut clean = initial_conditions_1900();
+        let mut damaged = initial_conditions_1900();
+        damaged.agriculture.land_fertility = 0.5 * LAND_YIELD_1900;
+        agriculture_derivatives(&mut clean, &params, &tables);
+        let d = agriculture_derivatives(&mut damaged, &params, &tables);
+        assert!((damaged.agriculture.land_yield / clean.agriculture.land_yield - 0.5).abs() < 1e-9);
+        assert!(d.d_land_fertility > 0.0);
+        assert!(d.fertility_regeneration < 0.5 * LAND_YIELD_1900 / 2.0);
     }
 }
@@ -0,0 +1,123 @@
+//! Biodiversity sector.
+//!
+//! World3-03's human ecological footprint: the arable land, urban-industrial
+//! land and the land needed to absorb the pollution generated, over Earth's
+//! biocapacity. Biodiversity intactness is a stock that follows the level the
+//! current land conversion and pollution would indicate — quickly when it is
+//! lost, slowly when habitat is given back.
+
+use crate::lookup::tables::WorldLookupTables;
+use crate::model::{params::ScenarioParams, state::WorldState};
+
+/// Earth's biocapacity [global hectares] (World3-03 TL)
+const BIOCAPACITY: f64 = 1.91e9;
+/// Land needed to absorb one pollution unit a year [hectares / (unit / yr)].
+/// Calibrated so the BAU footprint crosses 1 around 1980, as in World3-03.
+const ABSORPTION_LAND_PER_UNIT: f64 = 2.9e9;
+
+/// Land the model can convert: arable plus potentially arable land in 1900
+/// [hectares]
+const CONVERTIBLE_LAND: f64 = 3.2e9;
+/// Intactness lost when all of it is converted (the rest survives in
+/// farmland, field margins and cities)
+const LAND_CONVERSION_IMPACT: f64 = 0.35;
+
+/// Adjustment time when intactness is above the indicated level [years]
+const LOSS_TIME: f64 = 10.0;
+/// Adjustment time when it is below, as habitat regenerates [years]
+const RECOVERY_TIME: f64 = 50.0;
+
+pub struct BiodiversityDerivative {
+    pub d_intactness: f64,
+    // Flows behind the derivative [1 / year]
+    pub loss: f64,
+    pub recovery: f64,
+}
+
+/// Compute `d(intactness)/dt` and update the footprint fields on
+/// `state.biodiversity`.
+///
+/// Must run after the agriculture and pollution sectors (land use and the
+/// pollution generation rate).
+pub fn biodiversity_derivative(
+    state: &mut WorldState,
+    params: &ScenarioParams,
+    tables: &WorldLookupTables,
+) -> BiodiversityDerivative {
+    // A region's share of the world's biocapacity and convertible land
+    let biocapacity = BIOCAPACITY * params.region.land;
+    let convertible_land = CONVERTIBLE_LAND * params.region.land;
+
+    // ---- Ecological footprint ----
+    let urban_land = state.population.population
+        * tables
+            .urban_land_per_capita
+            .eval(state.capital.industrial_output_per_capita);
+    let absorption_land = state.pollution.generation_rate.max(0.0) * ABSORPTION_LAND_PER_UNIT;
+    state.biodiversity.urban_land = urban_land;
+    state.biodiversity.absorption_land = absorption_land;
+    state.biodiversity.ecological_footprint =
+        (state.agriculture.arable_land + urban_land + absorption_land) / biocapacity;
+
+    // ---- Intactness ----
+    // Land no longer potentially arable has been farmed, eroded or built on
+    let converted = ((convertible_land - state.agriculture.potentially_arable_land + urban_land)
+        / convertible_land)
+        .clamp(0.0, 1.0);
+    let indicated = (1.0 - LAND_CONVERSION_IMPACT * converted)
+        * tables
+            .biodiversity_pollution_multiplier
+            .eval(state.pollution.pollution_index);
+    state.biodiversity.indicated_intactness = indicated;
+
+    let gap = indicated - state.biodiversity.intactness;
+    let (loss, recovery) = if gap < 0.0 {
+        (-gap / LOSS_TIME, 0.0)
+    } else {
+        (0.0, gap / RECOVERY_TIME)
+    };
+
+    BiodiversityDerivative {
+        d_intactness: recovery - loss,
+        loss,
+        recovery,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::params::ScenarioParams;
+    use crate::test_util::run;
+
+    #[test]
+    fn test_footprint_overshoots_and_intactness_declines() {
+        let bau = run(ScenarioParams::bau());
+        let footprint = |year| bau.state_at_year(year).unwrap().biodiversity.ecological_footprint;
+        assert!(footprint(1970.0) < 1.0);
+        assert!(footprint(2000.0) > 1.0);
+
+        let intactness = |year| bau.state_at_year(year).unwrap().biodiversity.intactness;
+        assert!(intactness(2000.0) < 0.87);
+        assert!(intactness(2100.0) < intactness(2000.0) - 0.05);
+
+        // Welfare peaks while output still grows, then falls with life expectancy
+        let hwi = bau.extract_series("welfare.human_welfare_index");
+        let (peak, max) = hwi
+            .iter()
+            .enumerate()
+            .fold((0, 0.0), |acc, (i, &v)| if v > acc.1 { (i, v) } else { acc });
+        assert!((0.5..1.0).contains(&max));
+        assert!((2000.0..2030.0).contains(&bau.timeline[peak]));
+
+        // Less pollution: smaller footprint, more biodiversity left
+        let mut params = ScenarioParams::bau();
+        params.pollution_control = 0.5;
+        let controlled = run(params);
+        let (a, b) = (
+            bau.state_at_year(2050.0).unwrap(),
+            controlled.state_at_year(2050.0).unwrap(),
+        );
+        assert!(b.biodiversity.ecological_footprint < a.biodiversity.ecological_footprint);
+        assert!(b.biodiversity.intactness > a.biodiversity.intactness);
+    }
+}
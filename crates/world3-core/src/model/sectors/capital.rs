@@ -2,12 +2,18 @@
 //!
 //! Capital grows through investment (a fraction of industrial output) and
 //! declines through depreciation. As non-renewable resources deplete,
-//! the capital-output ratio rises, reducing effective production.
+//! the capital-output ratio rises, reducing effective production. Output is
+//! also scaled by the capacity utilization fraction when there are too few
+//! workers to man the capital (see the labor sector), and by the energy
+//! constraint when energy supply falls short (see the energy sector). Energy
+//! capital is funded out of the reinvestment share, so industrial capital
+//! gets what is left of it. Service output is split between health and
+//! education.
 //!
 //! Reference year for normalizations: 1970.
 
 use crate::lookup::tables::WorldLookupTables;
-use crate::model::{params::ScenarioParams, state::WorldState};
+use crate::model::{params::ScenarioParams, sectors::energy, state::WorldState};
 
 /// Industrial capital output ratio in 1970 [1975 USD capital / 1975 USD output / yr]
 const ICOR_1970: f64 = 3.0;
@@ -15,16 +21,24 @@ const ICOR_1970: f64 = 3.0;
 const SCOR_1970: f64 = 1.0;
 /// Reference population for per-capita normalizations [persons]
 const POP_REFERENCE: f64 = 3.6e9; // 1970 world population
+/// Service output per capita in 1970 [1975 USD / person / year]
+const SOPC_1970: f64 = 500.0;
 
 pub struct CapitalDerivatives {
     pub d_industrial_capital: f64,
     pub d_service_capital: f64,
+    // Flows behind the derivatives [1975 USD / year]
+    pub industrial_investment: f64,
+    pub industrial_depreciation: f64,
+    pub service_investment: f64,
+    pub service_depreciation: f64,
 }
 
 /// Compute d(industrial_capital)/dt and d(service_capital)/dt.
 ///
 /// Also fills in the derived auxiliary fields on `state.capital`:
-/// `industrial_output`, `industrial_output_per_capita`, `service_output_per_capita`.
+/// `industrial_output`, `industrial_output_per_capita`, `service_output_per_capita`,
+/// its health and education shares, and the capital multipliers.
 pub fn capital_derivatives(
     state: &mut WorldState,
     params: &ScenarioParams,
@@ -55,7 +69,21 @@ pub fn capital_derivatives(
         * (1.0 - capital_for_resources.clamp(0.0, 0.95))
         * tech_multiplier;
 
-    let industrial_output = (productive_capital / icor).max(0.0);
+    state.multipliers.cor_multiplier = cor_multiplier;
+    state.multipliers.tech_multiplier = tech_multiplier;
+    state.multipliers.capital_fraction_resources = capital_for_resources;
+
+    // Capital left unmanned when jobs outnumber workers (perceived, so it
+    // reads the labor sector's delay stock rather than this step's labor)
+    let capacity_utilization = tables
+        .capacity_utilization
+        .eval(state.labor.perceived_utilization);
+    state.labor.capacity_utilization = capacity_utilization;
+
+    // Output the capital could produce, cut back when energy is short
+    let potential_output = (productive_capital * capacity_utilization / icor).max(0.0);
+    let industrial_output =
+        potential_output * energy::energy_constraint(state, tables, potential_output);
     state.capital.industrial_output = industrial_output;
 
     let iopc = industrial_output / pop;
@@ -63,9 +91,22 @@ pub fn capital_derivatives(
 
     // ----- Service output -----
     let scor = SCOR_1970;
-    let service_output = (state.capital.service_capital / scor).max(0.0);
+    let service_output = (state.capital.service_capital * capacity_utilization / scor).max(0.0);
     state.capital.service_output_per_capita = service_output / pop;
 
+    // Health takes a growing share as services expand; the lever shifts it
+    // and education gets the rest
+    let frac_services_health = (tables
+        .fraction_services_health
+        .eval(state.capital.service_output_per_capita / SOPC_1970)
+        + params.health_share_shift)
+        .clamp(0.0, 1.0);
+    state.multipliers.frac_services_health = frac_services_health;
+    state.capital.health_services_per_capita =
+        state.capital.service_output_per_capita * frac_services_health;
+    state.capital.education_services_per_capita =
+        state.capital.service_output_per_capita * (1.0 - frac_services_health);
+
     // ----- Allocation fractions -----
     // How much of industrial output goes to agriculture vs services vs reinvestment?
     let food_ratio = if params.subsistence_food_per_capita > 0.0 {
@@ -79,13 +120,21 @@ pub fn capital_derivatives(
         .eval(food_ratio);
 
     let spc_normalized = state.capital.service_output_per_capita
-        / (industrial_output / POP_REFERENCE).max(1e-9);
+        / (industrial_output / (POP_REFERENCE * params.region.population)).max(1e-9);
     let frac_to_services = tables
         .industrial_fraction_to_services
         .eval(spc_normalized);
+    state.multipliers.frac_to_services = frac_to_services;
+
+    // Energy capital is paid for out of the reinvestment share; more of it
+    // goes to energy when supply falls short of demand
+    let frac_to_energy = tables
+        .energy_investment_fraction
+        .eval(state.energy.energy_availability);
+    state.multipliers.frac_to_energy = frac_to_energy;
 
     // Remaining fraction available for capital reinvestment
-    let frac_to_investment = params.investment_rate;
+    let frac_to_investment = (params.investment_rate - frac_to_energy).max(0.0);
 
     // ----- Industrial capital dynamics -----
     let investment = industrial_output * frac_to_investment;
@@ -105,5 +154,61 @@ pub fn capital_derivatives(
     CapitalDerivatives {
         d_industrial_capital: d_industrial,
         d_service_capital: d_service,
+        industrial_investment: investment,
+        industrial_depreciation: depreciation_i,
+        service_investment,
+        service_depreciation: depreciation_s,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::derivatives::{compute_auxiliaries, derivatives, flows};
+    use crate::model::{initial::initial_conditions_1900, params::ScenarioParams, state::Flows};
+    use crate::test_util::{run, tables};
+
+    #[test]
+    fn test_education_share_lowers_fertility() {
+        let bau = run(ScenarioParams::bau());
+        let capital = &bau.state_at_year(2050.0).unwrap().capital;
+        let split = capital.health_services_per_capita + capital.education_services_per_capita;
+        assert!((split - capital.service_output_per_capita).abs() < 1e-9 * split);
+
+        // BAU education stays below the $600 where it starts to lower family
+        // size; Technology passes it
+        let technology = run(ScenarioParams::comprehensive_technology());
+        let mut params = ScenarioParams::comprehensive_technology();
+        params.health_share_shift = -0.2;
+        let education = run(params);
+        let (a, b) = (
+            technology.state_at_year(1980.0).unwrap(),
+            education.state_at_year(1980.0).unwrap(),
+        );
+        assert!(b.multipliers.education_fertility < a.multipliers.education_fertility);
+        assert!(b.multipliers.labor_productivity > a.multipliers.labor_productivity);
+        assert!(b.population.life_expectancy < a.population.life_expectancy);
+    }
+
+    #[test]
+    fn test_energy_investment_comes_out_of_industrial_reinvestment() {
+        let params = ScenarioParams::bau();
+        let mut state = initial_conditions_1900();
+        compute_auxiliaries(&mut state, &params, &tables());
+        let mut costly = (*tables()).clone();
+        costly.energy_investment_fraction.y.iter_mut().for_each(|y| *y += 0.02);
+
+        let (a, b) = (flows(&state, &params, &tables()), flows(&state, &params, &costly));
+        let energy = |f: &Flows| f.fossil_energy_investment + f.renewable_energy_investment;
+        let output = state.capital.industrial_output;
+        assert!((energy(&b) - energy(&a) - 0.02 * output).abs() < 1e-9 * output);
+
+        // Energy and industrial capital share one reinvestment budget, so
+        // every dollar to energy is one less for industry
+        for f in [&a, &b] {
+            let total = f.industrial_investment + energy(f);
+            assert!((total - params.investment_rate * output).abs() < 1e-9 * output);
+        }
+        let growth = |t| derivatives(&state, &params, t).capital.industrial_capital;
+        assert!(growth(&costly) < growth(&tables()) - 0.019 * output);
     }
 }
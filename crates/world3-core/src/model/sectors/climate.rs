@@ -0,0 +1,149 @@
+//! Climate sector.
+//!
+//! Fossil energy use emits CO2 into a two-box
+//! carbon cycle: the atmosphere exchanges its excess over the preindustrial
+//! level with a combined ocean and land sink. The concentration sets the
+//! radiative forcing of a two-layer energy-balance model (surface and deep
+//! ocean), whose surface temperature anomaly feeds damage multipliers into
+//! land yield and life expectancy in the respective sectors.
+
+use crate::model::{params::ScenarioParams, state::WorldState};
+
+/// Preindustrial atmospheric carbon [GtC] (280 ppm)
+pub(crate) const PREINDUSTRIAL_CARBON: f64 = 594.7;
+/// Lower bound of the temperature anomalies [K]. They are signed stocks:
+/// strong carbon capture can cool below preindustrial. Like the upper stock
+/// bounds, this is a sanity limit, not a model constraint.
+pub(crate) const MIN_TEMPERATURE_ANOMALY: f64 = -100.0;
+/// Atmospheric carbon per ppm of CO2 [GtC / ppm]
+const GTC_PER_PPM: f64 = 2.124;
+
+/// Carbon emitted per unit of fossil energy in 1970 [GtC / EJ].
+/// Calibrated: 1970 fossil energy ≈ 145 EJ/yr → ≈ 4 GtC/yr, close to the
+/// fossil record (1900's ≈ 26 EJ/yr → ≈ 0.7 GtC/yr, against 0.5 recorded).
+const CARBON_PER_EJ_1970: f64 = 0.027;
+
+/// Time for the sink to absorb the atmosphere's excess carbon [years]
+const UPTAKE_TIME: f64 = 40.0;
+/// Time for the sink to return its excess carbon to the atmosphere [years]
+/// Together with `UPTAKE_TIME`: about half of 2000–2020 emissions stay airborne,
+/// and 30% of any pulse in the long run. BAU reaches ≈ 330 ppm in 1970, close to
+/// the observed 326 ppm. Its industrial output stalls after 1990, so 2020 stays
+/// at ≈ 380 ppm and +0.9 K, below the observed 410 ppm and +1.1 K.
+const RETURN_TIME: f64 = 100.0;
+
+/// CO2 forcing coefficient [W / m²]: forcing = 5.35 × ln(C / C₀)
+const CO2_FORCING_COEFF: f64 = 5.35;
+/// Climate feedback parameter [W / m² / K] (equilibrium warming ≈ 3.1 K per doubling)
+const CLIMATE_FEEDBACK: f64 = 1.2;
+/// Heat exchange coefficient between the surface and the deep ocean [W / m² / K]
+const OCEAN_HEAT_EXCHANGE: f64 = 0.7;
+/// Heat capacity of the surface layer (atmosphere, land, mixed ocean) [W yr / m² / K]
+const SURFACE_HEAT_CAPACITY: f64 = 8.0;
+/// Heat capacity of the deep ocean [W yr / m² / K]
+const DEEP_OCEAN_HEAT_CAPACITY: f64 = 100.0;
+
+pub struct ClimateDerivatives {
+    pub d_atmospheric_carbon: f64,
+    pub d_sink_carbon: f64,
+    pub d_temperature_anomaly: f64,
+    pub d_deep_ocean_temperature: f64,
+    // Carbon flows behind the derivatives [GtC / year]
+    pub carbon_emissions: f64,
+    pub carbon_uptake: f64,
+}
+
+/// Compute the climate derivatives and update `carbon_emissions`,
+/// `co2_concentration` and `radiative_forcing` on `state.climate`.
+///
+/// Must run after the energy sector (emissions follow fossil energy use).
+/// The damage multipliers read the temperature stock, so the agriculture and
+/// population sectors do not depend on this pass.
+pub fn climate_derivatives(
+    state: &mut WorldState,
+    params: &ScenarioParams,
+) -> ClimateDerivatives {
+    // ---- Emissions ----
+    // Carbon per unit of fossil energy falls from 1970 at the lever's rate
+    // (fuel switching); capture removes a fraction at the source
+    let decline_years = (state.time - 1970.0).max(0.0);
+    let intensity = CARBON_PER_EJ_1970
+        * (1.0 - params.carbon_intensity_decline.clamp(0.0, 1.0)).powf(decline_years);
+    let capture = params.carbon_capture.clamp(0.0, 1.0);
+    let emissions = state.energy.fossil_energy * intensity * (1.0 - capture);
+    state.climate.carbon_emissions = emissions;
+
+    // ---- Carbon cycle ----
+    let excess = state.climate.atmospheric_carbon - PREINDUSTRIAL_CARBON;
+    let uptake = excess / UPTAKE_TIME - state.climate.sink_carbon / RETURN_TIME;
+
+    let concentration = state.climate.atmospheric_carbon.max(1.0) / GTC_PER_PPM;
+    state.climate.co2_concentration = concentration;
+
+    // ---- Energy balance ----
+    let forcing = CO2_FORCING_COEFF
+        * (state.climate.atmospheric_carbon.max(1.0) / PREINDUSTRIAL_CARBON).ln();
+    state.climate.radiative_forcing = forcing;
+
+    let surface = state.climate.temperature_anomaly;
+    let deep = state.climate.deep_ocean_temperature;
+    let heat_to_deep = OCEAN_HEAT_EXCHANGE * (surface - deep);
+
+    ClimateDerivatives {
+        d_atmospheric_carbon: emissions - uptake,
+        d_sink_carbon: uptake,
+        d_temperature_anomaly: (forcing - CLIMATE_FEEDBACK * surface - heat_to_deep)
+            / SURFACE_HEAT_CAPACITY,
+        d_deep_ocean_temperature: heat_to_deep / DEEP_OCEAN_HEAT_CAPACITY,
+        carbon_emissions: emissions,
+        carbon_uptake: uptake,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{
+        initial::initial_conditions_1900,
+        params::{ScenarioParams, SolverKind},
+    };
+    use crate::test_util::{run, run_from};
+
+    #[test]
+    fn test_warming_tracks_emissions_and_capture_limits_it() {
+        // The 1970 calibration holds whichever solver integrates the run
+        for solver in [SolverKind::Rk4, SolverKind::Rk45, SolverKind::Euler] {
+            let mut params = ScenarioParams::bau();
+            params.solver = solver;
+            let bau = run(params);
+            let climate = &bau.state_at_year(1970.0).unwrap().climate;
+            assert!((3.5..4.5).contains(&climate.carbon_emissions), "{solver:?}");
+            assert!((320.0..340.0).contains(&climate.co2_concentration), "{solver:?}");
+            let climate = &bau.state_at_year(2020.0).unwrap().climate;
+            assert!((0.6..1.3).contains(&climate.temperature_anomaly), "{solver:?}");
+        }
+
+        // Technology keeps industry growing, which warms enough to cost yield
+        // and life expectancy; capture limits it
+        let tech = run(ScenarioParams::comprehensive_technology());
+        let mut params = ScenarioParams::comprehensive_technology();
+        params.carbon_capture = 0.9;
+        let captured = run(params);
+        let (a, b) = (
+            tech.state_at_year(2100.0).unwrap(),
+            captured.state_at_year(2100.0).unwrap(),
+        );
+        assert!(b.climate.temperature_anomaly < a.climate.temperature_anomaly);
+        assert!(b.multipliers.yield_multiplier_climate >= a.multipliers.yield_multiplier_climate);
+        assert!(a.multipliers.lem_climate < 0.99);
+    }
+
+    #[test]
+    fn test_temperature_below_preindustrial_is_not_clamped() {
+        let mut initial = initial_conditions_1900();
+        initial.climate.temperature_anomaly = -0.5;
+        initial.climate.deep_ocean_temperature = -0.3;
+        let states = run_from(initial, ScenarioParams::bau()).states;
+        assert_eq!(states[0].climate.temperature_anomaly, -0.5);
+        assert!(states[1].climate.deep_ocean_temperature < 0.0);
+    }
+}
@@ -0,0 +1,161 @@
+//! Energy sector.
+//!
+//! Industrial output requires energy. It is supplied by two capital stocks,
+//! fossil and renewable, each with its own energy yield, lifetime and
+//! energy return on investment (EROI). Fossil EROI falls as non-renewable
+//! resources deplete, so the same capital delivers less net energy. When net
+//! supply falls short of demand, the capital sector scales industrial output
+//! down. Energy investment is a fraction of industrial output that rises with
+//! the shortage, taken out of industrial reinvestment by the capital sector,
+//! and split between the two stocks by a renewable share that grows at the
+//! transition-rate lever.
+
+use crate::lookup::tables::WorldLookupTables;
+use crate::model::{params::ScenarioParams, state::WorldState};
+
+/// Primary energy needed per unit of industrial output [EJ / 1975 USD].
+/// Calibrated: 1970 output ≈ 7.4e11 USD/yr → 150 EJ/yr, close to historical
+/// fossil and hydro use; 1900's ≈ 1.3e11 USD/yr → 27 EJ/yr.
+const ENERGY_INTENSITY: f64 = 2.0e-10;
+
+/// Capital per unit of gross annual energy output [1975 USD / (EJ / yr)]
+const FOSSIL_CAPITAL_PER_EJ: f64 = 2.0e9;
+const RENEWABLE_CAPITAL_PER_EJ: f64 = 4.0e9;
+
+/// Average lifetime of energy capital [years]
+const FOSSIL_LIFETIME: f64 = 25.0;
+const RENEWABLE_LIFETIME: f64 = 20.0;
+
+/// Energy return on investment of renewables (constant; fossil EROI is a table)
+const RENEWABLE_EROI: f64 = 10.0;
+
+/// Renewable share of energy investment up to 1970 (mostly hydro)
+const RENEWABLE_SHARE_1970: f64 = 0.05;
+
+pub struct EnergyDerivatives {
+    pub d_fossil_capital: f64,
+    pub d_renewable_capital: f64,
+    // Flows behind the derivatives [1975 USD / year]
+    pub fossil_investment: f64,
+    pub fossil_depreciation: f64,
+    pub renewable_investment: f64,
+    pub renewable_depreciation: f64,
+}
+
+/// Compute the net energy supply of the capital stocks.
+///
+/// Updates `net_energy_supply` on `state.energy` and the fossil EROI. Must run
+/// after the resource auxiliaries (fossil EROI reads `fraction_remaining`).
+pub fn compute_energy_supply(state: &mut WorldState, tables: &WorldLookupTables) {
+    let fossil_eroi = tables.fossil_eroi.eval(state.resources.fraction_remaining);
+    state.multipliers.fossil_eroi = fossil_eroi;
+
+    state.energy.net_energy_supply = net_fossil(state.energy.fossil_capital, fossil_eroi)
+        + net_renewable(state.energy.renewable_capital);
+}
+
+/// Fraction of `potential_output` the energy supply can sustain [0..1].
+///
+/// Called by the capital sector; records `energy_demand` and
+/// `energy_availability` on `state.energy`.
+pub fn energy_constraint(
+    state: &mut WorldState,
+    tables: &WorldLookupTables,
+    potential_output: f64,
+) -> f64 {
+    let demand = potential_output.max(0.0) * ENERGY_INTENSITY;
+    let availability = state.energy.net_energy_supply / demand.max(1e-9);
+    state.energy.energy_demand = demand;
+    state.energy.energy_availability = availability;
+
+    let multiplier = tables.energy_output_multiplier.eval(availability);
+    state.multipliers.energy_output_multiplier = multiplier;
+    multiplier
+}
+
+/// Compute the energy capital derivatives and update the delivered energy
+/// fields on `state.energy`.
+///
+/// Must run after the capital sector (investment is its `frac_to_energy`
+/// share of industrial output).
+pub fn energy_derivatives(state: &mut WorldState, params: &ScenarioParams) -> EnergyDerivatives {
+    // ---- Delivered energy ----
+    // Capacity beyond demand sits idle; both sources run at the same rate
+    let fossil = net_fossil(state.energy.fossil_capital, state.multipliers.fossil_eroi);
+    let renewable = net_renewable(state.energy.renewable_capital);
+    let utilization = if fossil + renewable > 0.0 {
+        (state.energy.energy_demand / (fossil + renewable)).min(1.0)
+    } else {
+        0.0
+    };
+    state.energy.fossil_energy = fossil * utilization;
+    state.energy.renewable_energy = renewable * utilization;
+    state.energy.renewable_share = if fossil + renewable > 0.0 {
+        renewable / (fossil + renewable)
+    } else {
+        0.0
+    };
+
+    // ---- Investment ----
+    // The capital sector's energy share of industrial output
+    let investment = state.capital.industrial_output * state.multipliers.frac_to_energy;
+
+    // Renewables take a growing share of it after 1970
+    let transition_years = (state.time - 1970.0).max(0.0);
+    let renewable_share = (RENEWABLE_SHARE_1970
+        + params.energy_transition_rate.max(0.0) * transition_years)
+        .clamp(0.0, 1.0);
+
+    let fossil_investment = investment * (1.0 - renewable_share);
+    let renewable_investment = investment * renewable_share;
+    let fossil_depreciation = state.energy.fossil_capital / FOSSIL_LIFETIME;
+    let renewable_depreciation = state.energy.renewable_capital / RENEWABLE_LIFETIME;
+
+    EnergyDerivatives {
+        d_fossil_capital: fossil_investment - fossil_depreciation,
+        d_renewable_capital: renewable_investment - renewable_depreciation,
+        fossil_investment,
+        fossil_depreciation,
+        renewable_investment,
+        renewable_depreciation,
+    }
+}
+
+/// Net annual energy from fossil capital: gross output less the energy
+/// reinvested in extraction, `1 / EROI` of it.
+fn net_fossil(capital: f64, eroi: f64) -> f64 {
+    capital / FOSSIL_CAPITAL_PER_EJ * (1.0 - 1.0 / eroi.max(1.0))
+}
+
+fn net_renewable(capital: f64) -> f64 {
+    capital / RENEWABLE_CAPITAL_PER_EJ * (1.0 - 1.0 / RENEWABLE_EROI)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::params::ScenarioParams;
+    use crate::test_util::run;
+
+    #[test]
+    fn test_fast_transition_cuts_emissions_and_supply_margin() {
+        // BAU: supply keeps ahead of demand, so output is never constrained
+        let bau = run(ScenarioParams::bau());
+        let state = bau.state_at_year(2020.0).unwrap();
+        assert!(state.energy.energy_availability > 1.0);
+        assert_eq!(state.multipliers.energy_output_multiplier, 1.0);
+        assert!(state.energy.renewable_share < 0.2);
+
+        // Renewables deliver less net energy per unit capital: the switch
+        // eats into the supply margin
+        let mut params = ScenarioParams::bau();
+        params.energy_transition_rate = 0.02;
+        let fast = run(params);
+        let (a, b) = (
+            bau.state_at_year(2020.0).unwrap(),
+            fast.state_at_year(2020.0).unwrap(),
+        );
+        assert!(b.energy.renewable_share > 0.5);
+        assert!(b.climate.carbon_emissions < 0.6 * a.climate.carbon_emissions);
+        assert!(b.energy.energy_availability < a.energy.energy_availability - 0.05);
+    }
+}
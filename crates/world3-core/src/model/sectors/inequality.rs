@@ -0,0 +1,170 @@
+//! Income distribution.
+//!
+//! The population is split into equal-sized income groups (quintiles by
+//! default), poorest first. Their shares of income follow a lognormal
+//! distribution with the Gini coefficient of the `income_inequality` lever.
+//! The population sector scales food, health and education services and
+//! industrial output per capita by each group's income relative to the mean
+//! and evaluates life expectancy and fertility per group. A Gini of zero
+//! gives every group the world average.
+//!
+//! Groups are quantiles of the current population, re-ranked continuously, so
+//! they carry no stocks of their own.
+
+use std::f64::consts::SQRT_2;
+
+/// Highest Gini coefficient accepted; the lognormal spread diverges at 1.
+const MAX_GINI: f64 = 0.95;
+
+/// Income shares of `groups` equal population quantiles, poorest first, for a
+/// lognormal income distribution with Gini coefficient `gini`. Sums to 1.
+pub fn income_shares(groups: u32, gini: f64) -> Vec<f64> {
+    let n = groups.max(1);
+    let gini = gini.clamp(0.0, MAX_GINI);
+    if gini == 0.0 {
+        return vec![1.0 / n as f64; n as usize];
+    }
+
+    // A lognormal with log-spread σ has Gini 2Φ(σ/√2) − 1; the income share
+    // of the population below quantile z is Φ(z − σ)
+    let sigma = SQRT_2 * normal_quantile((gini + 1.0) / 2.0);
+    let below = |k: u32| match k {
+        0 => 0.0,
+        k if k == n => 1.0,
+        k => normal_cdf(normal_quantile(k as f64 / n as f64) - sigma),
+    };
+    (1..=n).map(|k| below(k) - below(k - 1)).collect()
+}
+
+/// Gini coefficient of income shares held by equal population groups,
+/// poorest first (trapezoidal Lorenz curve).
+///
+/// Lower than the Gini of the underlying distribution: inequality within a
+/// group is not resolved.
+pub fn grouped_gini(shares: &[f64]) -> f64 {
+    let n = shares.len() as f64;
+    let mut cumulative = 0.0;
+    let mut area = 0.0;
+    for share in shares {
+        area += (2.0 * cumulative + share) / n;
+        cumulative += share;
+    }
+    (1.0 - area).max(0.0)
+}
+
+/// Standard normal cumulative distribution (Abramowitz & Stegun 7.1.26,
+/// absolute error < 1.5e-7).
+fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * z);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-z * z).exp();
+    0.5 * (1.0 + erf.copysign(x))
+}
+
+/// Standard normal quantile for `p` in (0, 1) (Acklam's rational
+/// approximation, relative error < 1.2e-9).
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const P_LOW: f64 = 0.024_25;
+
+    let tail = |q: f64| {
+        let r = (-2.0 * q.ln()).sqrt();
+        (((((C[0] * r + C[1]) * r + C[2]) * r + C[3]) * r + C[4]) * r + C[5])
+            / ((((D[0] * r + D[1]) * r + D[2]) * r + D[3]) * r + 1.0)
+    };
+    let p = p.clamp(1e-12, 1.0 - 1e-12);
+    if p < P_LOW {
+        tail(p)
+    } else if p > 1.0 - P_LOW {
+        -tail(1.0 - p)
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        derivatives::compute_auxiliaries, initial::initial_conditions_1900, params::ScenarioParams,
+    };
+    use crate::test_util::{run, tables};
+
+    #[test]
+    fn test_world_average_food_hides_starvation_of_the_poorest() {
+        let shares = income_shares(5, 0.4);
+        assert!((shares.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(shares.windows(2).all(|w| w[0] < w[1]));
+        assert!(grouped_gini(&shares) < 0.4);
+        assert_eq!(grouped_gini(&income_shares(5, 0.0)), 0.0);
+
+        // Without inequality every group is the world average
+        let equal = run(ScenarioParams::bau());
+        let state = equal.state_at_year(2000.0).unwrap();
+        assert_eq!(state.inequality.groups.len(), 5);
+        assert_eq!(state.inequality.gini, 0.0);
+        for group in &state.inequality.groups {
+            assert!((group.food_per_capita / state.agriculture.food_per_capita - 1.0).abs() < 1e-12);
+        }
+
+        // In 1900 the world eats about three times subsistence, but the
+        // poorest fifth gets a quarter of the average
+        let mut params = ScenarioParams::bau();
+        params.income_inequality = 0.4;
+        let mut state = initial_conditions_1900();
+        compute_auxiliaries(&mut state, &params, &tables());
+        let (poorest, richest) = (
+            state.inequality.groups.first().unwrap(),
+            state.inequality.groups.last().unwrap(),
+        );
+        assert!(state.agriculture.food_per_capita > 2.0 * params.subsistence_food_per_capita);
+        assert!(poorest.food_per_capita < params.subsistence_food_per_capita);
+        assert_eq!(state.inequality.starving_fraction, 0.2);
+        assert!(poorest.life_expectancy < richest.life_expectancy - 10.0);
+
+        // Once everyone is fed, the poor have the larger families
+        let unequal = run(params);
+        let state = unequal.state_at_year(2000.0).unwrap();
+        let (poorest, richest) = (
+            state.inequality.groups.first().unwrap(),
+            state.inequality.groups.last().unwrap(),
+        );
+        assert_eq!(state.inequality.starving_fraction, 0.0);
+        assert!(poorest.life_expectancy < richest.life_expectancy - 10.0);
+        assert!(poorest.fertility_rate > richest.fertility_rate);
+    }
+}
@@ -0,0 +1,88 @@
+//! Labor sector.
+//!
+//! Jobs come from industrial capital, service capital and arable land, fewer
+//! of them as education raises output per worker; the labor force is the
+//! participating share of the 15–64 cohorts. Their ratio,
+//! the labor utilization fraction, is perceived with a delay. When jobs
+//! outnumber workers, part of the capital cannot be manned: the capital
+//! sector scales industrial and service output by the capacity utilization
+//! fraction read from the perceived value.
+
+use crate::lookup::tables::WorldLookupTables;
+use crate::model::state::WorldState;
+
+/// Labor utilization perception delay [years] (World 3 LUFDT)
+const LABOR_UTILIZATION_DELAY: f64 = 2.0;
+
+/// Compute `d(perceived_utilization)/dt` and update the auxiliary fields on
+/// `state.labor`: jobs per sector, labor force, employment, unemployment and
+/// labor utilization.
+///
+/// Must run after the capital and agriculture sectors (jobs depend on output
+/// per capita, education services and agricultural inputs per hectare).
+pub fn labor_derivative(state: &mut WorldState, tables: &WorldLookupTables) -> f64 {
+    // ---- Jobs ----
+    let labor_productivity = tables
+        .labor_productivity_education
+        .eval(state.capital.education_services_per_capita);
+    state.multipliers.labor_productivity = labor_productivity;
+
+    let industrial_jobs = state.capital.industrial_capital
+        * tables.jobs_per_capital.eval(state.capital.industrial_output_per_capita)
+        / labor_productivity;
+    let service_jobs = state.capital.service_capital
+        * tables.jobs_per_service_capital.eval(state.capital.service_output_per_capita)
+        / labor_productivity;
+    let agricultural_jobs = state.agriculture.arable_land
+        * tables.jobs_per_hectare.eval(state.agriculture.agricultural_inputs_per_hectare);
+    let jobs = industrial_jobs + service_jobs + agricultural_jobs;
+
+    // ---- Labor force ----
+    let working_age = state.population.cohort_15_44 + state.population.cohort_45_64;
+    let working_age_fraction = working_age / state.population.population.max(1.0);
+    let labor_force =
+        working_age * tables.labor_force_participation.eval(working_age_fraction);
+
+    let labor_utilization = jobs / labor_force.max(1.0);
+    let employment = jobs.min(labor_force);
+
+    state.labor.industrial_jobs = industrial_jobs;
+    state.labor.service_jobs = service_jobs;
+    state.labor.agricultural_jobs = agricultural_jobs;
+    state.labor.jobs = jobs;
+    state.labor.labor_force = labor_force;
+    state.labor.employment = employment;
+    state.labor.unemployment_rate = if labor_force > 0.0 {
+        1.0 - employment / labor_force
+    } else {
+        0.0
+    };
+    state.labor.labor_utilization = labor_utilization;
+
+    // ---- Perception delay (first-order smooth) ----
+    (labor_utilization - state.labor.perceived_utilization) / LABOR_UTILIZATION_DELAY
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::params::ScenarioParams;
+    use crate::test_util::run;
+
+    #[test]
+    fn test_labor_shortage_idles_capital_after_population_peak() {
+        // Unemployment in 1970: every job filled, capital fully manned
+        let output = run(ScenarioParams::bau());
+        let labor = &output.state_at_year(1970.0).unwrap().labor;
+        assert!(labor.jobs < labor.labor_force);
+        assert_eq!(labor.employment, labor.jobs);
+        assert!(labor.unemployment_rate > 0.0 && labor.unemployment_rate < 0.5);
+        assert_eq!(labor.capacity_utilization, 1.0);
+
+        // A stabilized population can no longer man all the capital by 2050
+        let output = run(ScenarioParams::stabilized_world());
+        let labor = &output.state_at_year(2050.0).unwrap().labor;
+        assert!(labor.perceived_utilization > 1.0);
+        assert_eq!(labor.unemployment_rate, 0.0);
+        assert!(labor.capacity_utilization < 1.0);
+    }
+}
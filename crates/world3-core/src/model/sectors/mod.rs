@@ -1,5 +1,11 @@
 pub mod agriculture;
+pub mod biodiversity;
 pub mod capital;
+pub mod climate;
+pub mod energy;
+pub mod inequality;
+pub mod labor;
 pub mod population;
 pub mod pollution;
 pub mod resources;
+pub mod welfare;
@@ -29,14 +29,18 @@ pub fn pollution_derivative(
         / 200.0_f64.max(1.0); // normalize to ~1970 level
     let agri_normalized = state.agriculture.agricultural_inputs_per_hectare / 40.0; // normalize
 
-    let gen_industry = state.capital.industrial_output
-        * PPGIO
-        * tables.pollution_generation_industry.eval(iopc_normalized);
+    let gen_multiplier_industry = tables.pollution_generation_industry.eval(iopc_normalized);
+    let gen_multiplier_agriculture =
+        tables.pollution_generation_agriculture.eval(agri_normalized);
+    state.multipliers.pollution_gen_industry = gen_multiplier_industry;
+    state.multipliers.pollution_gen_agriculture = gen_multiplier_agriculture;
+
+    let gen_industry = state.capital.industrial_output * PPGIO * gen_multiplier_industry;
 
     let gen_agriculture = state.agriculture.arable_land
         * state.agriculture.agricultural_inputs_per_hectare
         * PPGAO
-        * tables.pollution_generation_agriculture.eval(agri_normalized);
+        * gen_multiplier_agriculture;
 
     // Pollution control policy reduces generation
     let control = params.pollution_control.clamp(0.0, 1.0);
@@ -49,6 +53,7 @@ pub fn pollution_derivative(
     let assimilation_time = tables
         .pollution_assimilation_time
         .eval(state.pollution.pollution_index);
+    state.multipliers.assimilation_time = assimilation_time;
 
     let assimilation = if assimilation_time > 0.0 {
         state.pollution.persistent_pollution / assimilation_time
@@ -61,7 +66,9 @@ pub fn pollution_derivative(
     // reference_stock = 1.0 so that pollution_index = persistent_pollution directly.
     // Initial persistent_pollution = 0.25 → pollution_index = 0.25 in 1900.
     // Target: pollution_index = 1.0 in 1970, rising to 10-30 by 2040-2060 in BAU.
-    state.pollution.pollution_index = state.pollution.persistent_pollution.max(0.0);
+    // A region holds its land share of the reference stock.
+    state.pollution.pollution_index =
+        state.pollution.persistent_pollution.max(0.0) / params.region.land.max(1e-9);
 
     generation - assimilation
 }
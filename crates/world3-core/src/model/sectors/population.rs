@@ -3,17 +3,25 @@
 //! Tracks four age cohorts: 0–14, 15–44, 45–64, 65+.
 //! Births enter cohort 0–14; deaths occur in all cohorts; aging moves people
 //! between cohorts. The sector depends heavily on food, health services,
-//! crowding, and pollution through lookup-table multipliers on life expectancy.
+//! crowding, pollution and warming through lookup-table multipliers on life
+//! expectancy.
+//!
+//! Life expectancy and fertility are evaluated per income group (see
+//! [`super::inequality`]); births and deaths sum the groups.
 
 use crate::lookup::tables::WorldLookupTables;
-use crate::model::{params::ScenarioParams, state::WorldState};
+use crate::model::sectors::inequality;
+use crate::model::{
+    params::ScenarioParams,
+    state::{IncomeGroup, InequalityState, WorldState},
+};
 
 /// Base life expectancy [years] before applying lookup-table multipliers.
 ///
 /// With BASE = 20 and the new lem_health table (which returns < 1.0 at low sopc):
 ///   1900: 20 × 1.49 × 0.76 × 1.41 × 1.0 ≈ 32 yr  (lem_health < 1 = poor health services)
 ///   1970: 20 × 1.50 × 1.37 × 1.30 × 1.0 ≈ 53 yr  (lem_health ≈ 1.37 = adequate services)
-const LIFE_EXPECTANCY_BASE: f64 = 20.0;
+pub(crate) const LIFE_EXPECTANCY_BASE: f64 = 20.0;
 
 /// Cohort durations (years spent in each cohort before aging out)
 const COHORT_0_14_DURATION: f64 = 15.0;
@@ -25,10 +33,20 @@ pub struct PopulationDerivatives {
     pub d_cohort_15_44: f64,
     pub d_cohort_45_64: f64,
     pub d_cohort_65_plus: f64,
+    // Flows behind the derivatives [persons / year]
+    pub births: f64,
+    pub deaths: [f64; 4],
+    pub aging: [f64; 3],
+    pub net_migration: f64,
 }
 
 /// Compute population derivatives and update auxiliary fields:
-/// `life_expectancy`, `birth_rate`, `death_rate`, `fertility_rate`.
+/// `life_expectancy`, `birth_rate`, `death_rate`, `fertility_rate`, the
+/// income groups in `state.inequality`, and the life expectancy and
+/// fertility multipliers.
+///
+/// The multipliers are those of the world average; with income inequality
+/// the aggregate life expectancy and fertility differ from their product.
 pub fn population_derivatives(
     state: &mut WorldState,
     params: &ScenarioParams,
@@ -40,13 +58,14 @@ pub fn population_derivatives(
     let food_ratio = state.agriculture.food_per_capita / params.subsistence_food_per_capita;
 
     // Effective health services per capita [1975 USD/person/yr].
-    // The lem_health table x-axis is 0..100 USD/person/yr, calibrated so that
-    // 1970 conditions (~50 USD/person/yr) give LMH ≈ 1.62 multiplier.
-    let health_services = state.capital.service_output_per_capita
+    // The lem_health table x-axis is 0..500 USD/person/yr, calibrated so that
+    // 1970 conditions (~200 USD/person/yr) give LMH ≈ 1.34 multiplier.
+    let health_services = state.capital.health_services_per_capita
         * params.health_investment_multiplier;
 
-    // Crowding ratio: loosely based on population relative to 1970
-    let crowding_ratio = pop / 3.6e9;
+    // Crowding ratio: loosely based on population relative to 1970, on the
+    // region's share of the land
+    let crowding_ratio = pop / (3.6e9 * params.region.land);
 
     let lem_food = tables.life_exp_multiplier_food.eval(food_ratio);
     let lem_health = tables.life_exp_multiplier_health.eval(health_services);
@@ -54,18 +73,24 @@ pub fn population_derivatives(
     let lem_pollution = tables
         .life_exp_multiplier_pollution
         .eval(state.pollution.pollution_index);
+    let lem_climate = tables
+        .climate_life_expectancy_multiplier
+        .eval(state.climate.temperature_anomaly);
 
-    let life_expectancy = LIFE_EXPECTANCY_BASE
-        * lem_food
-        * lem_health
-        * lem_crowding
-        * lem_pollution;
-    state.population.life_expectancy = life_expectancy.clamp(5.0, 85.0);
+    state.multipliers.lem_food = lem_food;
+    state.multipliers.lem_health = lem_health;
+    state.multipliers.lem_crowding = lem_crowding;
+    state.multipliers.lem_pollution = lem_pollution;
+    state.multipliers.lem_climate = lem_climate;
 
     // ---- Fertility / birth rate ----
-    // Desired family size decreases with industrial output per capita
+    // Desired family size decreases with industrial output per capita and,
+    // beyond 1970 levels, with education
     let iopc = state.capital.industrial_output_per_capita;
     let desired_family_size = tables.desired_family_size.eval(iopc);
+    let education_fertility = tables
+        .education_family_size_multiplier
+        .eval(state.capital.education_services_per_capita);
 
     // Family planning ramps in from zero at 1900 to full efficacy by family_planning_year
     let fp_ramp = if params.family_planning_year <= 1900.0 {
@@ -79,7 +104,61 @@ pub fn population_derivatives(
     // Food effect on fertility
     let food_fertility = tables.food_fertility_multiplier.eval(food_ratio);
 
-    let total_fertility_rate = desired_family_size * fp_multiplier * food_fertility;
+    state.multipliers.desired_family_size = desired_family_size;
+    state.multipliers.education_fertility = education_fertility;
+    state.multipliers.fp_multiplier = fp_multiplier;
+    state.multipliers.food_fertility = food_fertility;
+
+    // ---- Income groups ----
+    // Each group has its income relative to the world average of food,
+    // services and industrial output. Groups are equal population quantiles
+    // with the same age structure, so mortality and fertility average evenly.
+    let shares = inequality::income_shares(params.income_groups, params.income_inequality);
+    let n = shares.len() as f64;
+    let mut groups = Vec::with_capacity(shares.len());
+    let mut base_mort = 0.0;
+    let mut total_fertility_rate = 0.0;
+    for &income_share in &shares {
+        let relative_income = income_share * n;
+        let group_food_ratio = food_ratio * relative_income;
+        let group_iopc = iopc * relative_income;
+
+        let life_expectancy = LIFE_EXPECTANCY_BASE
+            * tables.life_exp_multiplier_food.eval(group_food_ratio)
+            * tables.life_exp_multiplier_health.eval(health_services * relative_income)
+            * lem_crowding
+            * lem_pollution
+            * lem_climate;
+        let fertility_rate = tables.desired_family_size.eval(group_iopc)
+            * tables.education_family_size_multiplier.eval(
+                state.capital.education_services_per_capita * relative_income,
+            )
+            * fp_multiplier
+            * tables.food_fertility_multiplier.eval(group_food_ratio);
+
+        base_mort += 1.0 / life_expectancy.max(1.0) / n;
+        total_fertility_rate += fertility_rate / n;
+        groups.push(IncomeGroup {
+            income_share,
+            industrial_output_per_capita: group_iopc,
+            food_per_capita: state.agriculture.food_per_capita * relative_income,
+            life_expectancy: life_expectancy.clamp(5.0, 85.0),
+            fertility_rate: fertility_rate.clamp(0.5, 8.0),
+        });
+    }
+
+    let starving = groups
+        .iter()
+        .filter(|g| g.food_per_capita < params.subsistence_food_per_capita)
+        .count();
+    state.inequality = InequalityState {
+        gini: inequality::grouped_gini(&shares),
+        starving_fraction: starving as f64 / n,
+        groups,
+    };
+
+    // The life expectancy that gives the groups' mean mortality
+    state.population.life_expectancy = (1.0 / base_mort).clamp(5.0, 85.0);
     state.population.fertility_rate = total_fertility_rate.clamp(0.5, 8.0);
 
     // Births = fertile-age women × TFR / reproductive period
@@ -90,12 +169,11 @@ pub fn population_derivatives(
     state.population.birth_rate = birth_rate;
 
     // ---- Age-cohort mortality ----
-    // Base annual death fraction = 1 / life_expectancy.
+    // Base annual death fraction = 1 / life_expectancy, averaged over the
+    // income groups above (`base_mort`).
     // Cohort multipliers calibrated so weighted average ≈ 0.9 at 1900 age structure
     // (high child mortality and small 65+ cohort), yielding crude death rate ~2.8%
     // at LE=32 — consistent with historical 1900 world average.
-    let base_mort = 1.0 / life_expectancy.max(1.0);
-
     let deaths_0_14 = state.population.cohort_0_14 * base_mort * 0.8;
     let deaths_15_44 = state.population.cohort_15_44 * base_mort * 0.5;
     let deaths_45_64 = state.population.cohort_45_64 * base_mort * 1.0;
@@ -110,10 +188,17 @@ pub fn population_derivatives(
     let aging_15_to_45 = state.population.cohort_15_44 / COHORT_15_44_DURATION;
     let aging_45_to_65 = state.population.cohort_45_64 / COHORT_45_64_DURATION;
 
+    // Migrants of a regional run are working-age adults
+    let net_migration = state.trade.net_migration;
+
     PopulationDerivatives {
         d_cohort_0_14: births_per_year - aging_0_to_15 - deaths_0_14,
-        d_cohort_15_44: aging_0_to_15 - aging_15_to_45 - deaths_15_44,
+        d_cohort_15_44: aging_0_to_15 - aging_15_to_45 - deaths_15_44 + net_migration,
         d_cohort_45_64: aging_15_to_45 - aging_45_to_65 - deaths_45_64,
         d_cohort_65_plus: aging_45_to_65 - deaths_65_plus,
+        births: births_per_year,
+        deaths: [deaths_0_14, deaths_15_44, deaths_45_64, deaths_65_plus],
+        aging: [aging_0_to_15, aging_15_to_45, aging_45_to_65],
+        net_migration,
     }
 }
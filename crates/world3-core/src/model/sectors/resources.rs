@@ -14,21 +14,12 @@ use crate::model::{params::ScenarioParams, state::WorldState};
 /// Cumulatively depletes ~50% of NNR by 2050 in BAU — consistent with Meadows 1972.
 const RESOURCE_DEPLETION_COEFF: f64 = 3.0e-15;
 
-/// Compute the rate of change of non-renewable resources.
-///
-/// Returns `d(nonrenewable_resources)/dt` in resource units per year.
-/// This is always negative (resources are consumed, never replenished).
+/// Resource use at `state` in units of the world's 1900 endowment per year.
 ///
 /// Mechanism:
 /// - Per-capita resource use scales with industrial output per capita (IOPC).
-/// - Total extraction = POP × per_capita_use / resource_efficiency.
-/// - The FCAOR table (capital fraction for resource extraction) is used ONLY by
-///   the capital sector to reduce productive output — it does NOT appear here.
-pub fn resource_derivative(
-    state: &WorldState,
-    params: &ScenarioParams,
-    _tables: &WorldLookupTables,
-) -> f64 {
+/// - Total use = POP × per_capita_use / resource_efficiency.
+pub fn resource_use(state: &WorldState, params: &ScenarioParams) -> f64 {
     let pop = state.population.population;
     if pop <= 0.0 {
         return 0.0;
@@ -37,20 +28,40 @@ pub fn resource_derivative(
     // Per-capita resource demand scales with industrial output per capita
     let iopc = state.capital.industrial_output_per_capita.max(0.0);
 
-    // Extraction rate = POP × IOPC × coefficient / efficiency
+    // Use = POP × IOPC × coefficient / efficiency
     // Decreases naturally as resources deplete (via feedback through capital output)
-    let extraction_rate = pop * iopc * RESOURCE_DEPLETION_COEFF / params.resource_efficiency;
+    pop * iopc * RESOURCE_DEPLETION_COEFF / params.resource_efficiency
+}
 
-    -extraction_rate
+/// Compute the rate of change of non-renewable resources.
+///
+/// Returns `d(nonrenewable_resources)/dt` in resource units per year: the
+/// [`resource_use`] on the region's own endowment, less what a regional run
+/// imports. This is never positive for a single world run (resources are
+/// consumed, never replenished).
+///
+/// The FCAOR table (capital fraction for resource extraction) is used ONLY by
+/// the capital sector to reduce productive output — it does NOT appear here.
+pub fn resource_derivative(
+    state: &WorldState,
+    params: &ScenarioParams,
+    _tables: &WorldLookupTables,
+) -> f64 {
+    let use_rate = resource_use(state, params) / params.region.resources.max(1e-9);
+    -(use_rate - state.trade.resource_imports)
 }
 
 /// Compute auxiliary variables for the resource sector.
 ///
-/// Updates `state.resources.fraction_remaining` in place.
+/// Updates `state.resources.fraction_remaining` in place; a regional run
+/// with resource trade sets the fraction its extraction costs follow.
 /// Must be called before capital sector uses the cost multiplier.
 pub fn compute_resource_auxiliaries(state: &mut WorldState, tables: &WorldLookupTables) {
-    state.resources.fraction_remaining =
-        state.resources.nonrenewable_resources.clamp(0.0, 1.0);
+    state.resources.fraction_remaining = state
+        .trade
+        .resource_fraction_remaining
+        .unwrap_or(state.resources.nonrenewable_resources)
+        .clamp(0.0, 1.0);
 
     // Capital-output ratio multiplier is stored on capital sector (capital.rs computes it)
     let _ = tables
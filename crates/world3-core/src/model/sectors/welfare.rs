@@ -0,0 +1,36 @@
+//! Welfare sector.
+//!
+//! World3-03's human welfare index: the mean of a life expectancy index, an
+//! education index and a GDP index, the latter two read from GDP per capita.
+//! Purely an output — nothing in the model responds to it.
+
+use crate::lookup::tables::WorldLookupTables;
+use crate::model::state::WorldState;
+
+/// GDP per capita at which the GDP index is 0 and 1 [1990 USD / person / year]
+const GDP_INDEX_LOW: f64 = 24.0;
+const GDP_INDEX_HIGH: f64 = 9508.0;
+
+/// Update the fields of `state.welfare`.
+///
+/// Must run after the population sector (life expectancy).
+pub fn compute_welfare(state: &mut WorldState, tables: &WorldLookupTables) {
+    let gdp_per_capita = tables
+        .gdp_per_capita
+        .eval(state.capital.industrial_output_per_capita);
+    let life_expectancy_index = tables
+        .life_expectancy_index
+        .eval(state.population.life_expectancy);
+    let education_index = tables.education_index.eval(gdp_per_capita);
+    // Logarithmic: each doubling of income counts the same
+    let gdp_index = ((gdp_per_capita.max(GDP_INDEX_LOW) / GDP_INDEX_LOW).ln()
+        / (GDP_INDEX_HIGH / GDP_INDEX_LOW).ln())
+    .min(1.0);
+
+    state.welfare.gdp_per_capita = gdp_per_capita;
+    state.welfare.life_expectancy_index = life_expectancy_index;
+    state.welfare.education_index = education_index;
+    state.welfare.gdp_index = gdp_index;
+    state.welfare.human_welfare_index =
+        (life_expectancy_index + education_index + gdp_index) / 3.0;
+}
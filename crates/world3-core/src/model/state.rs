@@ -8,6 +8,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::model::sectors::climate::MIN_TEMPERATURE_ANOMALY;
+
 /// Complete state of the World 3 model at a single point in time.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorldState {
@@ -19,6 +21,31 @@ pub struct WorldState {
     pub agriculture: AgricultureState,
     pub resources: ResourceState,
     pub pollution: PollutionState,
+    #[serde(default)]
+    pub labor: LaborState,
+    /// Preindustrial when missing from older files
+    #[serde(default = "ClimateState::preindustrial")]
+    pub climate: ClimateState,
+    #[serde(default)]
+    pub energy: EnergyState,
+    /// Fully intact when missing from older files
+    #[serde(default = "BiodiversityState::intact")]
+    pub biodiversity: BiodiversityState,
+    #[serde(default)]
+    pub welfare: WelfareState,
+    #[serde(default)]
+    pub inequality: InequalityState,
+    /// Imports and migration of a regional run; zero for a world run
+    #[serde(default)]
+    pub trade: TradeState,
+    /// Rates of every stock inflow and outflow at `time`. Omitted from JSON
+    /// when zeroed, so a consumer can drop it from large payloads
+    #[serde(default, skip_serializing_if = "Flows::is_zero")]
+    pub flows: Flows,
+    /// Lookup multipliers and factors behind the auxiliaries at `time`.
+    /// Omitted from JSON when zeroed, like `flows`
+    #[serde(default, skip_serializing_if = "Multipliers::is_zero")]
+    pub multipliers: Multipliers,
 }
 
 // ---------------------------------------------------------------------------
@@ -59,6 +86,12 @@ pub struct CapitalState {
     pub industrial_output_per_capita: f64,
     /// Service output per capita [1975 USD / person / year]
     pub service_output_per_capita: f64,
+    /// Service output per capita spent on health and on education
+    /// [1975 USD / person / year]
+    #[serde(default)]
+    pub health_services_per_capita: f64,
+    #[serde(default)]
+    pub education_services_per_capita: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -67,6 +100,10 @@ pub struct AgricultureState {
     pub arable_land: f64,
     /// Potentially arable but not yet developed [hectares]
     pub potentially_arable_land: f64,
+    /// Yield of the soil without inputs [kg / hectare / year]; inherent
+    /// (1900) fertility when missing from older files
+    #[serde(default = "inherent_land_fertility")]
+    pub land_fertility: f64,
     /// Annual food production [vegetable-equivalent kg / year]
     pub food: f64,
     /// Food per capita [kg / person / year]
@@ -77,6 +114,10 @@ pub struct AgricultureState {
     pub agricultural_inputs_per_hectare: f64,
 }
 
+fn inherent_land_fertility() -> f64 {
+    crate::model::sectors::agriculture::LAND_YIELD_1900
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ResourceState {
     /// Non-renewable resources remaining [dimensionless, normalized to 1.0 in 1900]
@@ -97,13 +138,570 @@ pub struct PollutionState {
     pub assimilation_rate: f64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LaborState {
+    /// Labor utilization fraction as perceived by employers, i.e. smoothed
+    /// over the perception delay [dimensionless]
+    pub perceived_utilization: f64,
+    /// Jobs offered by industrial capital, service capital and arable land [persons]
+    pub industrial_jobs: f64,
+    pub service_jobs: f64,
+    pub agricultural_jobs: f64,
+    /// Total jobs [persons]
+    pub jobs: f64,
+    /// Labor force, from the 15–64 cohorts [persons]
+    pub labor_force: f64,
+    /// Employed persons, the lesser of jobs and labor force [persons]
+    pub employment: f64,
+    /// Fraction of the labor force without a job [0..1]
+    pub unemployment_rate: f64,
+    /// Labor utilization fraction = jobs / labor force [dimensionless]
+    pub labor_utilization: f64,
+    /// Fraction of capital that can be manned [0..1]
+    pub capacity_utilization: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ClimateState {
+    /// Carbon in the atmosphere [GtC]
+    pub atmospheric_carbon: f64,
+    /// Excess carbon taken up by the ocean and land sink since preindustrial
+    /// times [GtC]
+    pub sink_carbon: f64,
+    /// Surface temperature anomaly over preindustrial [K]
+    pub temperature_anomaly: f64,
+    /// Deep ocean temperature anomaly over preindustrial [K]
+    pub deep_ocean_temperature: f64,
+    /// Fossil CO2 emissions [GtC / year]
+    pub carbon_emissions: f64,
+    /// Atmospheric CO2 concentration [ppm]
+    pub co2_concentration: f64,
+    /// CO2 radiative forcing [W / m²]
+    pub radiative_forcing: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct EnergyState {
+    /// Fossil and renewable energy capital [1975 USD]
+    pub fossil_capital: f64,
+    pub renewable_capital: f64,
+    /// Energy needed by potential industrial output [EJ / year]
+    pub energy_demand: f64,
+    /// Net energy the capital can supply, after its own energy use [EJ / year]
+    pub net_energy_supply: f64,
+    /// Net supply / demand; output is constrained below 1 [dimensionless]
+    pub energy_availability: f64,
+    /// Net energy delivered from fossil and renewable capital [EJ / year]
+    pub fossil_energy: f64,
+    pub renewable_energy: f64,
+    /// Renewable fraction of net energy supply [0..1]
+    pub renewable_share: f64,
+}
+
+impl ClimateState {
+    /// Preindustrial climate: 280 ppm, empty sink, no warming.
+    pub fn preindustrial() -> Self {
+        Self {
+            atmospheric_carbon: crate::model::sectors::climate::PREINDUSTRIAL_CARBON,
+            ..Default::default()
+        }
+    }
+}
+
+/// World3-03's ecological footprint, plus a biodiversity intactness stock.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BiodiversityState {
+    /// Biodiversity intactness: share of the original species abundance
+    /// left [0..1]
+    pub intactness: f64,
+    /// Intactness the current land use and pollution would settle at [0..1]
+    pub indicated_intactness: f64,
+    /// Urban-industrial land [hectares]
+    pub urban_land: f64,
+    /// Land needed to absorb the pollution generated [hectares]
+    pub absorption_land: f64,
+    /// Arable, urban and absorption land over Earth's biocapacity [dimensionless]
+    pub ecological_footprint: f64,
+}
+
+impl BiodiversityState {
+    /// Untouched biodiversity, nothing else set.
+    pub fn intact() -> Self {
+        Self {
+            intactness: 1.0,
+            ..Default::default()
+        }
+    }
+}
+
+/// World3-03's human welfare index and its components.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WelfareState {
+    /// GDP per capita [1990 USD / person / year]
+    pub gdp_per_capita: f64,
+    /// Life expectancy, education and GDP indices [0..1]
+    pub life_expectancy_index: f64,
+    pub education_index: f64,
+    pub gdp_index: f64,
+    /// Mean of the three indices [0..1]
+    pub human_welfare_index: f64,
+}
+
+/// Outcomes per income group (see [`crate::model::sectors::inequality`]).
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct InequalityState {
+    /// Gini coefficient between the groups' incomes [0..1]
+    pub gini: f64,
+    /// Fraction of the population in groups fed below subsistence [0..1]
+    pub starving_fraction: f64,
+    /// Equal population groups, poorest first. Omitted from JSON when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<IncomeGroup>,
+}
+
+/// One income group's share of income and its own outcomes.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct IncomeGroup {
+    /// Share of total income [0..1]
+    pub income_share: f64,
+    /// Industrial output per capita [1975 USD / person / year]
+    pub industrial_output_per_capita: f64,
+    /// Food per capita [kg / person / year]
+    pub food_per_capita: f64,
+    /// Life expectancy at birth [years]
+    pub life_expectancy: f64,
+    /// Total fertility rate [children / woman]
+    pub fertility_rate: f64,
+}
+
+/// A region's exchanges with the other regions of a run (see
+/// [`crate::region`]); set by the regional runner, read by the sectors.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+pub struct TradeState {
+    /// Net food imports [kg / year]; negative for exports
+    pub food_imports: f64,
+    /// Net resource imports [own 1900 endowment / year]; negative for exports
+    pub resource_imports: f64,
+    /// Fraction remaining the region's extraction costs follow when it draws
+    /// on the pooled world reserves; its own stock's when `None`
+    pub resource_fraction_remaining: Option<f64>,
+    /// Net immigration [persons / year]; negative for emigration
+    pub net_migration: f64,
+}
+
+/// Every flow into and out of the 19 stocks [stock units / year].
+///
+/// These are the rates the solver integrates (the derivative pass at this
+/// state), so each stock's derivative is the sum of its in- and outflows —
+/// see [`Flows::net`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+pub struct Flows {
+    /// Births into cohort 0–14 [persons / year]
+    pub births: f64,
+    /// Deaths per cohort [persons / year]
+    pub deaths_0_14: f64,
+    pub deaths_15_44: f64,
+    pub deaths_45_64: f64,
+    pub deaths_65_plus: f64,
+    /// Aging between consecutive cohorts [persons / year]
+    pub aging_0_to_15: f64,
+    pub aging_15_to_45: f64,
+    pub aging_45_to_65: f64,
+    /// Industrial capital investment [1975 USD / year]
+    pub industrial_investment: f64,
+    /// Industrial capital depreciation [1975 USD / year]
+    pub industrial_depreciation: f64,
+    /// Service capital investment [1975 USD / year]
+    pub service_investment: f64,
+    /// Service capital depreciation [1975 USD / year]
+    pub service_depreciation: f64,
+    /// Potentially arable land developed into arable land [hectares / year]
+    pub land_development: f64,
+    /// Arable land lost to erosion [hectares / year]
+    pub land_erosion: f64,
+    /// Land fertility lost to pollution and intensive farming, and regained
+    /// [kg / hectare / year per year]
+    pub land_fertility_degradation: f64,
+    pub land_fertility_regeneration: f64,
+    /// Non-renewable resource extraction [resource units / year]
+    pub resource_extraction: f64,
+    /// Persistent pollution generation [pollution units / year]
+    pub pollution_generation: f64,
+    /// Persistent pollution assimilation [pollution units / year]
+    pub pollution_assimilation: f64,
+    /// Adjustment of the perceived labor utilization toward the actual one
+    /// [1 / year]; negative when perception is above the actual value
+    pub labor_utilization_adjustment: f64,
+    /// Fossil CO2 emissions into the atmosphere [GtC / year]
+    pub carbon_emissions: f64,
+    /// Net carbon uptake by the ocean and land sink [GtC / year]
+    pub carbon_uptake: f64,
+    /// Net warming of the surface layer and of the deep ocean [K / year]
+    pub surface_warming: f64,
+    pub deep_ocean_warming: f64,
+    /// Fossil energy capital investment and depreciation [1975 USD / year]
+    pub fossil_energy_investment: f64,
+    pub fossil_energy_depreciation: f64,
+    /// Renewable energy capital investment and depreciation [1975 USD / year]
+    pub renewable_energy_investment: f64,
+    pub renewable_energy_depreciation: f64,
+    /// Biodiversity intactness lost and regained [1 / year]
+    pub biodiversity_loss: f64,
+    pub biodiversity_recovery: f64,
+    /// Net immigration into cohort 15–44 [persons / year]
+    pub net_migration: f64,
+}
+
+impl Flows {
+    /// Number of flows.
+    pub const N: usize = 31;
+
+    /// Field names, in [`Flows::to_vec`] order.
+    pub const NAMES: [&'static str; Self::N] = [
+        "births",
+        "deaths_0_14",
+        "deaths_15_44",
+        "deaths_45_64",
+        "deaths_65_plus",
+        "aging_0_to_15",
+        "aging_15_to_45",
+        "aging_45_to_65",
+        "industrial_investment",
+        "industrial_depreciation",
+        "service_investment",
+        "service_depreciation",
+        "land_development",
+        "land_erosion",
+        "land_fertility_degradation",
+        "land_fertility_regeneration",
+        "resource_extraction",
+        "pollution_generation",
+        "pollution_assimilation",
+        "labor_utilization_adjustment",
+        "carbon_emissions",
+        "carbon_uptake",
+        "surface_warming",
+        "deep_ocean_warming",
+        "fossil_energy_investment",
+        "fossil_energy_depreciation",
+        "renewable_energy_investment",
+        "renewable_energy_depreciation",
+        "biodiversity_loss",
+        "biodiversity_recovery",
+        "net_migration",
+    ];
+
+    pub fn to_vec(&self) -> Vec<f64> {
+        vec![
+            self.births,
+            self.deaths_0_14,
+            self.deaths_15_44,
+            self.deaths_45_64,
+            self.deaths_65_plus,
+            self.aging_0_to_15,
+            self.aging_15_to_45,
+            self.aging_45_to_65,
+            self.industrial_investment,
+            self.industrial_depreciation,
+            self.service_investment,
+            self.service_depreciation,
+            self.land_development,
+            self.land_erosion,
+            self.land_fertility_degradation,
+            self.land_fertility_regeneration,
+            self.resource_extraction,
+            self.pollution_generation,
+            self.pollution_assimilation,
+            self.labor_utilization_adjustment,
+            self.carbon_emissions,
+            self.carbon_uptake,
+            self.surface_warming,
+            self.deep_ocean_warming,
+            self.fossil_energy_investment,
+            self.fossil_energy_depreciation,
+            self.renewable_energy_investment,
+            self.renewable_energy_depreciation,
+            self.biodiversity_loss,
+            self.biodiversity_recovery,
+            self.net_migration,
+        ]
+    }
+
+    pub fn from_vec(v: &[f64]) -> Self {
+        assert_eq!(v.len(), Self::N);
+        Self {
+            births: v[0],
+            deaths_0_14: v[1],
+            deaths_15_44: v[2],
+            deaths_45_64: v[3],
+            deaths_65_plus: v[4],
+            aging_0_to_15: v[5],
+            aging_15_to_45: v[6],
+            aging_45_to_65: v[7],
+            industrial_investment: v[8],
+            industrial_depreciation: v[9],
+            service_investment: v[10],
+            service_depreciation: v[11],
+            land_development: v[12],
+            land_erosion: v[13],
+            land_fertility_degradation: v[14],
+            land_fertility_regeneration: v[15],
+            resource_extraction: v[16],
+            pollution_generation: v[17],
+            pollution_assimilation: v[18],
+            labor_utilization_adjustment: v[19],
+            carbon_emissions: v[20],
+            carbon_uptake: v[21],
+            surface_warming: v[22],
+            deep_ocean_warming: v[23],
+            fossil_energy_investment: v[24],
+            fossil_energy_depreciation: v[25],
+            renewable_energy_investment: v[26],
+            renewable_energy_depreciation: v[27],
+            biodiversity_loss: v[28],
+            biodiversity_recovery: v[29],
+            net_migration: v[30],
+        }
+    }
+
+    /// Whether every flow is zero (e.g. cleared to shrink a payload).
+    pub fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Flow by field name (e.g. `"land_erosion"`).
+    pub fn get(&self, name: &str) -> Option<f64> {
+        let i = Self::NAMES.iter().position(|n| *n == name)?;
+        Some(self.to_vec()[i])
+    }
+
+    /// Net rate of change of each stock, in [`WorldState::to_vec`] order.
+    pub fn net(&self) -> Vec<f64> {
+        vec![
+            self.births - self.aging_0_to_15 - self.deaths_0_14,
+            self.aging_0_to_15 - self.aging_15_to_45 - self.deaths_15_44 + self.net_migration,
+            self.aging_15_to_45 - self.aging_45_to_65 - self.deaths_45_64,
+            self.aging_45_to_65 - self.deaths_65_plus,
+            self.industrial_investment - self.industrial_depreciation,
+            self.service_investment - self.service_depreciation,
+            self.land_development - self.land_erosion,
+            -self.land_development,
+            self.land_fertility_regeneration - self.land_fertility_degradation,
+            -self.resource_extraction,
+            self.pollution_generation - self.pollution_assimilation,
+            self.labor_utilization_adjustment,
+            self.carbon_emissions - self.carbon_uptake,
+            self.carbon_uptake,
+            self.surface_warming,
+            self.deep_ocean_warming,
+            self.fossil_energy_investment - self.fossil_energy_depreciation,
+            self.renewable_energy_investment - self.renewable_energy_depreciation,
+            self.biodiversity_recovery - self.biodiversity_loss,
+        ]
+    }
+}
+
+/// The intermediate factors each sector multiplies together, mostly lookup
+/// table outputs [dimensionless unless noted].
+///
+/// `life_expectancy = 20 × lem_food × lem_health × lem_crowding × lem_pollution ×
+/// lem_climate`,
+/// `fertility_rate = desired_family_size × education_fertility × fp_multiplier ×
+/// food_fertility` and
+/// `land_yield = 600 × land_fertility_ratio × yield_multiplier_capital ×
+/// yield_multiplier_pollution × yield_multiplier_climate ×
+/// agricultural_technology` (before clamping) — see
+/// [`crate::attribution`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+pub struct Multipliers {
+    /// Life expectancy multipliers from food, health services, crowding
+    /// and pollution
+    pub lem_food: f64,
+    pub lem_health: f64,
+    pub lem_crowding: f64,
+    pub lem_pollution: f64,
+    /// Life expectancy multiplier from warming
+    #[serde(default)]
+    pub lem_climate: f64,
+    /// Desired family size [children / woman]
+    pub desired_family_size: f64,
+    /// Family size multiplier from education services
+    #[serde(default)]
+    pub education_fertility: f64,
+    /// Fertility multiplier from family planning
+    pub fp_multiplier: f64,
+    /// Fertility multiplier from food
+    pub food_fertility: f64,
+    /// Land yield multipliers from agricultural inputs and pollution
+    pub yield_multiplier_capital: f64,
+    pub yield_multiplier_pollution: f64,
+    /// Land yield multiplier from warming
+    #[serde(default)]
+    pub yield_multiplier_climate: f64,
+    /// Land fertility relative to its inherent (1900) level
+    #[serde(default)]
+    pub land_fertility_ratio: f64,
+    /// Agricultural technology lever in effect
+    pub agricultural_technology: f64,
+    /// Fraction of industrial output allocated to agriculture
+    pub frac_to_agriculture: f64,
+    /// Land development cost multiplier
+    pub land_development_cost: f64,
+    /// Land erosion multiplier from yield intensity
+    pub erosion_multiplier: f64,
+    /// Fraction of agricultural inputs spent on land maintenance
+    #[serde(default)]
+    pub frac_to_land_maintenance: f64,
+    /// Land fertility regeneration time [years]
+    #[serde(default)]
+    pub fertility_regeneration_time: f64,
+    /// Capital-output ratio multiplier from resource depletion
+    pub cor_multiplier: f64,
+    /// Output per unit capital from technology progress since 1970
+    pub tech_multiplier: f64,
+    /// Fraction of industrial capital tied up in resource extraction
+    pub capital_fraction_resources: f64,
+    /// Fraction of industrial output invested in services
+    pub frac_to_services: f64,
+    /// Fraction of service output spent on health (the rest funds education)
+    #[serde(default)]
+    pub frac_services_health: f64,
+    /// Pollution generation multipliers from industry and agriculture
+    pub pollution_gen_industry: f64,
+    pub pollution_gen_agriculture: f64,
+    /// Pollution assimilation time [years]
+    pub assimilation_time: f64,
+    /// Output per worker from education; divides the jobs per unit capital
+    #[serde(default)]
+    pub labor_productivity: f64,
+    /// Fossil energy return on investment, from resource depletion
+    #[serde(default)]
+    pub fossil_eroi: f64,
+    /// Fraction of potential industrial output the energy supply sustains
+    #[serde(default)]
+    pub energy_output_multiplier: f64,
+    /// Fraction of industrial output invested in energy capital
+    #[serde(default)]
+    pub frac_to_energy: f64,
+}
+
+impl Multipliers {
+    /// Number of multipliers.
+    pub const N: usize = 31;
+
+    /// Field names, in [`Multipliers::to_vec`] order.
+    pub const NAMES: [&'static str; Self::N] = [
+        "lem_food",
+        "lem_health",
+        "lem_crowding",
+        "lem_pollution",
+        "lem_climate",
+        "desired_family_size",
+        "education_fertility",
+        "fp_multiplier",
+        "food_fertility",
+        "yield_multiplier_capital",
+        "yield_multiplier_pollution",
+        "yield_multiplier_climate",
+        "land_fertility_ratio",
+        "agricultural_technology",
+        "frac_to_agriculture",
+        "land_development_cost",
+        "erosion_multiplier",
+        "frac_to_land_maintenance",
+        "fertility_regeneration_time",
+        "cor_multiplier",
+        "tech_multiplier",
+        "capital_fraction_resources",
+        "frac_to_services",
+        "frac_services_health",
+        "pollution_gen_industry",
+        "pollution_gen_agriculture",
+        "assimilation_time",
+        "labor_productivity",
+        "fossil_eroi",
+        "energy_output_multiplier",
+        "frac_to_energy",
+    ];
+
+    pub fn to_vec(&self) -> Vec<f64> {
+        vec![
+            self.lem_food,
+            self.lem_health,
+            self.lem_crowding,
+            self.lem_pollution,
+            self.lem_climate,
+            self.desired_family_size,
+            self.education_fertility,
+            self.fp_multiplier,
+            self.food_fertility,
+            self.yield_multiplier_capital,
+            self.yield_multiplier_pollution,
+            self.yield_multiplier_climate,
+            self.land_fertility_ratio,
+            self.agricultural_technology,
+            self.frac_to_agriculture,
+            self.land_development_cost,
+            self.erosion_multiplier,
+            self.frac_to_land_maintenance,
+            self.fertility_regeneration_time,
+            self.cor_multiplier,
+            self.tech_multiplier,
+            self.capital_fraction_resources,
+            self.frac_to_services,
+            self.frac_services_health,
+            self.pollution_gen_industry,
+            self.pollution_gen_agriculture,
+            self.assimilation_time,
+            self.labor_productivity,
+            self.fossil_eroi,
+            self.energy_output_multiplier,
+            self.frac_to_energy,
+        ]
+    }
+
+    /// Whether every multiplier is zero (e.g. cleared to shrink a payload).
+    pub fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Multiplier by field name (e.g. `"lem_food"`).
+    pub fn get(&self, name: &str) -> Option<f64> {
+        let i = Self::NAMES.iter().position(|n| *n == name)?;
+        Some(self.to_vec()[i])
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Vec conversion for RK4 solver
 // ---------------------------------------------------------------------------
 
 impl WorldState {
     /// The number of state variables (excluding `time`, which is tracked separately).
-    pub const N: usize = 10;
+    pub const N: usize = 19;
+
+    /// `(sector, variable)` name of each entry of [`WorldState::to_vec`].
+    pub const STOCK_NAMES: [(&'static str, &'static str); Self::N] = [
+        ("population", "cohort_0_14"),
+        ("population", "cohort_15_44"),
+        ("population", "cohort_45_64"),
+        ("population", "cohort_65_plus"),
+        ("capital", "industrial_capital"),
+        ("capital", "service_capital"),
+        ("agriculture", "arable_land"),
+        ("agriculture", "potentially_arable_land"),
+        ("agriculture", "land_fertility"),
+        ("resources", "nonrenewable_resources"),
+        ("pollution", "persistent_pollution"),
+        ("labor", "perceived_utilization"),
+        ("climate", "atmospheric_carbon"),
+        ("climate", "sink_carbon"),
+        ("climate", "temperature_anomaly"),
+        ("climate", "deep_ocean_temperature"),
+        ("energy", "fossil_capital"),
+        ("energy", "renewable_capital"),
+        ("biodiversity", "intactness"),
+    ];
 
     /// Extract the integrable state variables into a flat `Vec<f64>`.
     /// `time` is not included — the solver manages time separately.
@@ -117,17 +715,30 @@ impl WorldState {
             // Capital (2 stocks)
             self.capital.industrial_capital,
             self.capital.service_capital,
-            // Agriculture (2 stocks)
+            // Agriculture (3 stocks)
             self.agriculture.arable_land,
             self.agriculture.potentially_arable_land,
+            self.agriculture.land_fertility,
             // Resources (1 stock)
             self.resources.nonrenewable_resources,
             // Pollution (1 stock)
             self.pollution.persistent_pollution,
+            // Labor (1 stock: the perception delay)
+            self.labor.perceived_utilization,
+            // Climate (4 stocks: carbon boxes and temperature layers)
+            self.climate.atmospheric_carbon,
+            self.climate.sink_carbon,
+            self.climate.temperature_anomaly,
+            self.climate.deep_ocean_temperature,
+            // Energy (2 capital stocks)
+            self.energy.fossil_capital,
+            self.energy.renewable_capital,
+            // Biodiversity (1 stock)
+            self.biodiversity.intactness,
         ]
     }
 
-    /// Reconstruct state from a flat vec (only the 10 ODE stocks).
+    /// Reconstruct state from a flat vec (only the 19 ODE stocks).
     /// Derived/auxiliary fields are left at their defaults — they will be
     /// computed by the derivative function before use.
     pub fn from_vec(time: f64, v: &[f64]) -> Self {
@@ -147,11 +758,24 @@ impl WorldState {
 
         s.agriculture.arable_land = v[6].max(0.0);
         s.agriculture.potentially_arable_land = v[7].max(0.0);
+        s.agriculture.land_fertility = v[8].max(0.0);
+
+        s.resources.nonrenewable_resources = v[9].max(0.0);
+        s.resources.fraction_remaining = v[9].clamp(0.0, 1.0);
+
+        s.pollution.persistent_pollution = v[10].max(0.0);
+
+        s.labor.perceived_utilization = v[11].max(0.0);
+
+        s.climate.atmospheric_carbon = v[12].max(0.0);
+        s.climate.sink_carbon = v[13].max(0.0);
+        s.climate.temperature_anomaly = v[14].max(MIN_TEMPERATURE_ANOMALY);
+        s.climate.deep_ocean_temperature = v[15].max(MIN_TEMPERATURE_ANOMALY);
 
-        s.resources.nonrenewable_resources = v[8].max(0.0);
-        s.resources.fraction_remaining = v[8].clamp(0.0, 1.0);
+        s.energy.fossil_capital = v[16].max(0.0);
+        s.energy.renewable_capital = v[17].max(0.0);
 
-        s.pollution.persistent_pollution = v[9].max(0.0);
+        s.biodiversity.intactness = v[18].clamp(0.0, 1.0);
         s
     }
 
@@ -164,6 +788,15 @@ impl WorldState {
             agriculture: AgricultureState::default(),
             resources: ResourceState::default(),
             pollution: PollutionState::default(),
+            labor: LaborState::default(),
+            climate: ClimateState::default(),
+            energy: EnergyState::default(),
+            biodiversity: BiodiversityState::default(),
+            welfare: WelfareState::default(),
+            inequality: InequalityState::default(),
+            trade: TradeState::default(),
+            flows: Flows::default(),
+            multipliers: Multipliers::default(),
         }
     }
 }
@@ -177,6 +810,15 @@ impl Default for WorldState {
             agriculture: AgricultureState::default(),
             resources: ResourceState::default(),
             pollution: PollutionState::default(),
+            labor: LaborState::default(),
+            climate: ClimateState::default(),
+            energy: EnergyState::default(),
+            biodiversity: BiodiversityState::default(),
+            welfare: WelfareState::default(),
+            inequality: InequalityState::default(),
+            trade: TradeState::default(),
+            flows: Flows::default(),
+            multipliers: Multipliers::default(),
         }
     }
 }
@@ -196,8 +838,17 @@ impl std::ops::Add for WorldState {
         self.capital.service_capital += rhs.capital.service_capital;
         self.agriculture.arable_land += rhs.agriculture.arable_land;
         self.agriculture.potentially_arable_land += rhs.agriculture.potentially_arable_land;
+        self.agriculture.land_fertility += rhs.agriculture.land_fertility;
         self.resources.nonrenewable_resources += rhs.resources.nonrenewable_resources;
         self.pollution.persistent_pollution += rhs.pollution.persistent_pollution;
+        self.labor.perceived_utilization += rhs.labor.perceived_utilization;
+        self.climate.atmospheric_carbon += rhs.climate.atmospheric_carbon;
+        self.climate.sink_carbon += rhs.climate.sink_carbon;
+        self.climate.temperature_anomaly += rhs.climate.temperature_anomaly;
+        self.climate.deep_ocean_temperature += rhs.climate.deep_ocean_temperature;
+        self.energy.fossil_capital += rhs.energy.fossil_capital;
+        self.energy.renewable_capital += rhs.energy.renewable_capital;
+        self.biodiversity.intactness += rhs.biodiversity.intactness;
         self
     }
 }
@@ -213,8 +864,17 @@ impl std::ops::Mul<f64> for WorldState {
         self.capital.service_capital *= rhs;
         self.agriculture.arable_land *= rhs;
         self.agriculture.potentially_arable_land *= rhs;
+        self.agriculture.land_fertility *= rhs;
         self.resources.nonrenewable_resources *= rhs;
         self.pollution.persistent_pollution *= rhs;
+        self.labor.perceived_utilization *= rhs;
+        self.climate.atmospheric_carbon *= rhs;
+        self.climate.sink_carbon *= rhs;
+        self.climate.temperature_anomaly *= rhs;
+        self.climate.deep_ocean_temperature *= rhs;
+        self.energy.fossil_capital *= rhs;
+        self.energy.renewable_capital *= rhs;
+        self.biodiversity.intactness *= rhs;
         self
     }
 }
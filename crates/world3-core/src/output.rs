@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
 
-use crate::model::{params::ScenarioParams, state::WorldState};
+use crate::events::Event;
+use crate::ledger::FlowLedger;
+use crate::model::{
+    params::ScenarioParams,
+    state::{Flows, Multipliers, WorldState},
+};
+use crate::solver::traits::PartialTrajectory;
 
 /// A complete simulation run: the time series of all world states.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,11 +21,21 @@ pub struct SimulationOutput {
     pub params: ScenarioParams,
     /// ISO-8601 timestamp when the simulation was computed
     pub computed_at: String,
+    /// Events located during the run, in order of occurrence
+    #[serde(default)]
+    pub events: Vec<Event>,
+    /// Solver error that cut a partial run short; `states` end before it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Cumulative flows and per-stock accounting
+    #[serde(default)]
+    pub ledger: FlowLedger,
 }
 
 impl SimulationOutput {
     pub fn new(states: Vec<WorldState>, params: ScenarioParams) -> Self {
         let timeline = states.iter().map(|s| s.time).collect();
+        let ledger = FlowLedger::from_states(&states);
         Self {
             scenario_id: params.meta.id.clone(),
             scenario_name: params.meta.name.clone(),
@@ -27,9 +43,21 @@ impl SimulationOutput {
             states,
             params,
             computed_at: chrono::Utc::now().to_rfc3339(),
+            events: Vec::new(),
+            error: None,
+            ledger,
         }
     }
 
+    /// Output of a run that may have stopped early: keeps the states and
+    /// events computed before the failure and records the error message.
+    pub fn from_partial(partial: PartialTrajectory, params: ScenarioParams) -> Self {
+        let mut output = Self::new(partial.states, params);
+        output.events = partial.events;
+        output.error = partial.error.map(|e| e.to_string());
+        output
+    }
+
     /// Return the state closest to the requested year.
     pub fn state_at_year(&self, year: f64) -> Option<&WorldState> {
         self.states
@@ -42,8 +70,35 @@ impl SimulationOutput {
             })
     }
 
+    /// Total of each flow between the output states nearest `from_year` and
+    /// `to_year` (e.g. births over 2000–2050).
+    pub fn cumulative_flows(&self, from_year: f64, to_year: f64) -> Option<Flows> {
+        let (from, to) = (self.index_at_year(from_year)?, self.index_at_year(to_year)?);
+        let (a, b) = (
+            self.ledger.cumulative.get(from)?.to_vec(),
+            self.ledger.cumulative.get(to)?.to_vec(),
+        );
+        Some(Flows::from_vec(&b.iter().zip(&a).map(|(b, a)| b - a).collect::<Vec<_>>()))
+    }
+
+    fn index_at_year(&self, year: f64) -> Option<usize> {
+        self.timeline
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                (*a - year)
+                    .abs()
+                    .partial_cmp(&(*b - year).abs())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map(|(i, _)| i)
+    }
+
     /// Extract a single named variable as a time series.
-    /// Supported paths: "population.population", "agriculture.food_per_capita", etc.
+    /// Supported paths: "population.population", "agriculture.food_per_capita", etc.,
+    /// "flows.<flow>" (e.g. "flows.land_erosion"), "multipliers.<multiplier>"
+    /// (e.g. "multipliers.lem_food") and "inequality.groups.<index>.<field>"
+    /// (e.g. "inequality.groups.0.food_per_capita").
     pub fn extract_series(&self, path: &str) -> Vec<f64> {
         self.states
             .iter()
@@ -52,7 +107,99 @@ impl SimulationOutput {
     }
 }
 
-fn extract_field(state: &WorldState, path: &str) -> Option<f64> {
+/// State variables `extract_field` knows, in `WorldState` order.
+pub const STATE_SERIES: [&str; 61] = [
+    "population.population",
+    "population.birth_rate",
+    "population.death_rate",
+    "population.life_expectancy",
+    "population.fertility_rate",
+    "capital.industrial_capital",
+    "capital.service_capital",
+    "capital.industrial_output",
+    "capital.industrial_output_per_capita",
+    "capital.service_output_per_capita",
+    "capital.health_services_per_capita",
+    "capital.education_services_per_capita",
+    "agriculture.arable_land",
+    "agriculture.land_fertility",
+    "agriculture.food",
+    "agriculture.food_per_capita",
+    "agriculture.land_yield",
+    "resources.nonrenewable_resources",
+    "resources.fraction_remaining",
+    "pollution.persistent_pollution",
+    "pollution.pollution_index",
+    "labor.perceived_utilization",
+    "labor.industrial_jobs",
+    "labor.service_jobs",
+    "labor.agricultural_jobs",
+    "labor.jobs",
+    "labor.labor_force",
+    "labor.employment",
+    "labor.unemployment_rate",
+    "labor.labor_utilization",
+    "labor.capacity_utilization",
+    "climate.atmospheric_carbon",
+    "climate.sink_carbon",
+    "climate.temperature_anomaly",
+    "climate.deep_ocean_temperature",
+    "climate.carbon_emissions",
+    "climate.co2_concentration",
+    "climate.radiative_forcing",
+    "energy.fossil_capital",
+    "energy.renewable_capital",
+    "energy.energy_demand",
+    "energy.net_energy_supply",
+    "energy.energy_availability",
+    "energy.fossil_energy",
+    "energy.renewable_energy",
+    "energy.renewable_share",
+    "biodiversity.intactness",
+    "biodiversity.indicated_intactness",
+    "biodiversity.urban_land",
+    "biodiversity.absorption_land",
+    "biodiversity.ecological_footprint",
+    "welfare.gdp_per_capita",
+    "welfare.life_expectancy_index",
+    "welfare.education_index",
+    "welfare.gdp_index",
+    "welfare.human_welfare_index",
+    "inequality.gini",
+    "inequality.starving_fraction",
+    "trade.food_imports",
+    "trade.resource_imports",
+    "trade.net_migration",
+];
+
+/// Every `extract_series` path: the state variables, then `flows.<flow>`,
+/// then `multipliers.<multiplier>`.
+pub fn series_paths() -> Vec<String> {
+    STATE_SERIES
+        .iter()
+        .map(|p| p.to_string())
+        .chain(Flows::NAMES.iter().map(|f| format!("flows.{}", f)))
+        .chain(Multipliers::NAMES.iter().map(|m| format!("multipliers.{}", m)))
+        .collect()
+}
+
+/// `groups.<index>.<field>` of `state.inequality`, poorest group first (e.g.
+/// `groups.0.food_per_capita`).
+fn income_group_field(state: &WorldState, path: &str) -> Option<f64> {
+    let (index, field) = path.strip_prefix("groups.")?.split_once('.')?;
+    let group = state.inequality.groups.get(index.parse::<usize>().ok()?)?;
+    match field {
+        "income_share" => Some(group.income_share),
+        "industrial_output_per_capita" => Some(group.industrial_output_per_capita),
+        "food_per_capita" => Some(group.food_per_capita),
+        "life_expectancy" => Some(group.life_expectancy),
+        "fertility_rate" => Some(group.fertility_rate),
+        _ => None,
+    }
+}
+
+/// Value of a named variable (an `extract_series` path) in one state.
+pub fn extract_field(state: &WorldState, path: &str) -> Option<f64> {
     match path {
         "population.population" => Some(state.population.population),
         "population.birth_rate" => Some(state.population.birth_rate),
@@ -64,7 +211,12 @@ fn extract_field(state: &WorldState, path: &str) -> Option<f64> {
         "capital.industrial_output" => Some(state.capital.industrial_output),
         "capital.industrial_output_per_capita" => Some(state.capital.industrial_output_per_capita),
         "capital.service_output_per_capita" => Some(state.capital.service_output_per_capita),
+        "capital.health_services_per_capita" => Some(state.capital.health_services_per_capita),
+        "capital.education_services_per_capita" => {
+            Some(state.capital.education_services_per_capita)
+        }
         "agriculture.arable_land" => Some(state.agriculture.arable_land),
+        "agriculture.land_fertility" => Some(state.agriculture.land_fertility),
         "agriculture.food" => Some(state.agriculture.food),
         "agriculture.food_per_capita" => Some(state.agriculture.food_per_capita),
         "agriculture.land_yield" => Some(state.agriculture.land_yield),
@@ -72,6 +224,51 @@ fn extract_field(state: &WorldState, path: &str) -> Option<f64> {
         "resources.fraction_remaining" => Some(state.resources.fraction_remaining),
         "pollution.persistent_pollution" => Some(state.pollution.persistent_pollution),
         "pollution.pollution_index" => Some(state.pollution.pollution_index),
-        _ => None,
+        "labor.perceived_utilization" => Some(state.labor.perceived_utilization),
+        "labor.industrial_jobs" => Some(state.labor.industrial_jobs),
+        "labor.service_jobs" => Some(state.labor.service_jobs),
+        "labor.agricultural_jobs" => Some(state.labor.agricultural_jobs),
+        "labor.jobs" => Some(state.labor.jobs),
+        "labor.labor_force" => Some(state.labor.labor_force),
+        "labor.employment" => Some(state.labor.employment),
+        "labor.unemployment_rate" => Some(state.labor.unemployment_rate),
+        "labor.labor_utilization" => Some(state.labor.labor_utilization),
+        "labor.capacity_utilization" => Some(state.labor.capacity_utilization),
+        "climate.atmospheric_carbon" => Some(state.climate.atmospheric_carbon),
+        "climate.sink_carbon" => Some(state.climate.sink_carbon),
+        "climate.temperature_anomaly" => Some(state.climate.temperature_anomaly),
+        "climate.deep_ocean_temperature" => Some(state.climate.deep_ocean_temperature),
+        "climate.carbon_emissions" => Some(state.climate.carbon_emissions),
+        "climate.co2_concentration" => Some(state.climate.co2_concentration),
+        "climate.radiative_forcing" => Some(state.climate.radiative_forcing),
+        "energy.fossil_capital" => Some(state.energy.fossil_capital),
+        "energy.renewable_capital" => Some(state.energy.renewable_capital),
+        "energy.energy_demand" => Some(state.energy.energy_demand),
+        "energy.net_energy_supply" => Some(state.energy.net_energy_supply),
+        "energy.energy_availability" => Some(state.energy.energy_availability),
+        "energy.fossil_energy" => Some(state.energy.fossil_energy),
+        "energy.renewable_energy" => Some(state.energy.renewable_energy),
+        "energy.renewable_share" => Some(state.energy.renewable_share),
+        "biodiversity.intactness" => Some(state.biodiversity.intactness),
+        "biodiversity.indicated_intactness" => Some(state.biodiversity.indicated_intactness),
+        "biodiversity.urban_land" => Some(state.biodiversity.urban_land),
+        "biodiversity.absorption_land" => Some(state.biodiversity.absorption_land),
+        "biodiversity.ecological_footprint" => Some(state.biodiversity.ecological_footprint),
+        "welfare.gdp_per_capita" => Some(state.welfare.gdp_per_capita),
+        "welfare.life_expectancy_index" => Some(state.welfare.life_expectancy_index),
+        "welfare.education_index" => Some(state.welfare.education_index),
+        "welfare.gdp_index" => Some(state.welfare.gdp_index),
+        "welfare.human_welfare_index" => Some(state.welfare.human_welfare_index),
+        "inequality.gini" => Some(state.inequality.gini),
+        "inequality.starving_fraction" => Some(state.inequality.starving_fraction),
+        "trade.food_imports" => Some(state.trade.food_imports),
+        "trade.resource_imports" => Some(state.trade.resource_imports),
+        "trade.net_migration" => Some(state.trade.net_migration),
+        _ => match path.split_once('.')? {
+            ("flows", flow) => state.flows.get(flow),
+            ("multipliers", multiplier) => state.multipliers.get(multiplier),
+            ("inequality", group) => income_group_field(state, group),
+            _ => None,
+        },
     }
 }
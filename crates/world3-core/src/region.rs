@@ -0,0 +1,684 @@
+//! Multi-region runs.
+//!
+//! A [`RegionalSpec`] splits the world into regions — e.g. an industrialized
+//! North and a developing South — each with its own stocks and its own
+//! `ScenarioParams` overrides. Every region runs the full model on its share
+//! of the world's population, capital, land and resources (see
+//! [`RegionScale`]), and the regions are integrated together, linked by
+//!
+//! - food trade: each region's food per capita moves a fraction of the way
+//!   to the world average, exports matching imports
+//! - resource trade: a fraction of the world's resource use is drawn from the
+//!   pooled reserves, in proportion to what each region has left, and
+//!   extraction costs follow the pooled fraction remaining
+//! - migration: working-age adults move toward regions with a higher
+//!   industrial output per capita
+//! - a shared atmosphere: every region's emissions warm all of them
+//!
+//! Trade is settled at every derivative evaluation from the regions'
+//! autarky food output and resource use; each region's sectors then see its
+//! imports through [`TradeState`]. The coupled regions are one [`Regions`]
+//! system for the [`Simulation`] stepper, so a regional run takes the base
+//! scenario's solver, run control, events and partial results like a single
+//! one. Event variables name a region (`"North/population.population"`) or
+//! are summed over the regions (`"population.population"`).
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::events::Event;
+use crate::lookup::tables::WorldLookupTables;
+use crate::model::{
+    derivatives::{compute_auxiliaries, evaluate, flows},
+    params::{RegionScale, ScenarioParams},
+    sectors::resources,
+    state::{TradeState, WorldState},
+};
+use crate::output::{extract_field, SimulationOutput};
+use crate::simulation::{Simulation, StepMethod, System};
+use crate::solver::{check_stocks, PartialTrajectory, SolverError};
+
+/// The climate stocks, in [`WorldState::to_vec`] order, starting with
+/// atmospheric carbon. There is one atmosphere and one ocean, so the coupled
+/// system holds them once, after every region's own stocks.
+const CLIMATE: Range<usize> = 12..16;
+/// Stocks each region holds for itself
+const OWN: usize = WorldState::N - (CLIMATE.end - CLIMATE.start);
+/// Tolerance on the shares of the world summing to one
+const SHARE_EPS: f64 = 1e-6;
+/// What a [`RegionSpec`] holds shares of
+const SHARED_STOCKS: [&str; 4] = ["population", "capital", "land", "resources"];
+
+/// One region and its share of the world in 1900.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionSpec {
+    pub name: String,
+    /// Share of the world population [0..1]
+    pub population: f64,
+    /// Share of the industrial, service and energy capital [0..1]
+    pub capital: f64,
+    /// Share of the arable and potentially arable land, and of the
+    /// persistent pollution on it [0..1]
+    pub land: f64,
+    /// Share of the nonrenewable resources [0..1]
+    pub resources: f64,
+    /// Parameter overrides, by tunable `ScenarioParams` field name
+    #[serde(default)]
+    pub overrides: BTreeMap<String, f64>,
+}
+
+impl RegionSpec {
+    pub fn new(
+        name: impl Into<String>,
+        population: f64,
+        capital: f64,
+        land: f64,
+        resources: f64,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            population,
+            capital,
+            land,
+            resources,
+            overrides: BTreeMap::new(),
+        }
+    }
+
+    /// Override `field` for this region.
+    pub fn with(mut self, field: impl Into<String>, value: f64) -> Self {
+        self.overrides.insert(field.into(), value);
+        self
+    }
+
+    /// The region's share of the world's 1900 stocks. Resources stay at the
+    /// region's own endowment (1.0); climate, perceived utilization and
+    /// biodiversity intactness are intensive and copied.
+    pub fn initial_state(&self, world: &WorldState) -> WorldState {
+        let mut s = world.clone();
+        let pop = &mut s.population;
+        pop.cohort_0_14 *= self.population;
+        pop.cohort_15_44 *= self.population;
+        pop.cohort_45_64 *= self.population;
+        pop.cohort_65_plus *= self.population;
+        pop.population = pop.cohort_0_14 + pop.cohort_15_44 + pop.cohort_45_64 + pop.cohort_65_plus;
+        s.capital.industrial_capital *= self.capital;
+        s.capital.service_capital *= self.capital;
+        s.energy.fossil_capital *= self.capital;
+        s.energy.renewable_capital *= self.capital;
+        s.agriculture.arable_land *= self.land;
+        s.agriculture.potentially_arable_land *= self.land;
+        s.pollution.persistent_pollution *= self.land;
+        s
+    }
+
+    fn params(&self, base: &ScenarioParams) -> Result<ScenarioParams, String> {
+        let mut params = base.clone();
+        for (field, value) in &self.overrides {
+            if !params.set_value(field, *value) {
+                return Err(format!("'{}' is not a tunable parameter", field));
+            }
+        }
+        params.region = RegionScale {
+            population: self.population,
+            land: self.land,
+            resources: self.resources,
+        };
+        params.meta.name = self.name.clone();
+        Ok(params)
+    }
+}
+
+/// How the regions are linked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TradeSpec {
+    /// Fraction of the gap to the world's average food per capita that trade
+    /// closes [0..1]
+    #[serde(default)]
+    pub food_trade: f64,
+    /// Fraction of resource use drawn from the pooled world reserves [0..1]
+    #[serde(default)]
+    pub resource_trade: f64,
+    /// Working-age migration per unit of log income ratio [1 / year]
+    #[serde(default)]
+    pub migration_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionalSpec {
+    /// Regions, whose shares of each stock sum to one
+    pub regions: Vec<RegionSpec>,
+    #[serde(default)]
+    pub trade: TradeSpec,
+}
+
+impl RegionalSpec {
+    /// An industrialized North holding most of the capital and a populous
+    /// South holding most of the land and resources, trading food and
+    /// resources.
+    pub fn north_south() -> Self {
+        Self {
+            regions: vec![
+                RegionSpec::new("North", 0.3, 0.8, 0.35, 0.4),
+                RegionSpec::new("South", 0.7, 0.2, 0.65, 0.6),
+            ],
+            trade: TradeSpec {
+                food_trade: 0.3,
+                resource_trade: 0.5,
+                migration_rate: 0.0,
+            },
+        }
+    }
+
+    fn validate(&self, base: &ScenarioParams) -> Result<Vec<ScenarioParams>, String> {
+        if self.regions.is_empty() {
+            return Err("at least one region is required".into());
+        }
+        let mut totals = [0.0; 4];
+        for region in &self.regions {
+            let shares = [
+                region.population,
+                region.capital,
+                region.land,
+                region.resources,
+            ];
+            for (k, value) in shares.into_iter().enumerate() {
+                if !(value > 0.0 && value <= 1.0) {
+                    return Err(format!(
+                        "region '{}': {} share must be in (0, 1], got {}",
+                        region.name, SHARED_STOCKS[k], value
+                    ));
+                }
+                totals[k] += value;
+            }
+        }
+        for (stock, total) in SHARED_STOCKS.iter().zip(totals) {
+            if (total - 1.0).abs() > SHARE_EPS {
+                return Err(format!("{} shares sum to {}, not 1", stock, total));
+            }
+        }
+        for (i, region) in self.regions.iter().enumerate() {
+            if self.regions[..i].iter().any(|r| r.name == region.name) {
+                return Err(format!("duplicate region '{}'", region.name));
+            }
+        }
+        let trade = &self.trade;
+        for (name, value) in [
+            ("food_trade", trade.food_trade),
+            ("resource_trade", trade.resource_trade),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(format!("{} must be in [0, 1], got {}", name, value));
+            }
+        }
+        if trade.migration_rate.is_nan() || trade.migration_rate < 0.0 {
+            return Err(format!(
+                "migration_rate must be >= 0, got {}",
+                trade.migration_rate
+            ));
+        }
+        self.regions
+            .iter()
+            .map(|r| {
+                r.params(base)
+                    .and_then(|params| params.validate_schedules().map(|()| params))
+                    .map_err(|e| format!("region '{}': {}", r.name, e))
+            })
+            .collect()
+    }
+}
+
+/// One trajectory per region, on a common output grid.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionalOutput {
+    pub timeline: Vec<f64>,
+    /// In spec order; each carries its region's parameters and flow ledger
+    pub regions: Vec<SimulationOutput>,
+    /// Events located during the run, in order of occurrence
+    #[serde(default)]
+    pub events: Vec<Event>,
+    /// Solver error that cut a partial run short; the regions end before it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RegionalOutput {
+    /// Output of a regional [`Simulation`] that may have stopped early, with
+    /// each region's parameters in spec order (see [`Regions::region_params`]).
+    pub fn from_partial(
+        partial: PartialTrajectory<Vec<WorldState>>,
+        params: Vec<ScenarioParams>,
+    ) -> Self {
+        let timeline = partial.states.iter().map(|regions| regions[0].time).collect();
+        let mut states = vec![Vec::with_capacity(partial.states.len()); params.len()];
+        for regions in partial.states {
+            for (region, state) in states.iter_mut().zip(regions) {
+                region.push(state);
+            }
+        }
+        Self {
+            timeline,
+            regions: states
+                .into_iter()
+                .zip(params)
+                .map(|(states, params)| SimulationOutput::new(states, params))
+                .collect(),
+            events: partial.events,
+            error: partial.error.map(|e| e.to_string()),
+        }
+    }
+
+    /// The run of the region called `name`.
+    pub fn region(&self, name: &str) -> Option<&SimulationOutput> {
+        self.regions.iter().find(|r| r.scenario_name == name)
+    }
+
+    /// `path` summed over the regions, per output year. Only meaningful for
+    /// extensive variables (population, output, food, trade flows…).
+    pub fn total(&self, path: &str) -> Vec<f64> {
+        let mut total = vec![0.0; self.timeline.len()];
+        for region in &self.regions {
+            for (sum, value) in total.iter_mut().zip(region.extract_series(path)) {
+                *sum += value;
+            }
+        }
+        total
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RegionError {
+    #[error("Invalid regional spec: {0}")]
+    InvalidSpec(String),
+    /// Stock errors name the region in their sector, e.g. `North/capital`
+    #[error(transparent)]
+    Solver(#[from] SolverError),
+}
+
+/// Run the regions of `spec` together from their shares of the world state
+/// `initial`, each with `base` plus its overrides.
+pub fn run_regions(
+    initial: &WorldState,
+    base: &ScenarioParams,
+    spec: &RegionalSpec,
+    tables: Arc<WorldLookupTables>,
+) -> Result<RegionalOutput, RegionError> {
+    let sim = regional_simulation(initial, base, spec, tables)?;
+    let params = sim.system().region_params().to_vec();
+    let mut partial = sim.collect_partial();
+    match partial.error.take() {
+        Some(e) => Err(e.into()),
+        None => Ok(RegionalOutput::from_partial(partial, params)),
+    }
+}
+
+/// The regions of `spec` as a [`Simulation`], ready to run from their shares
+/// of `initial`. `base` sets the solver, the years and the output grid; the
+/// simulation takes run control and events like a single run, and
+/// [`RegionalOutput::from_partial`] collects it.
+pub fn regional_simulation(
+    initial: &WorldState,
+    base: &ScenarioParams,
+    spec: &RegionalSpec,
+    tables: Arc<WorldLookupTables>,
+) -> Result<Simulation<Regions>, RegionError> {
+    let params = spec.validate(base).map_err(RegionError::InvalidSpec)?;
+    let system = Regions {
+        base: base.clone(),
+        names: spec.regions.iter().map(|r| r.name.clone()).collect(),
+        params,
+        trade: spec.trade,
+        tables,
+    };
+    let state = system.link(spec.regions.iter().map(|r| r.initial_state(initial)).collect());
+    let method = StepMethod::from_kind(base.solver);
+    Ok(Simulation::with_system(system, state, method))
+}
+
+/// The regions of a [`RegionalSpec`] as one ODE system.
+///
+/// The stock vector holds every region's own stocks in spec order, then the
+/// shared climate stocks once. A state is one `WorldState` per region, each
+/// with a copy of the climate and its trade.
+pub struct Regions {
+    /// Run settings
+    base: ScenarioParams,
+    names: Vec<String>,
+    params: Vec<ScenarioParams>,
+    trade: TradeSpec,
+    tables: Arc<WorldLookupTables>,
+}
+
+impl Regions {
+    /// Each region's parameters, in spec order.
+    pub fn region_params(&self) -> &[ScenarioParams] {
+        &self.params
+    }
+
+    /// Region `r`'s part of a stock (or derivative) vector, in
+    /// [`WorldState::to_vec`] order.
+    fn region_stocks(&self, y: &[f64], r: usize) -> Vec<f64> {
+        let own = &y[r * OWN..(r + 1) * OWN];
+        let climate = &y[self.params.len() * OWN..];
+        [&own[..CLIMATE.start], climate, &own[CLIMATE.start..]].concat()
+    }
+
+    /// Stack per-region vectors in `WorldState::to_vec` order, keeping the
+    /// climate part once.
+    fn stack(&self, regions: &[Vec<f64>], climate: &[f64]) -> Vec<f64> {
+        let mut y = Vec::with_capacity(regions.len() * OWN + climate.len());
+        for v in regions {
+            y.extend_from_slice(&v[..CLIMATE.start]);
+            y.extend_from_slice(&v[CLIMATE.end..]);
+        }
+        y.extend_from_slice(climate);
+        y
+    }
+
+    /// Settle the trade between `regions` and compute each one's auxiliaries
+    /// with it. With no trade this is what the world model does to a state.
+    fn link(&self, regions: Vec<WorldState>) -> Vec<WorldState> {
+        let with = |mut s: WorldState, trade: TradeState, params: &ScenarioParams| {
+            s.trade = trade;
+            compute_auxiliaries(&mut s, params, &self.tables);
+            s
+        };
+        // Autarky auxiliaries: what each region produces and uses on its own
+        let autarky: Vec<WorldState> = regions
+            .iter()
+            .zip(&self.params)
+            .map(|(s, params)| with(s.clone(), TradeState::default(), params))
+            .collect();
+        let trades = self.settle(&autarky);
+        regions
+            .into_iter()
+            .zip(trades)
+            .zip(&self.params)
+            .map(|((s, trade), params)| with(s, trade, params))
+            .collect()
+    }
+
+    /// Balanced food and resource trade and migration between the regions.
+    fn settle(&self, states: &[WorldState]) -> Vec<TradeState> {
+        let mut trades = vec![TradeState::default(); states.len()];
+
+        // Food: close part of each region's gap to the world food per capita
+        let food: f64 = states.iter().map(|s| s.agriculture.food).sum();
+        let pop: f64 = states.iter().map(|s| s.population.population).sum();
+        if pop > 0.0 {
+            let world_fpc = food / pop;
+            for (trade, s) in trades.iter_mut().zip(states) {
+                trade.food_imports = self.trade.food_trade
+                    * (world_fpc * s.population.population - s.agriculture.food);
+            }
+        }
+
+        // Resources: part of the world's use comes out of the pooled reserves
+        let theta = self.trade.resource_trade;
+        if theta > 0.0 {
+            let uses: Vec<f64> = states
+                .iter()
+                .zip(&self.params)
+                .map(|(s, p)| resources::resource_use(s, &p.at(s.time)))
+                .collect();
+            let reserves: Vec<f64> = states
+                .iter()
+                .zip(&self.params)
+                .map(|(s, p)| {
+                    s.resources.nonrenewable_resources.clamp(0.0, 1.0) * p.region.resources
+                })
+                .collect();
+            let total_use: f64 = uses.iter().sum();
+            let total_reserves: f64 = reserves.iter().sum();
+            for (i, trade) in trades.iter_mut().enumerate() {
+                let share = self.params[i].region.resources;
+                let drawn = if total_reserves > 0.0 {
+                    (1.0 - theta) * uses[i] + theta * total_use * reserves[i] / total_reserves
+                } else {
+                    uses[i]
+                };
+                trade.resource_imports = (uses[i] - drawn) / share;
+                trade.resource_fraction_remaining =
+                    Some((1.0 - theta) * reserves[i] / share + theta * total_reserves);
+            }
+        }
+
+        // Migration toward higher income, pairwise
+        if self.trade.migration_rate > 0.0 {
+            for i in 0..states.len() {
+                for j in i + 1..states.len() {
+                    let (iopc_i, iopc_j) = (
+                        states[i].capital.industrial_output_per_capita,
+                        states[j].capital.industrial_output_per_capita,
+                    );
+                    if iopc_i <= 0.0 || iopc_j <= 0.0 {
+                        continue;
+                    }
+                    let (rich, poor) = if iopc_i >= iopc_j { (i, j) } else { (j, i) };
+                    let flow = self.trade.migration_rate
+                        * states[poor].population.cohort_15_44
+                        * (iopc_i / iopc_j).ln().abs();
+                    trades[rich].net_migration += flow;
+                    trades[poor].net_migration -= flow;
+                }
+            }
+        }
+        trades
+    }
+}
+
+impl System for Regions {
+    type State = Vec<WorldState>;
+
+    fn params(&self) -> &ScenarioParams {
+        &self.base
+    }
+
+    fn time(&self, regions: &Vec<WorldState>) -> f64 {
+        regions[0].time
+    }
+
+    fn stocks(&self, regions: &Vec<WorldState>) -> Vec<f64> {
+        let stocks: Vec<Vec<f64>> = regions.iter().map(WorldState::to_vec).collect();
+        self.stack(&stocks, &stocks[0][CLIMATE])
+    }
+
+    fn state(&self, t: f64, y: &[f64]) -> Vec<WorldState> {
+        let regions = (0..self.params.len())
+            .map(|r| WorldState::from_vec(t, &self.region_stocks(y, r)))
+            .collect();
+        self.link(regions)
+    }
+
+    fn slope(&self, regions: &Vec<WorldState>) -> Vec<f64> {
+        let evaluated: Vec<_> = regions
+            .iter()
+            .zip(&self.params)
+            .map(|(s, params)| evaluate(s, params, &self.tables))
+            .collect();
+        let derivatives: Vec<Vec<f64>> = evaluated.iter().map(|(d, _)| d.to_vec()).collect();
+        // One atmosphere takes the world's emissions; the carbon cycle and
+        // energy balance only read the shared stocks, so any region has them
+        let mut climate = derivatives[0][CLIMATE].to_vec();
+        let emissions: f64 = evaluated.iter().map(|(_, f)| f.carbon_emissions).sum();
+        climate[0] = emissions - evaluated[0].1.carbon_uptake;
+        self.stack(&derivatives, &climate)
+    }
+
+    fn check(&self, t: f64, y: &[f64], last_good: &Vec<WorldState>) -> Result<(), SolverError> {
+        for (r, name) in self.names.iter().enumerate() {
+            check_stocks(t, &self.region_stocks(y, r), &last_good[r]).map_err(|mut e| {
+                if let SolverError::Diverged { sector, .. }
+                | SolverError::NonFinite { sector, .. }
+                | SolverError::NegativeStock { sector, .. } = &mut e
+                {
+                    *sector = format!("{}/{}", name, sector);
+                }
+                e
+            })?;
+        }
+        Ok(())
+    }
+
+    fn observe(&self, regions: &Vec<WorldState>, path: &str) -> Option<f64> {
+        match path.split_once('/') {
+            Some((name, path)) => {
+                let r = self.names.iter().position(|n| n == name)?;
+                extract_field(&regions[r], path)
+            }
+            None => regions.iter().map(|s| extract_field(s, path)).sum(),
+        }
+    }
+
+    fn record(&self, regions: &mut Vec<WorldState>) {
+        for (s, params) in regions.iter_mut().zip(&self.params) {
+            s.flows = flows(s, params, &self.tables);
+        }
+        // Each region's books carry the world's emissions into the shared
+        // atmosphere, so its carbon balance closes
+        let emissions: f64 = regions.iter().map(|s| s.flows.carbon_emissions).sum();
+        for s in regions.iter_mut() {
+            s.flows.carbon_emissions = emissions;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::{CancelToken, RunControl};
+    use crate::model::initial::initial_conditions_1900;
+    use crate::model::params::SolverKind;
+    use crate::solver::rhs;
+
+    #[test]
+    fn test_north_south_trade_balances_and_shares_one_climate() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let initial = initial_conditions_1900();
+        let base = ScenarioParams::bau();
+
+        // A single region covering the world without trade is the world model
+        let world = RegionalSpec {
+            regions: vec![RegionSpec::new("World", 1.0, 1.0, 1.0, 1.0)],
+            trade: TradeSpec::default(),
+        };
+        let sim = regional_simulation(&initial, &base, &world, Arc::clone(&tables)).unwrap();
+        let system = sim.system();
+        let y = initial.to_vec();
+        let slope = system.rhs(1900.0, &system.stocks(&vec![initial.clone()]));
+        assert_eq!(system.region_stocks(&slope, 0), rhs(1900.0, &y, &base, &tables));
+
+        let mut spec = RegionalSpec::north_south();
+        spec.trade.migration_rate = 0.002;
+        let traded = run_regions(&initial, &base, &spec, Arc::clone(&tables)).unwrap();
+        spec.trade = TradeSpec::default();
+        let autarky = run_regions(&initial, &base, &spec, tables).unwrap();
+        assert_eq!(traded.timeline.len(), 201);
+        assert!(traded.error.is_none());
+
+        // The regions start from the world's stocks
+        let pop = traded.total("population.population");
+        assert!((pop[0] - initial.population.population).abs() < 1.0);
+
+        // Trade and migration balance between the regions every year
+        let food = traded.total("agriculture.food");
+        for (i, imports) in traded.total("trade.food_imports").iter().enumerate() {
+            assert!(imports.abs() < 1e-9 * food[i]);
+        }
+        for (i, migration) in traded.total("trade.net_migration").iter().enumerate() {
+            assert!(migration.abs() < 1e-9 * pop[i]);
+        }
+
+        let (north, south) = (
+            traded.region("North").unwrap(),
+            traded.region("South").unwrap(),
+        );
+        let start = |o: &RegionalOutput, name: &str, path: &str| {
+            o.region(name).unwrap().extract_series(path)[0]
+        };
+        // The South is fed by the North's surplus in 1900, and people move North
+        let fpc = "agriculture.food_per_capita";
+        assert!(start(&traded, "South", fpc) > start(&autarky, "South", fpc) + 50.0);
+        assert!(north.states[0].trade.food_imports < 0.0);
+        assert!(north.states[0].trade.net_migration > 0.0);
+        // ...and the North's extraction draws on the South's reserves
+        let nnr = |o: &RegionalOutput, name: &str| {
+            o.region(name).unwrap().states[100]
+                .resources
+                .nonrenewable_resources
+        };
+        assert!(nnr(&traded, "North") > nnr(&autarky, "North"));
+        assert!(nnr(&traded, "South") < nnr(&autarky, "South"));
+
+        // One atmosphere, and every region's books still close
+        for (n, s) in north.states.iter().zip(&south.states) {
+            assert_eq!(n.climate.temperature_anomaly, s.climate.temperature_anomaly);
+        }
+        // (against the stock's size: the North's young cohort ends the run
+        // close to where it started, so its net change is no scale)
+        for region in &traded.regions {
+            let start = region.states[0].to_vec();
+            for (b, y0) in region.ledger.balance.iter().zip(start) {
+                if b.sector == "population" || b.variable == "atmospheric_carbon" {
+                    assert!(
+                        b.residual.abs() < 1e-3 * b.change.abs().max(y0.abs()),
+                        "{}: {:?}",
+                        region.scenario_name,
+                        b
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_regional_run_takes_solver_control_and_events() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let initial = initial_conditions_1900();
+        let base = ScenarioParams {
+            solver: SolverKind::Rk45,
+            ..ScenarioParams::bau()
+        };
+        let spec = RegionalSpec::north_south();
+        assert!(WorldState::STOCK_NAMES[CLIMATE].iter().all(|(sector, _)| *sector == "climate"));
+        assert_eq!(WorldState::STOCK_NAMES[CLIMATE.start].1, "atmospheric_carbon");
+
+        let sim = regional_simulation(&initial, &base, &spec, Arc::clone(&tables)).unwrap();
+        // Two regions' own stocks, one climate
+        assert_eq!(sim.system().stocks(sim.state()).len(), 2 * OWN + CLIMATE.len());
+        let unknown = vec!["peak:East/population.population".parse().unwrap()];
+        assert!(matches!(sim.with_events(unknown), Err(SolverError::InvalidEvent(_))));
+
+        let cancel = CancelToken::new();
+        let token = cancel.clone();
+        let control = RunControl::new().with_cancel(cancel).with_progress(move |p| {
+            if p.year >= 2050.0 {
+                token.cancel();
+            }
+        });
+        let events = vec![
+            "below:North/trade.food_imports:0".parse().unwrap(),
+            "above:population.population:3e9".parse().unwrap(),
+        ];
+        let sim = regional_simulation(&initial, &base, &spec, tables)
+            .unwrap()
+            .with_events(events)
+            .unwrap()
+            .with_control(control);
+        let params = sim.system().region_params().to_vec();
+        let partial = RegionalOutput::from_partial(sim.collect_partial(), params);
+
+        assert!(partial.error.as_ref().unwrap().starts_with("Cancelled"));
+        let last = *partial.timeline.last().unwrap();
+        assert!((2050.0..2060.0).contains(&last), "{last}");
+        assert_eq!(partial.regions[1].states.len(), partial.timeline.len());
+        // The world passes 3 billion, summed over the regions
+        let world = partial.events.iter().find(|e| e.name.starts_with("above")).unwrap();
+        let pop = partial.total("population.population");
+        let year = partial.timeline.iter().position(|&t| t >= world.year).unwrap();
+        assert!(pop[year - 1] < 3e9 && pop[year] >= 3e9);
+    }
+}
@@ -0,0 +1,714 @@
+//! Global sensitivity analysis over the slider ranges.
+//!
+//! Both methods vary the parameters over the `min`..`max` ranges declared in
+//! [`parameter_descriptors`], working in unit coordinates (`0` = `min`,
+//! `1` = `max`), and score each run with scalar [`Metric`]s:
+//!
+//! - [`morris`] — elementary-effects screening: `mu_star` ranks how much a
+//!   parameter moves a metric, `sigma` flags non-linearity or interactions.
+//!   Costs `trajectories × (k + 1)` runs for `k` parameters.
+//! - [`sobol`] — variance-based first-order and total-order indices
+//!   (Saltelli sampling; Saltelli 2010 first-order and Jansen total-order
+//!   estimators). Costs `samples × (k + 2)` runs.
+//!
+//! Runs that fail are dropped from the estimates.
+//!
+//! [`elasticities`] is the local counterpart: central finite differences
+//! around the scenario itself, for every output series and year.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::batch::{default_threads, run_parallel, solve_one, solve_one_with};
+use crate::control::RunControl;
+use crate::lookup::tables::WorldLookupTables;
+use crate::model::params::{parameter_descriptors, ParameterDescriptor, ScenarioParams};
+use crate::model::state::WorldState;
+use crate::output::{series_paths, SimulationOutput};
+use crate::rng::Rng;
+use crate::solver::traits::SolverError;
+
+/// Scalar outcome of a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Metric {
+    /// Largest value of `variable` over the run
+    Max { variable: String },
+    /// Smallest value of `variable` over the run
+    Min { variable: String },
+    /// Value of `variable` at the output year nearest `year`
+    AtYear { variable: String, year: f64 },
+}
+
+impl Metric {
+    pub fn peak_population() -> Self {
+        Metric::Max {
+            variable: "population.population".into(),
+        }
+    }
+
+    pub fn nnr_fraction_2100() -> Self {
+        Metric::AtYear {
+            variable: "resources.fraction_remaining".into(),
+            year: 2100.0,
+        }
+    }
+
+    pub fn max_pollution_index() -> Self {
+        Metric::Max {
+            variable: "pollution.pollution_index".into(),
+        }
+    }
+
+    /// Label used in results, e.g. `max(population.population)`.
+    pub fn name(&self) -> String {
+        match self {
+            Metric::Max { variable } => format!("max({})", variable),
+            Metric::Min { variable } => format!("min({})", variable),
+            Metric::AtYear { variable, year } => format!("{}@{}", variable, year),
+        }
+    }
+
+    pub fn evaluate(&self, output: &SimulationOutput) -> f64 {
+        match self {
+            Metric::Max { variable } => {
+                output.extract_series(variable).into_iter().fold(f64::NAN, f64::max)
+            }
+            Metric::Min { variable } => {
+                output.extract_series(variable).into_iter().fold(f64::NAN, f64::min)
+            }
+            Metric::AtYear { variable, year } => output
+                .state_at_year(*year)
+                .and_then(|s| crate::output::extract_field(s, variable))
+                .unwrap_or(f64::NAN),
+        }
+    }
+}
+
+fn default_metrics() -> Vec<Metric> {
+    vec![
+        Metric::peak_population(),
+        Metric::nnr_fraction_2100(),
+        Metric::max_pollution_index(),
+    ]
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MorrisSpec {
+    /// Number of one-at-a-time trajectories
+    pub trajectories: usize,
+    /// Grid levels per parameter (even, ≥ 2)
+    #[serde(default = "default_levels")]
+    pub levels: usize,
+    #[serde(default)]
+    pub seed: u64,
+    /// Descriptor fields to vary; all descriptors when empty
+    #[serde(default)]
+    pub parameters: Vec<String>,
+    #[serde(default = "default_metrics")]
+    pub metrics: Vec<Metric>,
+}
+
+fn default_levels() -> usize {
+    4
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SobolSpec {
+    /// Base sample size `N`
+    pub samples: usize,
+    #[serde(default)]
+    pub seed: u64,
+    /// Descriptor fields to vary; all descriptors when empty
+    #[serde(default)]
+    pub parameters: Vec<String>,
+    #[serde(default = "default_metrics")]
+    pub metrics: Vec<Metric>,
+}
+
+/// Elementary-effect statistics of one parameter on one metric, in metric
+/// units per full slider range.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MorrisIndex {
+    pub parameter: String,
+    pub metric: String,
+    /// Mean effect
+    pub mu: f64,
+    /// Mean absolute effect — the importance ranking
+    pub mu_star: f64,
+    /// Standard deviation of the effects
+    pub sigma: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SobolIndex {
+    pub parameter: String,
+    pub metric: String,
+    /// Share of the metric's variance explained by the parameter alone
+    pub first_order: f64,
+    /// Share including all interactions with other parameters
+    pub total_order: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensitivityResult<I> {
+    /// One entry per (parameter, metric), grouped by parameter
+    pub indices: Vec<I>,
+    /// Model runs performed
+    pub runs: usize,
+    /// Runs that hit a solver error
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElasticitySpec {
+    /// `ScenarioParams::TUNABLE` fields to perturb; every unscheduled one
+    /// when empty
+    #[serde(default)]
+    pub parameters: Vec<String>,
+    /// `extract_series` paths; every series when empty
+    #[serde(default)]
+    pub variables: Vec<String>,
+    /// Relative perturbation `h`: parameters are run at `p (1 ± h)`
+    #[serde(default = "default_relative_step")]
+    pub relative_step: f64,
+}
+
+fn default_relative_step() -> f64 {
+    0.01
+}
+
+impl Default for ElasticitySpec {
+    fn default() -> Self {
+        Self {
+            parameters: Vec::new(),
+            variables: Vec::new(),
+            relative_step: default_relative_step(),
+        }
+    }
+}
+
+/// Local sensitivities of every series, indexed `[year][variable][parameter]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElasticityTable {
+    pub timeline: Vec<f64>,
+    pub variables: Vec<String>,
+    pub parameters: Vec<String>,
+    /// `(∂y / y) / (∂p / p)`; NaN (`null` in JSON) where `y` is zero or a
+    /// perturbed run failed
+    pub elasticities: Vec<Vec<Vec<f64>>>,
+    /// `∂y / ∂p` in the same layout — the informative figure for parameters
+    /// whose base value is zero, where the elasticity is zero by definition
+    pub derivatives: Vec<Vec<Vec<f64>>>,
+    /// Model runs performed, including the base run
+    pub runs: usize,
+    /// Perturbed runs that hit a solver error
+    pub failed: usize,
+}
+
+impl ElasticityTable {
+    /// Elasticity of `variable` to `parameter` at the output year nearest
+    /// `year`.
+    pub fn elasticity(&self, year: f64, variable: &str, parameter: &str) -> Option<f64> {
+        let t = self
+            .timeline
+            .iter()
+            .enumerate()
+            .min_by(|a, b| (a.1 - year).abs().total_cmp(&(b.1 - year).abs()))?
+            .0;
+        let v = self.variables.iter().position(|x| x == variable)?;
+        let p = self.parameters.iter().position(|x| x == parameter)?;
+        Some(self.elasticities[t][v][p])
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SensitivityError {
+    #[error("Invalid sensitivity analysis: {0}")]
+    InvalidSpec(String),
+    #[error("Base run failed: {0}")]
+    BaseRun(#[from] SolverError),
+}
+
+/// Morris elementary-effects screening around `base`.
+pub fn morris(
+    initial: &WorldState,
+    base: &ScenarioParams,
+    spec: &MorrisSpec,
+    tables: Arc<WorldLookupTables>,
+) -> Result<SensitivityResult<MorrisIndex>, SensitivityError> {
+    if spec.trajectories == 0 {
+        return Err(SensitivityError::InvalidSpec("trajectories must be positive".into()));
+    }
+    if spec.levels < 2 || spec.levels & 1 == 1 {
+        return Err(SensitivityError::InvalidSpec(format!(
+            "levels must be even and at least 2, got {}",
+            spec.levels
+        )));
+    }
+    let model = Model::new(initial, base, &spec.parameters, &spec.metrics, tables)?;
+    let mut rng = Rng::new(spec.seed);
+    let (points, effects) = morris_design(model.k(), spec.trajectories, spec.levels, &mut rng);
+    let (values, failed) = model.run(&points);
+    let indices = morris_indices(&values, &effects, model.k(), spec.metrics.len())
+        .into_iter()
+        .map(|(i, m, (mu, mu_star, sigma))| MorrisIndex {
+            parameter: model.fields[i].clone(),
+            metric: spec.metrics[m].name(),
+            mu,
+            mu_star,
+            sigma,
+        })
+        .collect();
+    Ok(SensitivityResult {
+        indices,
+        runs: points.len(),
+        failed,
+    })
+}
+
+/// Sobol first- and total-order indices around `base`.
+pub fn sobol(
+    initial: &WorldState,
+    base: &ScenarioParams,
+    spec: &SobolSpec,
+    tables: Arc<WorldLookupTables>,
+) -> Result<SensitivityResult<SobolIndex>, SensitivityError> {
+    if spec.samples < 2 {
+        return Err(SensitivityError::InvalidSpec("samples must be at least 2".into()));
+    }
+    let model = Model::new(initial, base, &spec.parameters, &spec.metrics, tables)?;
+    let mut rng = Rng::new(spec.seed);
+    let points = saltelli_design(model.k(), spec.samples, &mut rng);
+    let (values, failed) = model.run(&points);
+    let indices = sobol_indices(&values, model.k(), spec.samples, spec.metrics.len())
+        .into_iter()
+        .map(|(i, m, (first_order, total_order))| SobolIndex {
+            parameter: model.fields[i].clone(),
+            metric: spec.metrics[m].name(),
+            first_order,
+            total_order,
+        })
+        .collect();
+    Ok(SensitivityResult {
+        indices,
+        runs: points.len(),
+        failed,
+    })
+}
+
+/// Central-difference elasticities around `base`: one base run plus two
+/// runs per parameter. Parameters whose base value is zero are stepped by
+/// `relative_step` of their slider range (or by `relative_step` itself).
+pub fn elasticities(
+    initial: &WorldState,
+    base: &ScenarioParams,
+    spec: &ElasticitySpec,
+    tables: Arc<WorldLookupTables>,
+) -> Result<ElasticityTable, SensitivityError> {
+    elasticities_with(initial, base, spec, tables, &RunControl::default())
+}
+
+/// [`elasticities`] under `control`: cancelling stops every run, started
+/// or not, and fails the table with [`SolverError::Cancelled`] instead of
+/// counting the stopped runs as failures.
+pub fn elasticities_with(
+    initial: &WorldState,
+    base: &ScenarioParams,
+    spec: &ElasticitySpec,
+    tables: Arc<WorldLookupTables>,
+    control: &RunControl,
+) -> Result<ElasticityTable, SensitivityError> {
+    let invalid = |msg: String| SensitivityError::InvalidSpec(msg);
+    if !(spec.relative_step > 0.0 && spec.relative_step < 1.0) {
+        return Err(invalid(format!(
+            "relative_step must be in (0, 1), got {}",
+            spec.relative_step
+        )));
+    }
+    let parameters: Vec<String> = if spec.parameters.is_empty() {
+        ScenarioParams::TUNABLE
+            .iter()
+            .filter(|f| !base.schedules.contains_key(**f))
+            .map(|f| f.to_string())
+            .collect()
+    } else {
+        for field in &spec.parameters {
+            if !ScenarioParams::TUNABLE.contains(&field.as_str()) {
+                return Err(invalid(format!("'{}' is not a tunable parameter", field)));
+            }
+            if base.schedules.contains_key(field) {
+                return Err(invalid(format!("'{}' follows a schedule", field)));
+            }
+        }
+        spec.parameters.clone()
+    };
+    let known = series_paths();
+    let variables: Vec<String> = if spec.variables.is_empty() {
+        known
+    } else {
+        if let Some(v) = spec.variables.iter().find(|v| !known.contains(v)) {
+            return Err(invalid(format!("unknown variable '{}'", v)));
+        }
+        spec.variables.clone()
+    };
+
+    let descriptors = parameter_descriptors();
+    let base_values: Vec<f64> =
+        parameters.iter().map(|f| base.value(f).expect("parameters are checked against TUNABLE")).collect();
+    let deltas: Vec<f64> = parameters
+        .iter()
+        .zip(&base_values)
+        .map(|(field, &p)| {
+            let scale = if p != 0.0 {
+                p.abs()
+            } else {
+                descriptors.iter().find(|d| &d.field == field).map_or(1.0, |d| d.max - d.min)
+            };
+            spec.relative_step * scale
+        })
+        .collect();
+    let mut points = vec![base.clone()];
+    for ((field, p), delta) in parameters.iter().zip(&base_values).zip(&deltas) {
+        for sign in [1.0, -1.0] {
+            let mut params = base.clone();
+            params.set_value(field, p + sign * delta);
+            points.push(params);
+        }
+    }
+
+    let mut outputs = run_parallel(&points, default_threads(), |params| {
+        solve_one_with(initial, params, Arc::clone(&tables), control)
+    });
+    if let Some(i) = outputs.iter().position(|o| matches!(o, Err(SolverError::Cancelled { .. }))) {
+        return Err(outputs.swap_remove(i).unwrap_err().into());
+    }
+    let base_output = outputs.remove(0)?;
+    let series = |output: &SimulationOutput| -> Vec<Vec<f64>> {
+        variables.iter().map(|v| output.extract_series(v)).collect()
+    };
+    let y0 = series(&base_output);
+    let perturbed: Vec<Option<Vec<Vec<f64>>>> =
+        outputs.iter().map(|o| o.as_ref().ok().map(series)).collect();
+
+    let n = base_output.timeline.len();
+    let shape = vec![vec![vec![f64::NAN; parameters.len()]; variables.len()]; n];
+    let (mut elasticities, mut derivatives) = (shape.clone(), shape);
+    for (j, (p, delta)) in base_values.iter().zip(&deltas).enumerate() {
+        let (Some(up), Some(down)) = (&perturbed[2 * j], &perturbed[2 * j + 1]) else {
+            continue;
+        };
+        for (t, (e_year, d_year)) in elasticities.iter_mut().zip(&mut derivatives).enumerate() {
+            for v in 0..variables.len() {
+                let (Some(a), Some(b)) = (up[v].get(t), down[v].get(t)) else {
+                    continue;
+                };
+                let slope = (a - b) / (2.0 * delta);
+                let y = y0[v][t];
+                d_year[v][j] = slope;
+                e_year[v][j] = if y != 0.0 { slope * p / y } else { f64::NAN };
+            }
+        }
+    }
+
+    Ok(ElasticityTable {
+        timeline: base_output.timeline,
+        variables,
+        parameters,
+        elasticities,
+        derivatives,
+        runs: points.len(),
+        failed: outputs.iter().filter(|o| o.is_err()).count(),
+    })
+}
+
+/// Maps unit-cube points to runs and metric values.
+struct Model<'a> {
+    initial: &'a WorldState,
+    base: &'a ScenarioParams,
+    fields: Vec<String>,
+    ranges: Vec<(f64, f64)>,
+    metrics: &'a [Metric],
+    tables: Arc<WorldLookupTables>,
+}
+
+impl<'a> Model<'a> {
+    fn new(
+        initial: &'a WorldState,
+        base: &'a ScenarioParams,
+        parameters: &[String],
+        metrics: &'a [Metric],
+        tables: Arc<WorldLookupTables>,
+    ) -> Result<Self, SensitivityError> {
+        let descriptors = parameter_descriptors();
+        let chosen: Vec<&ParameterDescriptor> = if parameters.is_empty() {
+            descriptors.iter().collect()
+        } else {
+            parameters
+                .iter()
+                .map(|field| {
+                    descriptors.iter().find(|d| &d.field == field).ok_or_else(|| {
+                        SensitivityError::InvalidSpec(format!("no descriptor for '{}'", field))
+                    })
+                })
+                .collect::<Result<_, _>>()?
+        };
+        if metrics.is_empty() {
+            return Err(SensitivityError::InvalidSpec("no metrics".into()));
+        }
+        Ok(Self {
+            initial,
+            base,
+            fields: chosen.iter().map(|d| d.field.clone()).collect(),
+            ranges: chosen.iter().map(|d| (d.min, d.max)).collect(),
+            metrics,
+            tables,
+        })
+    }
+
+    fn k(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Metric values per point (NaN for failed runs) and the failure count.
+    fn run(&self, points: &[Vec<f64>]) -> (Vec<Vec<f64>>, usize) {
+        let results = run_parallel(points, default_threads(), |x| {
+            let mut params = self.base.clone();
+            for ((field, (min, max)), u) in self.fields.iter().zip(&self.ranges).zip(x) {
+                params.set_value(field, min + u * (max - min));
+            }
+            solve_one(self.initial, &params, Arc::clone(&self.tables))
+                .ok()
+                .map(|output| self.metrics.iter().map(|m| m.evaluate(&output)).collect())
+        });
+        let failed = results.iter().filter(|r| r.is_none()).count();
+        let values = results
+            .into_iter()
+            .map(|r| r.unwrap_or_else(|| vec![f64::NAN; self.metrics.len()]))
+            .collect();
+        (values, failed)
+    }
+}
+
+/// One elementary effect: the parameter moved between `points[from]` and
+/// `points[from + 1]`, by `delta` in unit coordinates.
+struct Step {
+    parameter: usize,
+    from: usize,
+    delta: f64,
+}
+
+/// Morris trajectories on a `levels`-point grid: each starts at a random
+/// grid point and moves every parameter once, in random order, by
+/// `levels / (2 (levels − 1))`.
+fn morris_design(
+    k: usize,
+    trajectories: usize,
+    levels: usize,
+    rng: &mut Rng,
+) -> (Vec<Vec<f64>>, Vec<Step>) {
+    let step = levels as f64 / (2.0 * (levels - 1) as f64);
+    let grid = |i: usize| i as f64 / (levels - 1) as f64;
+    let mut points = Vec::with_capacity(trajectories * (k + 1));
+    let mut steps = Vec::with_capacity(trajectories * k);
+    for _ in 0..trajectories {
+        let mut x: Vec<f64> = (0..k).map(|_| grid(pick(rng, levels))).collect();
+        let mut order: Vec<usize> = (0..k).collect();
+        for i in (1..k).rev() {
+            order.swap(i, pick(rng, i + 1));
+        }
+        points.push(x.clone());
+        for &i in &order {
+            let delta = if x[i] + step <= 1.0 + 1e-12 { step } else { -step };
+            x[i] += delta;
+            steps.push(Step {
+                parameter: i,
+                from: points.len() - 1,
+                delta,
+            });
+            points.push(x.clone());
+        }
+    }
+    (points, steps)
+}
+
+fn pick(rng: &mut Rng, n: usize) -> usize {
+    ((rng.next_f64() * n as f64) as usize).min(n - 1)
+}
+
+/// `(parameter, metric, (mu, mu_star, sigma))` for every pair.
+fn morris_indices(
+    values: &[Vec<f64>],
+    steps: &[Step],
+    k: usize,
+    metrics: usize,
+) -> Vec<(usize, usize, (f64, f64, f64))> {
+    let stats = |i: usize, m: usize| {
+        let effects: Vec<f64> = steps
+            .iter()
+            .filter(|s| s.parameter == i)
+            .map(|s| (values[s.from + 1][m] - values[s.from][m]) / s.delta)
+            .filter(|e| e.is_finite())
+            .collect();
+        let n = effects.len() as f64;
+        let mu = effects.iter().sum::<f64>() / n;
+        let mu_star = effects.iter().map(|e| e.abs()).sum::<f64>() / n;
+        let sigma = if effects.len() > 1 {
+            (effects.iter().map(|e| (e - mu).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+        (mu, mu_star, sigma)
+    };
+    (0..k)
+        .flat_map(|i| (0..metrics).map(move |m| (i, m)))
+        .map(|(i, m)| (i, m, stats(i, m)))
+        .collect()
+}
+
+/// Saltelli design: `n` rows each of A, B, then A with column `i` from B
+/// for every `i` — `n (k + 2)` points, in blocks.
+fn saltelli_design(k: usize, n: usize, rng: &mut Rng) -> Vec<Vec<f64>> {
+    let a: Vec<Vec<f64>> = (0..n).map(|_| (0..k).map(|_| rng.next_f64()).collect()).collect();
+    let b: Vec<Vec<f64>> = (0..n).map(|_| (0..k).map(|_| rng.next_f64()).collect()).collect();
+    let mut points = Vec::with_capacity(n * (k + 2));
+    points.extend(a.iter().cloned());
+    points.extend(b.iter().cloned());
+    for i in 0..k {
+        for (row_a, row_b) in a.iter().zip(&b) {
+            let mut x = row_a.clone();
+            x[i] = row_b[i];
+            points.push(x);
+        }
+    }
+    points
+}
+
+/// `(parameter, metric, (first_order, total_order))` from values on a
+/// [`saltelli_design`]. Rows with any failed run are dropped.
+fn sobol_indices(
+    values: &[Vec<f64>],
+    k: usize,
+    n: usize,
+    metrics: usize,
+) -> Vec<(usize, usize, (f64, f64))> {
+    let indices = |m: usize| {
+        let f = |block: usize, row: usize| values[block * n + row][m];
+        let rows: Vec<usize> = (0..n)
+            .filter(|&j| (0..k + 2).all(|block| f(block, j).is_finite()))
+            .collect();
+        let count = rows.len() as f64;
+        let all: Vec<f64> = rows.iter().flat_map(|&j| [f(0, j), f(1, j)]).collect();
+        let mean = all.iter().sum::<f64>() / all.len() as f64;
+        let variance = all.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / all.len() as f64;
+        (0..k)
+            .map(|i| {
+                let (mut first, mut total) = (0.0, 0.0);
+                for &j in &rows {
+                    let (fa, fb, fab) = (f(0, j), f(1, j), f(2 + i, j));
+                    first += fb * (fab - fa);
+                    total += (fa - fab).powi(2);
+                }
+                (i, m, (first / count / variance, total / (2.0 * count) / variance))
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut out: Vec<_> = (0..metrics).flat_map(indices).collect();
+    out.sort_by_key(|&(i, m, _)| (i, m));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::CancelToken;
+    use crate::model::initial::initial_conditions_1900;
+
+    /// f(x) = x0 + 2 x1 on the unit square: Var = 5/12, S0 = 0.2, S1 = 0.8,
+    /// no interactions, elementary effects exactly 1 and 2.
+    fn linear(points: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        points.iter().map(|x| vec![x[0] + 2.0 * x[1]]).collect()
+    }
+
+    #[test]
+    fn test_estimators_on_linear_function() {
+        let mut rng = Rng::new(1);
+        let (points, steps) = morris_design(2, 10, 4, &mut rng);
+        let morris = morris_indices(&linear(&points), &steps, 2, 1);
+        assert!((morris[0].2 .1 - 1.0).abs() < 1e-9);
+        assert!((morris[1].2 .1 - 2.0).abs() < 1e-9);
+        assert!(morris[1].2 .2 < 1e-9);
+
+        let n = 20_000;
+        let points = saltelli_design(2, n, &mut rng);
+        let sobol = sobol_indices(&linear(&points), 2, n, 1);
+        for (i, expected) in [(0, 0.2), (1, 0.8)] {
+            let (first, total) = sobol[i].2;
+            assert!((first - expected).abs() < 0.03, "S{} = {}", i, first);
+            assert!((total - expected).abs() < 0.03, "ST{} = {}", i, total);
+        }
+    }
+
+    #[test]
+    fn test_morris_ranks_inactive_slider_last() {
+        // BAU has no family planning, so its year cannot matter
+        let spec = MorrisSpec {
+            trajectories: 3,
+            levels: 4,
+            seed: 3,
+            parameters: vec!["family_planning_year".into(), "pollution_control".into()],
+            metrics: vec![Metric::max_pollution_index()],
+        };
+        let tables = Arc::new(WorldLookupTables::load());
+        let result =
+            morris(&initial_conditions_1900(), &ScenarioParams::bau(), &spec, tables).unwrap();
+        assert_eq!(result.runs, 9);
+        assert_eq!(result.indices[0].mu_star, 0.0);
+        assert!(result.indices[1].mu_star > 1.0);
+        assert!(result.indices[1].mu < 0.0);
+    }
+
+    #[test]
+    fn test_elasticities_of_capital_to_investment() {
+        let spec = ElasticitySpec {
+            parameters: vec!["investment_rate".into(), "family_planning_year".into()],
+            variables: vec!["capital.industrial_capital".into(), "flows.births".into()],
+            ..ElasticitySpec::default()
+        };
+        let tables = Arc::new(WorldLookupTables::load());
+        let table =
+            elasticities(&initial_conditions_1900(), &ScenarioParams::bau(), &spec, tables)
+                .unwrap();
+        assert_eq!(table.runs, 5);
+        assert_eq!(table.failed, 0);
+        assert_eq!(table.elasticities.len(), table.timeline.len());
+        assert_eq!(table.elasticities[0].len(), 2);
+        assert_eq!(table.elasticities[0][0].len(), 2);
+
+        let capital =
+            |year| table.elasticity(year, "capital.industrial_capital", "investment_rate");
+        assert_eq!(capital(1900.0), Some(0.0));
+        assert!(capital(1950.0).unwrap() > 0.1);
+        // BAU has no family planning, so its year cannot matter
+        assert_eq!(table.elasticity(2100.0, "flows.births", "family_planning_year"), Some(0.0));
+    }
+
+    #[test]
+    fn test_cancelled_elasticities_fail_instead_of_counting_failures() {
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let control = RunControl::new().with_cancel(cancel);
+        let result = elasticities_with(
+            &initial_conditions_1900(),
+            &ScenarioParams::bau(),
+            &ElasticitySpec::default(),
+            Arc::new(WorldLookupTables::load()),
+            &control,
+        );
+        assert!(matches!(
+            result,
+            Err(SensitivityError::BaseRun(SolverError::Cancelled { year })) if year == 1900.0
+        ));
+    }
+}
@@ -0,0 +1,875 @@
+//! Incremental simulation stepper.
+//!
+//! `Simulation` owns the current `WorldState`, the scenario parameters and
+//! the lookup tables, and advances the model one integration step at a time.
+//! It is the engine behind every `OdeSolver`: `solve()` simply drains the
+//! iterator. Callers that need states as they are computed (e.g. the
+//! WebSocket stream) can drive it directly:
+//!
+//! - [`Simulation::step`] advances one integration step
+//! - [`Simulation::run_until`] advances until a given year
+//! - the `Iterator` impl yields states on the output grid
+//!   (`start_year, start_year + output_interval, …, end_year`, where
+//!   `output_interval` defaults to `time_step`)
+//! - [`Simulation::checkpoint`] / [`Simulation::from_checkpoint`] save and
+//!   resume a run, optionally with new parameters
+//! - [`Simulation::with_control`] makes the run cancellable and reports
+//!   progress (see [`crate::control`])
+//! - [`Simulation::with_events`] registers conditions located during
+//!   integration (see [`crate::events`]); a terminal event ends the run
+//!
+//! Every yielded state carries its [`Flows`](crate::model::state::Flows):
+//! the in- and outflows of each stock at that point.
+//!
+//! If `params.start_year` is later than the initial state's year, the model
+//! is spun up to `start_year` before the first output.
+//!
+//! The stepper itself only sees stock vectors: a [`System`] turns them into
+//! states and evaluates the right-hand side. [`World`], the World 3 model,
+//! is the default; [`crate::region`] runs several coupled regions as one
+//! system through the same stepper.
+//!
+//! The integration step depends on the method: `time_step` for RK4, DYNAMO's
+//! DT for Euler, and an error-controlled step for RK45. It is independent of
+//! the output grid: output grid points that fall inside a step are filled by
+//! cubic Hermite interpolation, and steps between grid points are not
+//! reported.
+
+use std::sync::Arc;
+
+use crate::checkpoint::Checkpoint;
+use crate::control::{Progress, RunControl};
+use crate::events::{Event, EventDetector, EventSpec};
+use crate::lookup::tables::WorldLookupTables;
+use crate::model::{
+    derivatives::{compute_auxiliaries, derivatives, flows},
+    params::{ScenarioParams, SolverKind},
+    state::WorldState,
+};
+use crate::output::extract_field;
+use crate::solver::{
+    check_stocks,
+    euler::{euler_step, DYNAMO_DT},
+    hermite, rhs,
+    rk4::rk4_step,
+    rk45::{
+        dopri_attempt, step_factor, DEFAULT_ATOL, DEFAULT_MAX_STEP, DEFAULT_MIN_STEP,
+        DEFAULT_RTOL,
+    },
+    PartialTrajectory, SolverError, StepStats,
+};
+
+/// Tolerance when comparing simulation times [years]
+const TIME_EPS: f64 = 1e-9;
+
+/// Integration scheme and its settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepMethod {
+    /// Fixed-step RK4 at `params.time_step`
+    Rk4,
+    /// Adaptive Dormand–Prince 5(4)
+    Rk45 {
+        rtol: f64,
+        atol: f64,
+        max_step: f64,
+        min_step: f64,
+    },
+    /// Explicit Euler at `dt`, shrunk so it divides `params.time_step`
+    Euler { dt: f64 },
+}
+
+impl StepMethod {
+    /// Default settings for a `ScenarioParams::solver` choice.
+    pub fn from_kind(kind: SolverKind) -> Self {
+        match kind {
+            SolverKind::Rk4 => StepMethod::Rk4,
+            SolverKind::Rk45 => StepMethod::Rk45 {
+                rtol: DEFAULT_RTOL,
+                atol: DEFAULT_ATOL,
+                max_step: DEFAULT_MAX_STEP,
+                min_step: DEFAULT_MIN_STEP,
+            },
+            SolverKind::Euler => StepMethod::Euler { dt: DYNAMO_DT },
+        }
+    }
+}
+
+/// An ODE system [`Simulation`] can integrate.
+pub trait System {
+    /// Stocks and auxiliaries at one time
+    type State: Clone;
+
+    /// Run settings: years, time step, output grid and solver. Event
+    /// thresholds read parameters from here.
+    fn params(&self) -> &ScenarioParams;
+
+    fn time(&self, state: &Self::State) -> f64;
+
+    /// Integrated stocks of `state`
+    fn stocks(&self, state: &Self::State) -> Vec<f64>;
+
+    /// State at `(t, y)`, auxiliaries included
+    fn state(&self, t: f64, y: &[f64]) -> Self::State;
+
+    /// `dy/dt` at `state`, whose auxiliaries are up to date
+    fn slope(&self, state: &Self::State) -> Vec<f64>;
+
+    /// Right-hand side as a pure function of `(t, y)`
+    fn rhs(&self, t: f64, y: &[f64]) -> Vec<f64> {
+        self.slope(&self.state(t, y))
+    }
+
+    /// Check freshly integrated stocks `y` before they are clamped into a
+    /// state; `last_good` is the state the step started from.
+    fn check(&self, t: f64, y: &[f64], last_good: &Self::State) -> Result<(), SolverError>;
+
+    /// Value of a variable path at `state`, for events
+    fn observe(&self, state: &Self::State, path: &str) -> Option<f64>;
+
+    /// Fill in the flows of a state about to be output.
+    fn record(&self, state: &mut Self::State);
+}
+
+/// The World 3 model under one set of parameters.
+pub struct World {
+    params: ScenarioParams,
+    tables: Arc<WorldLookupTables>,
+}
+
+impl System for World {
+    type State = WorldState;
+
+    fn params(&self) -> &ScenarioParams {
+        &self.params
+    }
+
+    fn time(&self, state: &WorldState) -> f64 {
+        state.time
+    }
+
+    fn stocks(&self, state: &WorldState) -> Vec<f64> {
+        state.to_vec()
+    }
+
+    fn state(&self, t: f64, y: &[f64]) -> WorldState {
+        let mut state = WorldState::from_vec(t, y);
+        compute_auxiliaries(&mut state, &self.params, &self.tables);
+        state
+    }
+
+    fn slope(&self, state: &WorldState) -> Vec<f64> {
+        derivatives(state, &self.params, &self.tables).to_vec()
+    }
+
+    fn rhs(&self, t: f64, y: &[f64]) -> Vec<f64> {
+        rhs(t, y, &self.params, &self.tables)
+    }
+
+    fn check(&self, t: f64, y: &[f64], last_good: &WorldState) -> Result<(), SolverError> {
+        check_stocks(t, y, last_good)
+    }
+
+    fn observe(&self, state: &WorldState, path: &str) -> Option<f64> {
+        extract_field(state, path)
+    }
+
+    fn record(&self, state: &mut WorldState) {
+        state.flows = flows(state, &self.params, &self.tables);
+    }
+}
+
+pub struct Simulation<S: System = World> {
+    system: S,
+    state: S::State,
+    method: StepMethod,
+    /// Time of the first output point (`params.start_year`)
+    start: f64,
+    /// Time of the last output point: `params.end_year`, or the year of a
+    /// terminal event
+    end: f64,
+    /// RK45: next trial step size
+    step_size: Option<f64>,
+    /// Slope at `state`, when the method already has it (RK45 FSAL)
+    slope: Option<Vec<f64>>,
+    /// Previous accepted point `(t, y, slope)`, kept for dense output
+    previous: Option<(f64, Vec<f64>, Option<Vec<f64>>)>,
+    /// Index of the next output grid point to yield
+    next_output: usize,
+    /// Whether the initial state has been checked (on the first step or output)
+    started: bool,
+    finished: bool,
+    stats: StepStats,
+    error: Option<SolverError>,
+    detectors: Vec<EventDetector>,
+    events: Vec<Event>,
+    /// Year of the initial state, for progress fractions
+    origin: f64,
+    control: RunControl,
+}
+
+impl Simulation {
+    /// Start a simulation from `initial` using the method selected by
+    /// `params.solver`. Output begins at `params.start_year`.
+    pub fn new(
+        initial: WorldState,
+        params: ScenarioParams,
+        tables: Arc<WorldLookupTables>,
+    ) -> Result<Self, SolverError> {
+        let method = StepMethod::from_kind(params.solver);
+        Self::with_method(initial, params, tables, method)
+    }
+
+    /// Start a simulation with an explicit integration method.
+    ///
+    /// Fails with [`SolverError::InvalidInitial`] if a schedule cannot be
+    /// evaluated; schedules are checked before anything evaluates them.
+    pub fn with_method(
+        initial: WorldState,
+        params: ScenarioParams,
+        tables: Arc<WorldLookupTables>,
+        method: StepMethod,
+    ) -> Result<Self, SolverError> {
+        params.validate_schedules().map_err(SolverError::InvalidInitial)?;
+        let mut state = initial;
+        compute_auxiliaries(&mut state, &params, &tables);
+        Ok(Self::with_system(World { params, tables }, state, method))
+    }
+
+    /// Capture the current state and solver state for a later resume.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            state: self.state.clone(),
+            step_size: self.step_size,
+        }
+    }
+
+    /// Resume from a checkpoint with (possibly different) parameters.
+    ///
+    /// Auxiliaries are recomputed from the checkpoint's stocks with the new
+    /// parameters, so resuming with the original parameters reproduces the
+    /// original trajectory. Output starts at the checkpoint year, or at
+    /// `params.start_year` if that is later.
+    pub fn from_checkpoint(
+        checkpoint: Checkpoint,
+        mut params: ScenarioParams,
+        tables: Arc<WorldLookupTables>,
+    ) -> Result<Self, SolverError> {
+        let state = WorldState::from_vec(checkpoint.state.time, &checkpoint.state.to_vec());
+        params.start_year = params.start_year.max(state.time);
+        let mut sim = Self::new(state, params, tables)?;
+        if matches!(sim.method, StepMethod::Rk45 { .. }) {
+            sim.step_size = checkpoint.step_size;
+        }
+        Ok(sim)
+    }
+}
+
+impl<S: System> Simulation<S> {
+    /// Start integrating `system` from `state`, whose auxiliaries are
+    /// already computed.
+    pub(crate) fn with_system(system: S, state: S::State, method: StepMethod) -> Self {
+        let params = system.params();
+        Self {
+            start: params.start_year,
+            end: params.end_year,
+            origin: system.time(&state),
+            system,
+            state,
+            method,
+            step_size: None,
+            slope: None,
+            previous: None,
+            next_output: 0,
+            started: false,
+            finished: false,
+            stats: StepStats::default(),
+            error: None,
+            detectors: Vec::new(),
+            events: Vec::new(),
+            control: RunControl::default(),
+        }
+    }
+
+    /// Watch for `specs` during the run. Fails on unknown variables or
+    /// parameters.
+    pub fn with_events(mut self, specs: Vec<EventSpec>) -> Result<Self, SolverError> {
+        let known = |path: &str| self.system.observe(&self.state, path).is_some();
+        let detectors = specs
+            .into_iter()
+            .map(|spec| EventDetector::new(spec, self.system.params(), &known))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(SolverError::InvalidEvent)?;
+        self.detectors.extend(detectors);
+        Ok(self)
+    }
+
+    /// Check `control.cancel` before every step and report progress after
+    /// each accepted one.
+    pub fn with_control(mut self, control: RunControl) -> Self {
+        self.control = control;
+        self
+    }
+
+    /// Events located so far, in order of occurrence.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Current state (stocks and auxiliaries).
+    pub fn state(&self) -> &S::State {
+        &self.state
+    }
+
+    /// The system being integrated.
+    pub fn system(&self) -> &S {
+        &self.system
+    }
+
+    pub fn params(&self) -> &ScenarioParams {
+        self.system.params()
+    }
+
+    pub fn time(&self) -> f64 {
+        self.system.time(&self.state)
+    }
+
+    /// Step counters accumulated so far.
+    pub fn stats(&self) -> StepStats {
+        self.stats
+    }
+
+    /// True once `end_year` (or a terminal event) has been reached or an
+    /// error occurred.
+    pub fn is_finished(&self) -> bool {
+        self.finished || self.time() >= self.end - TIME_EPS
+    }
+
+    /// Error that stopped the iterator, if any.
+    pub fn error(&self) -> Option<&SolverError> {
+        self.error.as_ref()
+    }
+
+    pub fn take_error(&mut self) -> Option<SolverError> {
+        self.error.take()
+    }
+
+    /// Advance one integration step (never past `end_year`).
+    ///
+    /// Returns the new state; at `end_year` this is a no-op.
+    pub fn step(&mut self) -> Result<&S::State, SolverError> {
+        self.advance(self.end)?;
+        Ok(&self.state)
+    }
+
+    /// Advance until `year` (clamped to `end_year`), shortening the last
+    /// step so the simulation lands exactly on it.
+    pub fn run_until(&mut self, year: f64) -> Result<&S::State, SolverError> {
+        let limit = year.min(self.end);
+        while self.time() < limit - TIME_EPS {
+            self.advance(limit)?;
+        }
+        Ok(&self.state)
+    }
+
+    /// Drain the output grid into a vector, as `OdeSolver::solve` returns it.
+    pub fn collect_states(self) -> Result<Vec<S::State>, SolverError> {
+        self.collect_partial().into_result()
+    }
+
+    /// Drain the output grid, keeping the states computed before any error.
+    pub fn collect_partial(mut self) -> PartialTrajectory<S::State> {
+        let states: Vec<S::State> = self.by_ref().collect();
+        PartialTrajectory {
+            states,
+            events: self.events,
+            error: self.error,
+        }
+    }
+
+    /// Integration step (`time_step`): the RK4 step, the interval Euler
+    /// steps divide, and the first RK45 trial step.
+    fn step_interval(&self) -> Result<f64, SolverError> {
+        let dt = self.params().time_step;
+        if dt.is_nan() || dt <= 0.0 {
+            return Err(SolverError::InvalidInitial(format!(
+                "time_step must be positive, got {dt}"
+            )));
+        }
+        Ok(dt)
+    }
+
+    /// Spacing of the output grid: `output_interval`, or `time_step` if unset.
+    fn output_interval(&self) -> Result<f64, SolverError> {
+        match self.params().output_interval {
+            None => self.step_interval(),
+            Some(dt) if dt.is_nan() || dt <= 0.0 => Err(SolverError::InvalidInitial(format!(
+                "output_interval must be positive, got {dt}"
+            ))),
+            Some(dt) => Ok(dt),
+        }
+    }
+
+    /// Take one integration step, ending no later than `limit`.
+    fn advance(&mut self, limit: f64) -> Result<(), SolverError> {
+        let t = self.time();
+        let remaining = limit - t;
+        if remaining <= TIME_EPS {
+            return Ok(());
+        }
+        self.begin()?;
+        if self.control.is_cancelled() {
+            return Err(SolverError::Cancelled { year: t });
+        }
+        let interval = self.step_interval()?;
+        let system = &self.system;
+        let f = |t: f64, y: &[f64]| system.rhs(t, y);
+        let y0 = system.stocks(&self.state);
+        let slope0 = self.slope.take();
+
+        let (t_next, y_next) = match self.method {
+            StepMethod::Rk4 => {
+                self.stats.accepted += 1;
+                self.stats.evaluations += 4;
+                let h = interval.min(remaining);
+                (t + h, rk4_step(t, &y0, h, f))
+            }
+            StepMethod::Euler { dt } => {
+                if dt.is_nan() || dt <= 0.0 {
+                    return Err(SolverError::InvalidInitial(format!(
+                        "Euler dt must be positive, got {dt}"
+                    )));
+                }
+                let substeps = (interval / dt - TIME_EPS).ceil().max(1.0);
+                self.stats.accepted += 1;
+                self.stats.evaluations += 1;
+                let h = (interval / substeps).min(remaining);
+                (t + h, euler_step(&y0, &system.slope(&self.state), h))
+            }
+            StepMethod::Rk45 { rtol, atol, max_step, min_step } => {
+                let k1 = match &slope0 {
+                    Some(k) => k.clone(),
+                    None => {
+                        self.stats.evaluations += 1;
+                        f(t, &y0)
+                    }
+                };
+                let mut h = self.step_size.unwrap_or(interval).min(max_step);
+                loop {
+                    let h_try = h.min(remaining);
+                    let attempt = dopri_attempt(t, &y0, &k1, h_try, rtol, atol, f);
+                    self.stats.evaluations += 6;
+                    let factor = step_factor(attempt.err);
+                    if attempt.err.is_finite() && attempt.err <= 1.0 {
+                        self.stats.accepted += 1;
+                        // Don't let a step truncated at `limit` shrink the next trial step
+                        let proposed = h_try * factor;
+                        self.step_size = Some(if h_try < h { proposed.max(h) } else { proposed });
+                        self.slope = Some(attempt.k7);
+                        self.previous = Some((t, y0.clone(), Some(k1)));
+                        break (t + h_try, attempt.y_new);
+                    }
+                    self.stats.rejected += 1;
+                    h = h_try * factor;
+                    if h < min_step {
+                        return Err(SolverError::StepSizeTooSmall { year: t, step: h });
+                    }
+                }
+            }
+        };
+
+        if !matches!(self.method, StepMethod::Rk45 { .. }) {
+            self.previous = Some((t, y0, slope0));
+        }
+        system.check(t_next, &y_next, &self.state)?;
+        self.state = system.state(t_next, &y_next);
+        self.detect_events();
+        let year = self.time();
+        let span = self.end - self.origin;
+        let fraction = if span > 0.0 { (year - self.origin) / span } else { 1.0 };
+        self.control.report(Progress {
+            year,
+            fraction: fraction.clamp(0.0, 1.0),
+        });
+        Ok(())
+    }
+
+    /// State at `t_out` inside the last accepted step.
+    fn interpolate(&mut self, t_out: f64) -> Result<S::State, SolverError> {
+        Ok(self.interpolant().at(t_out))
+    }
+
+    /// Dense output over the last accepted step.
+    fn interpolant(&self) -> Interpolant<'_, S> {
+        let system = &self.system;
+        let (t0, y0, f0) = self
+            .previous
+            .clone()
+            .expect("interpolation requires an accepted step");
+        let t1 = self.time();
+        let y1 = system.stocks(&self.state);
+        let f0 = f0.unwrap_or_else(|| system.rhs(t0, &y0));
+        let f1 = match &self.slope {
+            Some(k) => k.clone(),
+            None => system.rhs(t1, &y1),
+        };
+        Interpolant { t0, y0, f0, t1, y1, f1, system }
+    }
+
+    /// Run the event detectors over the last accepted step. A terminal event
+    /// truncates the step at the event and ends the run there.
+    fn detect_events(&mut self) {
+        if self.detectors.is_empty() {
+            return;
+        }
+        let mut detectors = std::mem::take(&mut self.detectors);
+        let interpolant = self.interpolant();
+        let (t0, t1) = (interpolant.t0, interpolant.t1);
+        let at = |t: f64| interpolant.at(t);
+        let observe = |state: &S::State, path: &str| self.system.observe(state, path);
+        let mut found: Vec<Event> = detectors
+            .iter_mut()
+            .filter_map(|d| d.check(t0, t1, &at, &observe))
+            .collect();
+        found.sort_by(|a, b| a.year.total_cmp(&b.year));
+        let stop = found.iter().find(|e| e.terminal).map(|e| e.year);
+        let stop_state = stop.map(|year| interpolant.at(year));
+        self.detectors = detectors;
+
+        if let (Some(year), Some(state)) = (stop, stop_state) {
+            found.retain(|e| e.year <= year);
+            self.state = state;
+            self.slope = None;
+            self.end = year;
+        }
+        self.events.extend(found);
+    }
+
+    /// Check the initial state, once, before the first step.
+    fn begin(&mut self) -> Result<(), SolverError> {
+        if self.started {
+            return Ok(());
+        }
+        if self.origin > self.start + TIME_EPS {
+            return Err(SolverError::InvalidInitial(format!(
+                "initial state is at {:.2}, after start_year {:.2}",
+                self.origin, self.start
+            )));
+        }
+        self.system
+            .check(self.time(), &self.system.stocks(&self.state), &self.state)
+            .map_err(|e| SolverError::InvalidInitial(e.to_string()))?;
+        self.started = true;
+        Ok(())
+    }
+
+    fn next_output_state(&mut self) -> Result<Option<S::State>, SolverError> {
+        if self.next_output == 0 {
+            self.begin()?;
+            if self.time() <= self.start + TIME_EPS {
+                // Spin up to start_year without recording output
+                self.run_until(self.start)?;
+                self.next_output = 1;
+                return Ok(Some(self.state.clone()));
+            }
+        }
+        let interval = self.output_interval()?;
+        let end = self.end;
+        if self.next_output > 0 {
+            let prev_out = self.start + (self.next_output - 1) as f64 * interval;
+            if prev_out >= end - TIME_EPS {
+                return Ok(None);
+            }
+        }
+        // Grid points already stepped past (via `step`/`run_until`) can only be
+        // recovered inside the last accepted step; earlier ones are skipped.
+        let earliest = match &self.previous {
+            Some((t0, _, _)) => *t0,
+            None => self.time(),
+        };
+        while self.start + self.next_output as f64 * interval < earliest - TIME_EPS {
+            self.next_output += 1;
+        }
+        let mut t_out = (self.start + self.next_output as f64 * interval).min(end);
+
+        while self.time() < t_out - TIME_EPS {
+            self.advance(end)?;
+            // A terminal event may have moved the end before `t_out`
+            t_out = t_out.min(self.end);
+        }
+        self.next_output += 1;
+
+        if (self.time() - t_out).abs() <= TIME_EPS {
+            Ok(Some(self.state.clone()))
+        } else {
+            self.interpolate(t_out).map(Some)
+        }
+    }
+}
+
+/// Cubic Hermite interpolant of one accepted step, with auxiliaries.
+struct Interpolant<'a, S: System> {
+    t0: f64,
+    y0: Vec<f64>,
+    f0: Vec<f64>,
+    t1: f64,
+    y1: Vec<f64>,
+    f1: Vec<f64>,
+    system: &'a S,
+}
+
+impl<S: System> Interpolant<'_, S> {
+    fn at(&self, t: f64) -> S::State {
+        let h = self.t1 - self.t0;
+        let y = hermite(&self.y0, &self.f0, &self.y1, &self.f1, h, (t - self.t0) / h);
+        self.system.state(t, &y)
+    }
+}
+
+impl<S: System> Iterator for Simulation<S> {
+    type Item = S::State;
+
+    /// Yield the next state on the output grid. Iteration stops at
+    /// `end_year` or on the first error, which is then available from
+    /// [`Simulation::error`].
+    fn next(&mut self) -> Option<S::State> {
+        if self.finished {
+            return None;
+        }
+        match self.next_output_state() {
+            Ok(Some(mut state)) => {
+                self.system.record(&mut state);
+                Some(state)
+            }
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.error = Some(e);
+                self.finished = true;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::initial::initial_conditions_1900;
+
+    #[test]
+    fn test_start_year_spins_up_before_output() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let mut params = ScenarioParams::bau();
+        params.start_year = 1950.0;
+        let states = Simulation::new(initial_conditions_1900(), params, tables)
+            .unwrap()
+            .collect_states()
+            .unwrap();
+        assert_eq!(states.len(), 151);
+        assert!((states[0].time - 1950.0).abs() < 1e-9);
+        assert!(states[0].population.population > 1.6e9);
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint_reproduces_run() {
+        let tables = Arc::new(WorldLookupTables::load());
+        for solver in [SolverKind::Rk4, SolverKind::Rk45, SolverKind::Euler] {
+            let params = ScenarioParams { solver, ..ScenarioParams::bau() };
+            let full =
+                Simulation::new(initial_conditions_1900(), params.clone(), Arc::clone(&tables))
+                    .unwrap()
+                    .collect_states()
+                    .unwrap();
+
+            let mut sim =
+                Simulation::new(initial_conditions_1900(), params.clone(), Arc::clone(&tables))
+                    .unwrap();
+            sim.run_until(2000.0).unwrap();
+            let resumed =
+                Simulation::from_checkpoint(sim.checkpoint(), params, Arc::clone(&tables))
+                    .unwrap()
+                    .collect_states()
+                    .unwrap();
+
+            // The adaptive solver's step sequence changes when the run stops
+            // at the checkpoint, so it only matches to within its tolerance.
+            let tolerance = if solver == SolverKind::Rk45 { 1e-4 } else { 1e-9 };
+            assert_eq!(resumed.len(), 101);
+            for (a, b) in resumed.iter().zip(full.iter().skip(100)) {
+                approx::assert_relative_eq!(
+                    a.population.population,
+                    b.population.population,
+                    max_relative = tolerance
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_step_checks_initial_state() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let mut initial = initial_conditions_1900();
+        initial.population.cohort_0_14 = -1.0;
+        let mut sim = Simulation::new(initial, ScenarioParams::bau(), tables).unwrap();
+        assert!(matches!(sim.step(), Err(SolverError::InvalidInitial(_))));
+    }
+
+    #[test]
+    fn test_output_after_run_until_continues_grid() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let mut sim =
+            Simulation::new(initial_conditions_1900(), ScenarioParams::bau(), tables).unwrap();
+        sim.run_until(1950.0).unwrap();
+        // 1949 is still inside the last accepted step, so it is recovered
+        let states = sim.collect_states().unwrap();
+        assert_eq!(states.len(), 152);
+        assert!((states[0].time - 1949.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_blow_up_returns_partial_trajectory() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let params = ScenarioParams {
+            industrial_depreciation_rate: -1.0,
+            ..ScenarioParams::bau()
+        };
+        let partial =
+            Simulation::new(initial_conditions_1900(), params, tables).unwrap().collect_partial();
+        let error = partial.error.expect("negative depreciation must blow up");
+        assert!(matches!(
+            &error,
+            SolverError::Diverged { variable, .. } if variable == "industrial_capital"
+        ));
+        let last_good = error.last_good().expect("step errors carry the last good state");
+        let last_output = partial.states.last().unwrap();
+        assert!(partial.states.len() < 201);
+        assert!(last_good.time >= last_output.time);
+    }
+
+    #[test]
+    fn test_negative_stock_is_reported_not_clamped() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let params = ScenarioParams {
+            industrial_depreciation_rate: 5.0,
+            solver: SolverKind::Euler,
+            ..ScenarioParams::bau()
+        };
+        let error = Simulation::new(initial_conditions_1900(), params, tables)
+            .unwrap()
+            .collect_states()
+            .unwrap_err();
+        match error {
+            SolverError::NegativeStock { sector, variable, value, .. } => {
+                assert_eq!((sector.as_str(), variable.as_str()), ("capital", "industrial_capital"));
+                assert!(value < 0.0);
+            }
+            other => panic!("expected NegativeStock, got {other}"),
+        }
+    }
+
+    #[test]
+    fn test_peak_event_refines_grid_maximum() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let spec: EventSpec = "peak:population.population".parse().unwrap();
+        let partial = Simulation::new(initial_conditions_1900(), ScenarioParams::bau(), tables)
+            .unwrap()
+            .with_events(vec![spec])
+            .unwrap()
+            .collect_partial();
+        let grid_peak = partial
+            .states
+            .iter()
+            .max_by(|a, b| a.population.population.total_cmp(&b.population.population))
+            .unwrap();
+        assert_eq!(partial.events.len(), 1);
+        let peak = &partial.events[0];
+        assert!((peak.year - grid_peak.time).abs() <= 1.0);
+        assert!(peak.value >= grid_peak.population.population * (1.0 - 1e-9));
+    }
+
+    #[test]
+    fn test_terminal_event_ends_run_at_crossing() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let spec = EventSpec::new(
+            "pollution",
+            crate::events::EventCondition::Above {
+                variable: "pollution.pollution_index".into(),
+                threshold: crate::events::Operand::Value(5.0),
+            },
+        )
+        .terminal();
+        let params = ScenarioParams { solver: SolverKind::Rk45, ..ScenarioParams::bau() };
+        let partial = Simulation::new(initial_conditions_1900(), params, tables)
+            .unwrap()
+            .with_events(vec![spec])
+            .unwrap()
+            .collect_partial();
+        let event = &partial.events[0];
+        let last = partial.states.last().unwrap();
+        assert!(event.terminal);
+        assert!((last.time - event.year).abs() < 1e-9);
+        approx::assert_relative_eq!(last.pollution.pollution_index, 5.0, max_relative = 1e-4);
+        assert!(partial.states.iter().rev().skip(1).all(|s| s.time < event.year));
+    }
+
+    #[test]
+    fn test_output_interval_is_independent_of_time_step() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let run = |time_step: f64, output_interval: Option<f64>| {
+            let params = ScenarioParams {
+                time_step,
+                output_interval,
+                ..ScenarioParams::bau()
+            };
+            Simulation::new(initial_conditions_1900(), params, Arc::clone(&tables))
+                .unwrap()
+                .collect_states()
+                .unwrap()
+        };
+
+        // Sparse: integrate at 0.125, report every 5 years
+        let fine = run(0.125, None);
+        let sparse = run(0.125, Some(5.0));
+        assert_eq!(sparse.len(), 41);
+        for (i, s) in sparse.iter().enumerate() {
+            let f = &fine[i * 40];
+            assert!((s.time - f.time).abs() < 1e-9);
+            assert_eq!(s.population.population, f.population.population);
+        }
+
+        // Dense: integrate at 1, interpolate every quarter year
+        let coarse = run(1.0, None);
+        let dense = run(1.0, Some(0.25));
+        assert_eq!(dense.len(), 801);
+        for (i, c) in coarse.iter().enumerate() {
+            assert_eq!(dense[i * 4].population.population, c.population.population);
+        }
+    }
+
+    #[test]
+    fn test_cancel_from_progress_callback() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let cancel = crate::control::CancelToken::new();
+        let token = cancel.clone();
+        let last = Arc::new(std::sync::Mutex::new(0.0));
+        let seen = Arc::clone(&last);
+        let control = RunControl::new().with_cancel(cancel).with_progress(move |p| {
+            let mut last = seen.lock().unwrap();
+            assert!(p.fraction >= *last && p.fraction <= 1.0);
+            *last = p.fraction;
+            if p.year >= 1950.0 {
+                token.cancel();
+            }
+        });
+        let partial = Simulation::new(initial_conditions_1900(), ScenarioParams::bau(), tables)
+            .unwrap()
+            .with_control(control)
+            .collect_partial();
+
+        assert!(matches!(partial.error, Some(SolverError::Cancelled { year }) if year == 1950.0));
+        assert_eq!(partial.states.last().unwrap().time, 1950.0);
+        assert_eq!(*last.lock().unwrap(), 0.25);
+    }
+}
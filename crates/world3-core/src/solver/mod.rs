@@ -1,5 +1,117 @@
+pub mod euler;
 pub mod rk4;
+pub mod rk45;
 pub mod traits;
 
+pub use euler::EulerSolver;
 pub use rk4::Rk4Solver;
-pub use traits::{OdeSolver, SolverError};
+pub use rk45::{Rk45Solver, StepStats};
+pub use traits::{OdeSolver, PartialTrajectory, SolverError};
+
+use std::sync::Arc;
+
+use crate::lookup::tables::WorldLookupTables;
+use crate::model::{
+    derivatives::{compute_auxiliaries, derivatives},
+    params::{ScenarioParams, SolverKind},
+    sectors::climate::MIN_TEMPERATURE_ANOMALY,
+    state::WorldState,
+};
+
+/// Build the solver selected by `ScenarioParams::solver`.
+pub fn build_solver(kind: SolverKind, tables: Arc<WorldLookupTables>) -> Box<dyn OdeSolver> {
+    match kind {
+        SolverKind::Rk4 => Box::new(Rk4Solver::new(tables)),
+        SolverKind::Rk45 => Box::new(Rk45Solver::new(tables)),
+        SolverKind::Euler => Box::new(EulerSolver::new(tables)),
+    }
+}
+
+/// Upper physical bound of each stock, in [`WorldState::to_vec`] order.
+///
+/// These are sanity limits, not model constraints: a stock beyond them means
+/// the integration has blown up.
+const STOCK_UPPER_BOUNDS: [f64; WorldState::N] = [
+    1e13, 1e13, 1e13, 1e13, // population cohorts [persons]
+    1e16, 1e16, // industrial and service capital [1975 USD]
+    1.5e10, 1.5e10, // arable and potentially arable land [ha] (Earth's land area)
+    1e5, // land fertility [kg / ha / yr]
+    1.0 + 1e-9, // nonrenewable resources [fraction of 1900 stock]
+    1e6, // persistent pollution [pollution units]
+    1e6, // perceived labor utilization [jobs / worker]
+    1e5, 1e5, // atmospheric and sink carbon [GtC]
+    100.0, 100.0, // surface and deep ocean temperature anomaly [K]
+    1e16, 1e16, // fossil and renewable energy capital [1975 USD]
+    1.0 + 1e-9, // biodiversity intactness [fraction]
+];
+
+/// Lower physical bound of each stock, in [`WorldState::to_vec`] order:
+/// zero, except for the signed temperature anomalies.
+const STOCK_LOWER_BOUNDS: [f64; WorldState::N] = {
+    let mut bounds = [0.0; WorldState::N];
+    bounds[14] = MIN_TEMPERATURE_ANOMALY; // surface temperature anomaly [K]
+    bounds[15] = MIN_TEMPERATURE_ANOMALY; // deep ocean temperature anomaly [K]
+    bounds
+};
+
+/// Check a freshly integrated stock vector before `WorldState::from_vec`
+/// clamps it: every stock must be finite and within
+/// [`STOCK_LOWER_BOUNDS`]..=[`STOCK_UPPER_BOUNDS`]. `last_good` is the state
+/// the step started from.
+pub(crate) fn check_stocks(
+    year: f64,
+    y: &[f64],
+    last_good: &WorldState,
+) -> Result<(), SolverError> {
+    for (i, &value) in y.iter().enumerate() {
+        let ok = value.is_finite()
+            && (STOCK_LOWER_BOUNDS[i]..=STOCK_UPPER_BOUNDS[i]).contains(&value);
+        if ok {
+            continue;
+        }
+        let (sector, variable) = WorldState::STOCK_NAMES[i];
+        let (sector, variable) = (sector.to_string(), variable.to_string());
+        let last_good = Box::new(last_good.clone());
+        return Err(if !value.is_finite() {
+            SolverError::NonFinite { sector, variable, year, value, last_good }
+        } else if value < 0.0 && STOCK_LOWER_BOUNDS[i] == 0.0 {
+            SolverError::NegativeStock { sector, variable, year, value, last_good }
+        } else {
+            SolverError::Diverged { sector, variable, year, value, last_good }
+        });
+    }
+    Ok(())
+}
+
+/// Right-hand side as a pure function of `(t, y)`: auxiliaries are rebuilt
+/// from the stocks before the derivatives are evaluated (DYNAMO ordering).
+pub(crate) fn rhs(
+    t: f64,
+    y: &[f64],
+    params: &ScenarioParams,
+    tables: &WorldLookupTables,
+) -> Vec<f64> {
+    let mut state = WorldState::from_vec(t, y);
+    compute_auxiliaries(&mut state, params, tables);
+    derivatives(&state, params, tables).to_vec()
+}
+
+/// Cubic Hermite interpolation on `[t0, t0 + h]` at fraction `theta`.
+pub(crate) fn hermite(
+    y0: &[f64],
+    f0: &[f64],
+    y1: &[f64],
+    f1: &[f64],
+    h: f64,
+    theta: f64,
+) -> Vec<f64> {
+    let t2 = theta * theta;
+    let t3 = t2 * theta;
+    let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+    let h10 = t3 - 2.0 * t2 + theta;
+    let h01 = -2.0 * t3 + 3.0 * t2;
+    let h11 = t3 - t2;
+    (0..y0.len())
+        .map(|i| h00 * y0[i] + h10 * h * f0[i] + h01 * y1[i] + h11 * h * f1[i])
+        .collect()
+}
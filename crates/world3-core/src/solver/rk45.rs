@@ -0,0 +1,301 @@
+//! Adaptive-step Dormand–Prince RK5(4) solver for the World 3 ODE system.
+//!
+//! Each step evaluates the 7-stage Dormand–Prince pair: the 5th-order
+//! solution is propagated and the embedded 4th-order solution provides a
+//! local error estimate. Steps whose scaled error exceeds 1 are rejected and
+//! retried with a smaller step; accepted steps grow the step size up to
+//! `max_step`. The last stage is evaluated at the new point, so it is reused
+//! as the first stage of the next step (FSAL).
+//!
+//! The stepping loop lives in [`Simulation`]. Output is still emitted on the
+//! fixed output grid (`params.output_interval`, default `time_step`): grid
+//! points that fall inside an accepted step are filled by cubic Hermite
+//! interpolation from the step's end-point values and slopes, then
+//! auxiliaries are recomputed on the interpolated state.
+//!
+//! Several sectors read auxiliaries computed earlier in the step (e.g. the
+//! resource sector reads `industrial_output_per_capita`, agriculture reads
+//! `food_per_capita`). Every stage therefore rebuilds the stage state from
+//! its stocks and recomputes its auxiliaries before evaluating derivatives,
+//! so the right-hand side is a pure function of `(t, y)` — a requirement for
+//! the error estimate to be meaningful. This matches the DYNAMO ordering
+//! used by the Euler solver.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::control::RunControl;
+use crate::lookup::tables::WorldLookupTables;
+use crate::model::{params::ScenarioParams, state::WorldState};
+use crate::simulation::{Simulation, StepMethod};
+use crate::solver::traits::{OdeSolver, PartialTrajectory, SolverError};
+
+// Dormand–Prince 5(4) Butcher tableau
+const C2: f64 = 1.0 / 5.0;
+const C3: f64 = 3.0 / 10.0;
+const C4: f64 = 4.0 / 5.0;
+const C5: f64 = 8.0 / 9.0;
+
+const A21: f64 = 1.0 / 5.0;
+const A31: f64 = 3.0 / 40.0;
+const A32: f64 = 9.0 / 40.0;
+const A41: f64 = 44.0 / 45.0;
+const A42: f64 = -56.0 / 15.0;
+const A43: f64 = 32.0 / 9.0;
+const A51: f64 = 19372.0 / 6561.0;
+const A52: f64 = -25360.0 / 2187.0;
+const A53: f64 = 64448.0 / 6561.0;
+const A54: f64 = -212.0 / 729.0;
+const A61: f64 = 9017.0 / 3168.0;
+const A62: f64 = -355.0 / 33.0;
+const A63: f64 = 46732.0 / 5247.0;
+const A64: f64 = 49.0 / 176.0;
+const A65: f64 = -5103.0 / 18656.0;
+
+// 5th-order weights (also row 7 of the tableau)
+const B1: f64 = 35.0 / 384.0;
+const B3: f64 = 500.0 / 1113.0;
+const B4: f64 = 125.0 / 192.0;
+const B5: f64 = -2187.0 / 6784.0;
+const B6: f64 = 11.0 / 84.0;
+
+// Error weights: 5th-order minus embedded 4th-order
+const E1: f64 = 71.0 / 57600.0;
+const E3: f64 = -71.0 / 16695.0;
+const E4: f64 = 71.0 / 1920.0;
+const E5: f64 = -17253.0 / 339200.0;
+const E6: f64 = 22.0 / 525.0;
+const E7: f64 = -1.0 / 40.0;
+
+/// Default controller settings of [`Rk45Solver::new`], which
+/// [`StepMethod::from_kind`] also uses for `SolverKind::Rk45`
+pub const DEFAULT_RTOL: f64 = 1e-6;
+pub const DEFAULT_ATOL: f64 = 1e-9;
+/// [years]
+pub const DEFAULT_MAX_STEP: f64 = 5.0;
+/// [years]
+pub const DEFAULT_MIN_STEP: f64 = 1e-6;
+
+/// Safety factor applied to the optimal step-size estimate
+const SAFETY: f64 = 0.9;
+/// Bounds on the step-size change per step
+const MIN_FACTOR: f64 = 0.2;
+const MAX_FACTOR: f64 = 5.0;
+
+/// Step counters reported by [`Rk45Solver::solve_with_stats`].
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct StepStats {
+    /// Steps whose error estimate was within tolerance
+    pub accepted: usize,
+    /// Steps retried with a smaller step size
+    pub rejected: usize,
+    /// Total right-hand-side evaluations
+    pub evaluations: usize,
+}
+
+pub struct Rk45Solver {
+    pub tables: Arc<WorldLookupTables>,
+    /// Relative tolerance on each stock
+    pub rtol: f64,
+    /// Absolute tolerance on each stock (stocks are in mixed units, so keep this small)
+    pub atol: f64,
+    /// Largest step the controller may take [years]
+    pub max_step: f64,
+    /// Smallest step before the solver gives up [years]
+    pub min_step: f64,
+}
+
+impl Rk45Solver {
+    pub fn new(tables: Arc<WorldLookupTables>) -> Self {
+        Self {
+            tables,
+            rtol: DEFAULT_RTOL,
+            atol: DEFAULT_ATOL,
+            max_step: DEFAULT_MAX_STEP,
+            min_step: DEFAULT_MIN_STEP,
+        }
+    }
+
+    pub fn with_tolerances(mut self, rtol: f64, atol: f64) -> Self {
+        self.rtol = rtol;
+        self.atol = atol;
+        self
+    }
+
+    pub fn with_max_step(mut self, max_step: f64) -> Self {
+        self.max_step = max_step;
+        self
+    }
+
+    fn method(&self) -> StepMethod {
+        StepMethod::Rk45 {
+            rtol: self.rtol,
+            atol: self.atol,
+            max_step: self.max_step,
+            min_step: self.min_step,
+        }
+    }
+
+    /// Integrate like [`OdeSolver::solve`] and also report step counters.
+    pub fn solve_with_stats(
+        &self,
+        initial: WorldState,
+        params: &ScenarioParams,
+    ) -> Result<(Vec<WorldState>, StepStats), SolverError> {
+        let tables = Arc::clone(&self.tables);
+        let mut sim = Simulation::with_method(initial, params.clone(), tables, self.method())?;
+        let states: Vec<WorldState> = sim.by_ref().collect();
+        match sim.take_error() {
+            Some(e) => Err(e),
+            None => Ok((states, sim.stats())),
+        }
+    }
+}
+
+impl OdeSolver for Rk45Solver {
+    fn solve_partial_with(
+        &self,
+        initial: WorldState,
+        params: &ScenarioParams,
+        control: &RunControl,
+    ) -> PartialTrajectory {
+        let tables = Arc::clone(&self.tables);
+        match Simulation::with_method(initial, params.clone(), tables, self.method()) {
+            Ok(sim) => sim.with_control(control.clone()).collect_partial(),
+            Err(e) => e.into(),
+        }
+    }
+}
+
+/// Result of one Dormand–Prince step attempt from `(t, y)` with step `h`.
+pub(crate) struct DopriAttempt {
+    pub y_new: Vec<f64>,
+    /// Slope at the new point (first stage of the next step)
+    pub k7: Vec<f64>,
+    /// Scaled RMS error estimate; the step is acceptable when `err <= 1`
+    pub err: f64,
+}
+
+/// Evaluate the six new stages of a Dormand–Prince step. `k1` is the slope
+/// at `(t, y)` carried over from the previous step; `f` is the system's
+/// right-hand side.
+pub(crate) fn dopri_attempt(
+    t: f64,
+    y: &[f64],
+    k1: &[f64],
+    h: f64,
+    rtol: f64,
+    atol: f64,
+    f: impl Fn(f64, &[f64]) -> Vec<f64>,
+) -> DopriAttempt {
+    let y2 = axpy(y, h, &[(A21, k1)]);
+    let k2 = f(t + C2 * h, &y2);
+    let y3 = axpy(y, h, &[(A31, k1), (A32, &k2)]);
+    let k3 = f(t + C3 * h, &y3);
+    let y4 = axpy(y, h, &[(A41, k1), (A42, &k2), (A43, &k3)]);
+    let k4 = f(t + C4 * h, &y4);
+    let y5 = axpy(y, h, &[(A51, k1), (A52, &k2), (A53, &k3), (A54, &k4)]);
+    let k5 = f(t + C5 * h, &y5);
+    let y6 = axpy(y, h, &[(A61, k1), (A62, &k2), (A63, &k3), (A64, &k4), (A65, &k5)]);
+    let k6 = f(t + h, &y6);
+    let y_new = axpy(y, h, &[(B1, k1), (B3, &k3), (B4, &k4), (B5, &k5), (B6, &k6)]);
+    let k7 = f(t + h, &y_new);
+
+    // Scaled RMS norm of the local error estimate
+    let sum: f64 = (0..y.len())
+        .map(|i| {
+            let e = h
+                * (E1 * k1[i] + E3 * k3[i] + E4 * k4[i] + E5 * k5[i] + E6 * k6[i] + E7 * k7[i]);
+            let scale = atol + rtol * y[i].abs().max(y_new[i].abs());
+            (e / scale).powi(2)
+        })
+        .sum();
+    let err = (sum / y.len() as f64).sqrt();
+
+    DopriAttempt { y_new, k7, err }
+}
+
+/// Step-size multiplier suggested by an error estimate.
+pub(crate) fn step_factor(err: f64) -> f64 {
+    if !err.is_finite() {
+        MIN_FACTOR
+    } else if err == 0.0 {
+        MAX_FACTOR
+    } else {
+        (SAFETY * err.powf(-0.2)).clamp(MIN_FACTOR, MAX_FACTOR)
+    }
+}
+
+/// `y + h * Σ aᵢ kᵢ`
+fn axpy(y: &[f64], h: f64, terms: &[(f64, &[f64])]) -> Vec<f64> {
+    let mut out = y.to_vec();
+    for (a, k) in terms {
+        for (o, ki) in out.iter_mut().zip(k.iter()) {
+            *o += h * a * ki;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::initial::initial_conditions_1900 as initial;
+    use crate::model::params::SolverKind;
+
+    #[test]
+    fn test_rk45_emits_output_grid() {
+        let solver = Rk45Solver::new(Arc::new(WorldLookupTables::load()));
+        // `params.solver = rk45` runs the same controller
+        assert_eq!(solver.method(), StepMethod::from_kind(SolverKind::Rk45));
+        let mut params = ScenarioParams::bau();
+        params.time_step = 2.5;
+        let (states, stats) = solver.solve_with_stats(initial(), &params).unwrap();
+        assert_eq!(states.len(), 81);
+        for (i, s) in states.iter().enumerate() {
+            assert!((s.time - (1900.0 + 2.5 * i as f64)).abs() < 1e-9);
+        }
+        assert!(stats.accepted > 0);
+    }
+
+    #[test]
+    fn test_rk45_converges_with_tolerance() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let params = ScenarioParams::bau();
+        let loose = Rk45Solver::new(Arc::clone(&tables))
+            .solve(initial(), &params)
+            .unwrap();
+        let tight = Rk45Solver::new(tables)
+            .with_tolerances(1e-10, 1e-13)
+            .solve(initial(), &params)
+            .unwrap();
+        for (a, b) in loose.iter().zip(tight.iter()) {
+            approx::assert_relative_eq!(
+                a.population.population,
+                b.population.population,
+                max_relative = 1e-4
+            );
+        }
+    }
+
+    #[test]
+    fn test_rk45_agrees_with_fine_euler() {
+        let tables = Arc::new(WorldLookupTables::load());
+        let params = ScenarioParams::bau();
+        let rk45 = Rk45Solver::new(Arc::clone(&tables))
+            .solve(initial(), &params)
+            .unwrap();
+        let euler = crate::solver::EulerSolver::new(tables)
+            .with_dt(0.02)
+            .solve(initial(), &params)
+            .unwrap();
+        for (a, b) in rk45.iter().zip(euler.iter()).step_by(25) {
+            approx::assert_relative_eq!(
+                a.population.population,
+                b.population.population,
+                max_relative = 1e-2
+            );
+        }
+    }
+}
@@ -1,24 +1,119 @@
+use crate::control::RunControl;
+use crate::events::Event;
 use crate::model::{params::ScenarioParams, state::WorldState};
 
 /// Abstract ODE solver.
 pub trait OdeSolver: Send + Sync {
-    /// Integrate the World 3 ODE from `initial.time` to `params.end_year`
-    /// in steps of `params.time_step`, returning one `WorldState` per step.
+    /// Integrate the World 3 ODE from `params.start_year` to
+    /// `params.end_year`, returning one `WorldState` per output point
+    /// (every `params.output_interval`, default `params.time_step`).
     fn solve(
         &self,
         initial: WorldState,
         params: &ScenarioParams,
-    ) -> Result<Vec<WorldState>, SolverError>;
+    ) -> Result<Vec<WorldState>, SolverError> {
+        self.solve_with(initial, params, &RunControl::default())
+    }
+
+    /// Like [`OdeSolver::solve`], but stop with [`SolverError::Cancelled`]
+    /// once `control.cancel` is set and report progress after every step.
+    fn solve_with(
+        &self,
+        initial: WorldState,
+        params: &ScenarioParams,
+        control: &RunControl,
+    ) -> Result<Vec<WorldState>, SolverError> {
+        self.solve_partial_with(initial, params, control).into_result()
+    }
+
+    /// Like [`OdeSolver::solve`], but on failure also keep the states
+    /// computed before it.
+    fn solve_partial(&self, initial: WorldState, params: &ScenarioParams) -> PartialTrajectory {
+        self.solve_partial_with(initial, params, &RunControl::default())
+    }
+
+    /// [`OdeSolver::solve_partial`] with cancellation and progress.
+    fn solve_partial_with(
+        &self,
+        initial: WorldState,
+        params: &ScenarioParams,
+        control: &RunControl,
+    ) -> PartialTrajectory;
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum SolverError {
-    #[error("State diverged at year {year:.1}: {variable} = {value:.3e}")]
+    #[error("State diverged at year {year:.1}: {sector}.{variable} = {value:.3e}")]
     Diverged {
+        sector: String,
+        variable: String,
         year: f64,
+        value: f64,
+        /// Last accepted state before the failure
+        last_good: Box<WorldState>,
+    },
+    #[error("Non-finite value at year {year:.1}: {sector}.{variable} = {value}")]
+    NonFinite {
+        sector: String,
         variable: String,
+        year: f64,
         value: f64,
+        last_good: Box<WorldState>,
     },
+    #[error("Negative stock at year {year:.1}: {sector}.{variable} = {value:.3e}")]
+    NegativeStock {
+        sector: String,
+        variable: String,
+        year: f64,
+        value: f64,
+        last_good: Box<WorldState>,
+    },
+    #[error("Step size underflow at year {year:.3}: h = {step:.3e}")]
+    StepSizeTooSmall { year: f64, step: f64 },
     #[error("Invalid initial conditions: {0}")]
     InvalidInitial(String),
+    #[error("Invalid event: {0}")]
+    InvalidEvent(String),
+    #[error("Cancelled at year {year:.1}")]
+    Cancelled { year: f64 },
+}
+
+impl SolverError {
+    /// Last accepted state before the failure, for errors raised by a step.
+    pub fn last_good(&self) -> Option<&WorldState> {
+        match self {
+            SolverError::Diverged { last_good, .. }
+            | SolverError::NonFinite { last_good, .. }
+            | SolverError::NegativeStock { last_good, .. } => Some(last_good),
+            _ => None,
+        }
+    }
+}
+
+/// States computed before a failure, returned by [`OdeSolver::solve_partial`]
+/// (and, for a regional run, one `Vec<WorldState>` per output point).
+#[derive(Debug)]
+pub struct PartialTrajectory<T = WorldState> {
+    /// Output states up to (not including) the failure
+    pub states: Vec<T>,
+    /// Events located before the failure
+    pub events: Vec<Event>,
+    /// The error that stopped the run, if any
+    pub error: Option<SolverError>,
+}
+
+/// A run that failed before producing any state.
+impl<T> From<SolverError> for PartialTrajectory<T> {
+    fn from(error: SolverError) -> Self {
+        Self { states: Vec::new(), events: Vec::new(), error: Some(error) }
+    }
+}
+
+impl<T> PartialTrajectory<T> {
+    pub fn into_result(self) -> Result<Vec<T>, SolverError> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.states),
+        }
+    }
 }
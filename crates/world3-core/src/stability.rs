@@ -0,0 +1,605 @@
+//! Steady states and linear stability of the stock system.
+//!
+//! The model is non-autonomous — technology compounds from 1970, family
+//! planning ramps in, schedules move levers — so everything here freezes
+//! time at one year and studies `dy/dt = f(y)` for the ten stocks of
+//! [`WorldState::to_vec`] at that year, with auxiliaries rebuilt from the
+//! stocks (the pure right-hand side the adaptive solver integrates).
+//!
+//! - [`linearize`] — numerical Jacobian at a state (e.g. any state of a
+//!   trajectory) and its eigenvalues: growth or decay rates, and periods of
+//!   oscillatory modes.
+//! - [`find_equilibrium`] — damped Newton search for `f(y) = 0` from a
+//!   guess, optionally holding some stocks fixed. Nonrenewable resources
+//!   have no inflow, so a strict steady state needs extraction to stop;
+//!   freezing `resources.nonrenewable_resources` asks for the quasi-steady
+//!   state at the current resource level instead.
+//!
+//! Stocks span fifteen orders of magnitude (persons vs. resource fraction),
+//! so rates are compared relative to each stock's scale: a residual of
+//! `1e-8` means no free stock changes by more than `1e-8` of itself per
+//! year.
+
+use serde::{Deserialize, Serialize};
+
+use crate::lookup::tables::WorldLookupTables;
+use crate::model::derivatives::{compute_auxiliaries, flows};
+use crate::model::initial::initial_conditions_1900;
+use crate::model::{params::ScenarioParams, state::WorldState};
+use crate::solver::rhs;
+
+/// Largest change of a stock per search step, in units of its scale.
+const MAX_STEP: f64 = 0.5;
+
+/// Pseudo-time step [years] at which the search is plain Newton.
+const MAX_PSEUDO_STEP: f64 = 1e12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Eigenvalue {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Eigenvalue {
+    /// Period [years] of the oscillation, for complex eigenvalues.
+    pub fn period(&self) -> Option<f64> {
+        (self.im != 0.0).then(|| 2.0 * std::f64::consts::PI / self.im.abs())
+    }
+
+    /// Years for the mode to grow or decay by a factor e.
+    pub fn time_constant(&self) -> f64 {
+        1.0 / self.re.abs()
+    }
+}
+
+/// Local linear behaviour around a state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Linearization {
+    pub year: f64,
+    /// `"sector.variable"` of each row and column
+    pub stocks: Vec<String>,
+    /// `∂(dy_i/dt) / ∂y_j`, in natural units
+    pub jacobian: Vec<Vec<f64>>,
+    /// Sorted by decreasing real part; complex pairs are adjacent
+    pub eigenvalues: Vec<Eigenvalue>,
+}
+
+impl Linearization {
+    /// Largest real part: the slowest decay (or fastest growth) rate.
+    pub fn spectral_abscissa(&self) -> f64 {
+        self.eigenvalues.iter().map(|e| e.re).fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// Every mode decays.
+    pub fn is_stable(&self) -> bool {
+        self.spectral_abscissa() < 0.0
+    }
+
+    /// One eigenvalue (positive imaginary part) per oscillatory mode.
+    pub fn oscillatory_modes(&self) -> impl Iterator<Item = &Eigenvalue> {
+        self.eigenvalues.iter().filter(|e| e.im > 0.0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquilibriumOptions {
+    /// Stocks (`"sector.variable"`) held at their guess values
+    #[serde(default)]
+    pub frozen: Vec<String>,
+    /// Converged when no free stock changes by more than this fraction of
+    /// itself per year
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+    #[serde(default = "default_max_iterations")]
+    pub max_iterations: usize,
+}
+
+fn default_tolerance() -> f64 {
+    1e-8
+}
+
+fn default_max_iterations() -> usize {
+    500
+}
+
+impl Default for EquilibriumOptions {
+    fn default() -> Self {
+        Self {
+            frozen: Vec::new(),
+            tolerance: default_tolerance(),
+            max_iterations: default_max_iterations(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Equilibrium {
+    /// Best state found, with auxiliaries and flows filled in
+    pub state: WorldState,
+    /// Largest relative rate of change of a free stock [1/year]
+    pub residual: f64,
+    pub iterations: usize,
+    pub converged: bool,
+    /// Linearization over the free stocks at `state`
+    pub linearization: Linearization,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StabilityError {
+    #[error("Unknown stock '{0}'. Use sector.variable, e.g. resources.nonrenewable_resources")]
+    UnknownStock(String),
+    #[error("Eigenvalue iteration did not converge")]
+    Eigenvalues,
+}
+
+/// Numerical Jacobian `∂(dy_i/dt) / ∂y_j` of all stocks at `state`, frozen
+/// at `state.time`, in [`WorldState::to_vec`] order.
+pub fn jacobian(
+    state: &WorldState,
+    params: &ScenarioParams,
+    tables: &WorldLookupTables,
+) -> Vec<Vec<f64>> {
+    jacobian_at(state.time, &state.to_vec(), params, tables)
+}
+
+/// Jacobian and eigenvalues of all stocks at `state`.
+pub fn linearize(
+    state: &WorldState,
+    params: &ScenarioParams,
+    tables: &WorldLookupTables,
+) -> Result<Linearization, StabilityError> {
+    let y = state.to_vec();
+    let all: Vec<usize> = (0..WorldState::N).collect();
+    linearize_over(state.time, &y, &all, params, tables)
+}
+
+/// Search for a steady state near `guess`, at `guess.time`.
+pub fn find_equilibrium(
+    guess: &WorldState,
+    params: &ScenarioParams,
+    tables: &WorldLookupTables,
+    options: &EquilibriumOptions,
+) -> Result<Equilibrium, StabilityError> {
+    let names = stock_names();
+    for stock in &options.frozen {
+        if !names.contains(stock) {
+            return Err(StabilityError::UnknownStock(stock.clone()));
+        }
+    }
+    let free: Vec<usize> =
+        (0..WorldState::N).filter(|&i| !options.frozen.contains(&names[i])).collect();
+    let year = guess.time;
+    let start = guess.to_vec();
+    let s = scales(&start);
+    let (y, residual, iterations, converged) =
+        steady_state(start, &s, &free, options, |y| rhs(year, y, params, tables));
+
+    let mut state = WorldState::from_vec(year, &y);
+    compute_auxiliaries(&mut state, params, tables);
+    state.flows = flows(&state, params, tables);
+    Ok(Equilibrium {
+        state,
+        residual,
+        iterations,
+        converged,
+        linearization: linearize_over(year, &y, &free, params, tables)?,
+    })
+}
+
+/// `"sector.variable"` of each stock.
+fn stock_names() -> Vec<String> {
+    WorldState::STOCK_NAMES
+        .iter()
+        .map(|(sector, variable)| format!("{}.{}", sector, variable))
+        .collect()
+}
+
+/// Scale of each stock: its magnitude, floored at a thousandth of its 1900
+/// value so stocks near zero keep a sensible step.
+fn scales(y: &[f64]) -> Vec<f64> {
+    let reference = initial_conditions_1900().to_vec();
+    y.iter().zip(reference).map(|(v, r)| v.abs().max(1e-3 * r.abs()).max(1e-12)).collect()
+}
+
+fn jacobian_at(
+    year: f64,
+    y: &[f64],
+    params: &ScenarioParams,
+    tables: &WorldLookupTables,
+) -> Vec<Vec<f64>> {
+    finite_difference_jacobian(y, &scales(y), |y| rhs(year, y, params, tables))
+}
+
+/// `∂f_i/∂y_j` with steps of `1e-6` of each scale: central differences,
+/// forward where stepping down would make the stock negative.
+fn finite_difference_jacobian(
+    y: &[f64],
+    s: &[f64],
+    f: impl Fn(&[f64]) -> Vec<f64>,
+) -> Vec<Vec<f64>> {
+    let f0 = f(y);
+    let mut j = vec![vec![0.0; y.len()]; f0.len()];
+    for col in 0..y.len() {
+        let h = 1e-6 * s[col];
+        let shifted = |dy: f64| {
+            let mut y = y.to_vec();
+            y[col] += dy;
+            f(&y)
+        };
+        let up = shifted(h);
+        let (down, width) = if y[col] >= h { (shifted(-h), 2.0 * h) } else { (f0.clone(), h) };
+        for (row, entry) in j.iter_mut().enumerate() {
+            entry[col] = (up[row] - down[row]) / width;
+        }
+    }
+    j
+}
+
+/// Drive the `free` components of `dy/dt = f(y)` to rest from `y`, by
+/// pseudo-transient continuation: implicit Euler steps of pseudo-time `dt`
+/// in scaled coordinates `z = y / s`, with `dt` growing as the residual
+/// falls until the steps become Newton steps. Following the dynamics finds
+/// the steady state the system settles into, where plain Newton tends to
+/// wander across the lookup tables' kinks.
+///
+/// Returns the final point, its residual (largest `|f_i| / s_i`), the
+/// iterations taken and whether the residual met the tolerance.
+fn steady_state(
+    mut y: Vec<f64>,
+    s: &[f64],
+    free: &[usize],
+    options: &EquilibriumOptions,
+    f: impl Fn(&[f64]) -> Vec<f64>,
+) -> (Vec<f64>, f64, usize, bool) {
+    let residual = |y: &[f64]| -> Vec<f64> {
+        let rates = f(y);
+        free.iter().map(|&i| rates[i] / s[i]).collect()
+    };
+    let max_norm = |r: &[f64]| r.iter().fold(0.0, |m: f64, v| m.max(v.abs()));
+    let norm2 = |r: &[f64]| r.iter().map(|v| v * v).sum::<f64>().sqrt();
+
+    let mut r = residual(&y);
+    let mut dt = 1.0;
+    let mut iterations = 0;
+    let mut converged = max_norm(&r) <= options.tolerance;
+    while !converged && iterations < options.max_iterations {
+        iterations += 1;
+        let j = finite_difference_jacobian(&y, s, &f);
+        // (I / dt − J) dz = r
+        let system: Vec<Vec<f64>> = free
+            .iter()
+            .enumerate()
+            .map(|(a, &i)| {
+                free.iter()
+                    .enumerate()
+                    .map(|(b, &k)| if a == b { 1.0 / dt } else { 0.0 } - j[i][k] * s[k] / s[i])
+                    .collect()
+            })
+            .collect();
+        let Some(mut dz) = solve_linear(system, r.clone()) else { break };
+        let largest = dz.iter().fold(0.0, |m: f64, d| m.max(d.abs()));
+        if largest > MAX_STEP {
+            dz.iter_mut().for_each(|d| *d *= MAX_STEP / largest);
+        }
+        let mut trial = y.clone();
+        for (&i, d) in free.iter().zip(&dz) {
+            trial[i] = (y[i] + d * s[i]).max(0.0);
+        }
+        let r_trial = residual(&trial);
+        if !r_trial.iter().all(|v| v.is_finite()) {
+            dt *= 0.1;
+            continue;
+        }
+        // Switched evolution relaxation
+        dt = (dt * norm2(&r) / norm2(&r_trial)).clamp(1e-3, MAX_PSEUDO_STEP);
+        y = trial;
+        r = r_trial;
+        converged = max_norm(&r) <= options.tolerance;
+    }
+    (y, max_norm(&r), iterations, converged)
+}
+
+/// Linearization restricted to the stocks at `indices`.
+fn linearize_over(
+    year: f64,
+    y: &[f64],
+    indices: &[usize],
+    params: &ScenarioParams,
+    tables: &WorldLookupTables,
+) -> Result<Linearization, StabilityError> {
+    let full = jacobian_at(year, y, params, tables);
+    let s = scales(y);
+    let names = stock_names();
+    let jacobian: Vec<Vec<f64>> =
+        indices.iter().map(|&a| indices.iter().map(|&b| full[a][b]).collect()).collect();
+    // Similar matrix in relative units: same eigenvalues, balanced entries
+    let scaled: Vec<Vec<f64>> = indices
+        .iter()
+        .map(|&a| indices.iter().map(|&b| full[a][b] * s[b] / s[a]).collect())
+        .collect();
+    let mut eigenvalues = eigenvalues(scaled).ok_or(StabilityError::Eigenvalues)?;
+    eigenvalues.sort_by(|a, b| b.re.total_cmp(&a.re).then(b.im.total_cmp(&a.im)));
+    Ok(Linearization {
+        year,
+        stocks: indices.iter().map(|&i| names[i].clone()).collect(),
+        jacobian,
+        eigenvalues,
+    })
+}
+
+/// Solve `a x = b` by Gaussian elimination with partial pivoting; `None`
+/// if `a` is singular.
+fn solve_linear(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for k in 0..n {
+        let pivot = (k..n).max_by(|&i, &j| a[i][k].abs().total_cmp(&a[j][k].abs()))?;
+        if a[pivot][k] == 0.0 || !a[pivot][k].is_finite() {
+            return None;
+        }
+        a.swap(k, pivot);
+        b.swap(k, pivot);
+        for i in k + 1..n {
+            let factor = a[i][k] / a[k][k];
+            let (upper, lower) = a.split_at_mut(i);
+            for (x, p) in lower[0][k..].iter_mut().zip(&upper[k][k..]) {
+                *x -= factor * p;
+            }
+            b[i] -= factor * b[k];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for k in (0..n).rev() {
+        let sum: f64 = (k + 1..n).map(|j| a[k][j] * x[j]).sum();
+        x[k] = (b[k] - sum) / a[k][k];
+    }
+    Some(x)
+}
+
+/// Eigenvalues of a real square matrix: reduction to Hessenberg form by
+/// elimination, then the shifted QR algorithm (Numerical Recipes `elmhes`
+/// and `hqr`). `None` if QR needs more than 30 iterations for one
+/// eigenvalue.
+// Kept index-for-index with the published algorithm
+#[allow(clippy::needless_range_loop)]
+fn eigenvalues(matrix: Vec<Vec<f64>>) -> Option<Vec<Eigenvalue>> {
+    let n = matrix.len();
+    // 1-based indexing keeps the classic algorithm recognizable
+    let mut a = vec![vec![0.0; n + 1]; n + 1];
+    for (i, row) in matrix.iter().enumerate() {
+        a[i + 1][1..].copy_from_slice(row);
+    }
+
+    // Hessenberg reduction
+    for m in 2..n {
+        let mut x: f64 = 0.0;
+        let mut i = m;
+        for j in m..=n {
+            if a[j][m - 1].abs() > x.abs() {
+                x = a[j][m - 1];
+                i = j;
+            }
+        }
+        if i != m {
+            a.swap(i, m);
+            for row in a.iter_mut() {
+                row.swap(i, m);
+            }
+        }
+        if x != 0.0 {
+            for i in m + 1..=n {
+                let mut y = a[i][m - 1];
+                if y != 0.0 {
+                    y /= x;
+                    a[i][m - 1] = y;
+                    for j in m..=n {
+                        a[i][j] -= y * a[m][j];
+                    }
+                    for j in 1..=n {
+                        a[j][m] += y * a[j][i];
+                    }
+                }
+            }
+        }
+    }
+    for (i, row) in a.iter_mut().enumerate().skip(3) {
+        row[1..i - 1].iter_mut().for_each(|v| *v = 0.0);
+    }
+
+    // Shifted QR on the Hessenberg matrix
+    let mut wr = vec![0.0; n + 1];
+    let mut wi = vec![0.0; n + 1];
+    let mut anorm = 0.0;
+    for i in 1..=n {
+        for j in i.saturating_sub(1).max(1)..=n {
+            anorm += a[i][j].abs();
+        }
+    }
+    let mut nn = n;
+    let mut t = 0.0;
+    while nn >= 1 {
+        let mut its = 0;
+        loop {
+            let mut l = nn;
+            while l >= 2 {
+                let mut s = a[l - 1][l - 1].abs() + a[l][l].abs();
+                if s == 0.0 {
+                    s = anorm;
+                }
+                if a[l][l - 1].abs() + s == s {
+                    a[l][l - 1] = 0.0;
+                    break;
+                }
+                l -= 1;
+            }
+            let mut x = a[nn][nn];
+            if l == nn {
+                // One root found
+                wr[nn] = x + t;
+                wi[nn] = 0.0;
+                nn -= 1;
+            } else {
+                let mut y = a[nn - 1][nn - 1];
+                let mut w = a[nn][nn - 1] * a[nn - 1][nn];
+                if l == nn - 1 {
+                    // Two roots found
+                    let p = 0.5 * (y - x);
+                    let q = p * p + w;
+                    let mut z = q.abs().sqrt();
+                    x += t;
+                    if q >= 0.0 {
+                        z = p + z.copysign(p);
+                        wr[nn - 1] = x + z;
+                        wr[nn] = if z != 0.0 { x - w / z } else { x + z };
+                        wi[nn - 1] = 0.0;
+                        wi[nn] = 0.0;
+                    } else {
+                        wr[nn - 1] = x + p;
+                        wr[nn] = x + p;
+                        wi[nn - 1] = -z;
+                        wi[nn] = z;
+                    }
+                    nn = nn.saturating_sub(2);
+                } else {
+                    if its == 30 {
+                        return None;
+                    }
+                    if its == 10 || its == 20 {
+                        // Exceptional shift
+                        t += x;
+                        for i in 1..=nn {
+                            a[i][i] -= x;
+                        }
+                        let s = a[nn][nn - 1].abs() + a[nn - 1][nn - 2].abs();
+                        x = 0.75 * s;
+                        y = x;
+                        w = -0.4375 * s * s;
+                    }
+                    its += 1;
+                    let (mut p, mut q, mut r, mut z);
+                    let mut m = nn - 2;
+                    loop {
+                        z = a[m][m];
+                        r = x - z;
+                        let s = y - z;
+                        p = (r * s - w) / a[m + 1][m] + a[m][m + 1];
+                        q = a[m + 1][m + 1] - z - r - s;
+                        r = a[m + 2][m + 1];
+                        let s = p.abs() + q.abs() + r.abs();
+                        p /= s;
+                        q /= s;
+                        r /= s;
+                        if m == l {
+                            break;
+                        }
+                        let u = a[m][m - 1].abs() * (q.abs() + r.abs());
+                        let v = p.abs() * (a[m - 1][m - 1].abs() + z.abs() + a[m + 1][m + 1].abs());
+                        if u + v == v {
+                            break;
+                        }
+                        m -= 1;
+                    }
+                    for i in m + 2..=nn {
+                        a[i][i - 2] = 0.0;
+                        if i != m + 2 {
+                            a[i][i - 3] = 0.0;
+                        }
+                    }
+                    for k in m..nn {
+                        if k != m {
+                            p = a[k][k - 1];
+                            q = a[k + 1][k - 1];
+                            r = if k != nn - 1 { a[k + 2][k - 1] } else { 0.0 };
+                            x = p.abs() + q.abs() + r.abs();
+                            if x != 0.0 {
+                                p /= x;
+                                q /= x;
+                                r /= x;
+                            }
+                        }
+                        let s = (p * p + q * q + r * r).sqrt().copysign(p);
+                        if s == 0.0 {
+                            continue;
+                        }
+                        if k == m {
+                            if l != m {
+                                a[k][k - 1] = -a[k][k - 1];
+                            }
+                        } else {
+                            a[k][k - 1] = -s * x;
+                        }
+                        p += s;
+                        x = p / s;
+                        y = q / s;
+                        z = r / s;
+                        q /= p;
+                        r /= p;
+                        for j in k..=nn {
+                            p = a[k][j] + q * a[k + 1][j];
+                            if k != nn - 1 {
+                                p += r * a[k + 2][j];
+                                a[k + 2][j] -= p * z;
+                            }
+                            a[k + 1][j] -= p * y;
+                            a[k][j] -= p * x;
+                        }
+                        for i in l..=nn.min(k + 3) {
+                            p = x * a[i][k] + y * a[i][k + 1];
+                            if k != nn - 1 {
+                                p += z * a[i][k + 2];
+                                a[i][k + 2] -= p * r;
+                            }
+                            a[i][k + 1] -= p * q;
+                            a[i][k] -= p;
+                        }
+                    }
+                }
+            }
+            if nn < 2 || l + 1 >= nn {
+                break;
+            }
+        }
+    }
+    Some((1..=n).map(|i| Eigenvalue { re: wr[i], im: wi[i] }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steady_state_of_damped_predator_prey() {
+        // Logistic prey x, predator y: interior focus at x = 2, y = 0.8,
+        // besides the unstable (0, 0) and (10, 0)
+        let f = |v: &[f64]| {
+            let (x, y) = (v[0], v[1]);
+            vec![x * (1.0 - x / 10.0) - x * y, 0.5 * x * y - y]
+        };
+        let (y, residual, _, converged) =
+            steady_state(vec![5.0, 2.0], &[5.0, 2.0], &[0, 1], &EquilibriumOptions::default(), f);
+        assert!(converged && residual < 1e-8);
+        assert!((y[0] - 2.0).abs() < 1e-6 && (y[1] - 0.8).abs() < 1e-6, "{:?}", y);
+
+        // Holding the predator at 0 leaves the prey at carrying capacity
+        let (y, _, _, converged) =
+            steady_state(vec![5.0, 0.0], &[5.0, 1.0], &[0], &EquilibriumOptions::default(), f);
+        assert!(converged && (y[0] - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_eigenvalues_of_companion_matrix() {
+        // x⁴ + 5x³ + 13x² + 19x + 10 = (x + 1)(x + 2)(x² + 2x + 5)
+        let companion = vec![
+            vec![-5.0, -13.0, -19.0, -10.0],
+            vec![1.0, 0.0, 0.0, 0.0],
+            vec![0.0, 1.0, 0.0, 0.0],
+            vec![0.0, 0.0, 1.0, 0.0],
+        ];
+        let found = eigenvalues(companion).unwrap();
+        for (re, im) in [(-1.0, 2.0), (-1.0, -2.0), (-1.0, 0.0), (-2.0, 0.0)] {
+            let close = |e: &Eigenvalue| (e.re - re).abs() < 1e-9 && (e.im - im).abs() < 1e-9;
+            assert!(found.iter().any(close), "{:?} missing from {:?}", (re, im), found);
+        }
+        let mode = found.iter().find(|e| e.im > 0.0).unwrap();
+        assert!((mode.period().unwrap() - std::f64::consts::PI).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,28 @@
+//! Fixtures shared by the unit tests.
+
+use std::sync::{Arc, OnceLock};
+
+use crate::lookup::tables::WorldLookupTables;
+use crate::model::{initial::initial_conditions_1900, params::ScenarioParams, state::WorldState};
+use crate::output::SimulationOutput;
+use crate::simulation::Simulation;
+
+/// Lookup tables, loaded once for the whole test binary.
+pub(crate) fn tables() -> Arc<WorldLookupTables> {
+    static TABLES: OnceLock<Arc<WorldLookupTables>> = OnceLock::new();
+    Arc::clone(TABLES.get_or_init(|| Arc::new(WorldLookupTables::load())))
+}
+
+/// Run `params` from the 1900 initial conditions; panics if the run fails.
+pub(crate) fn run(params: ScenarioParams) -> SimulationOutput {
+    run_from(initial_conditions_1900(), params)
+}
+
+/// Run `params` from `initial`; panics if the run fails.
+pub(crate) fn run_from(initial: WorldState, params: ScenarioParams) -> SimulationOutput {
+    let states = Simulation::new(initial, params.clone(), tables())
+        .unwrap()
+        .collect_states()
+        .unwrap();
+    SimulationOutput::new(states, params)
+}
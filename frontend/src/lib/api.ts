@@ -1,5 +1,7 @@
 import { PUBLIC_API_BASE } from '$env/static/public';
 import type {
+	ElasticitySpec,
+	ElasticityTable,
 	ParameterDescriptor,
 	Scenario,
 	ScenarioParams,
@@ -56,3 +58,10 @@ export function deleteScenario(id: string): Promise<void> {
 export function runScenario(id: string): Promise<SimulationOutput> {
 	return apiFetch(`/scenarios/${encodeURIComponent(id)}/run`, { method: 'POST' });
 }
+
+export function getElasticities(id: string, spec: ElasticitySpec = {}): Promise<ElasticityTable> {
+	return apiFetch(`/scenarios/${encodeURIComponent(id)}/elasticities`, {
+		method: 'POST',
+		body: JSON.stringify(spec)
+	});
+}
@@ -22,11 +22,14 @@ export interface CapitalState {
 	industrial_output: number;
 	industrial_output_per_capita: number;
 	service_output_per_capita: number;
+	health_services_per_capita: number;
+	education_services_per_capita: number;
 }
 
 export interface AgricultureState {
 	arable_land: number;
 	potentially_arable_land: number;
+	land_fertility: number;
 	food: number;
 	food_per_capita: number;
 	land_yield: number;
@@ -45,6 +48,157 @@ export interface PollutionState {
 	assimilation_rate: number;
 }
 
+export interface LaborState {
+	perceived_utilization: number;
+	industrial_jobs: number;
+	service_jobs: number;
+	agricultural_jobs: number;
+	jobs: number;
+	labor_force: number;
+	employment: number;
+	unemployment_rate: number;
+	labor_utilization: number;
+	capacity_utilization: number;
+}
+
+export interface ClimateState {
+	atmospheric_carbon: number;
+	sink_carbon: number;
+	temperature_anomaly: number;
+	deep_ocean_temperature: number;
+	carbon_emissions: number;
+	co2_concentration: number;
+	radiative_forcing: number;
+}
+
+export interface EnergyState {
+	fossil_capital: number;
+	renewable_capital: number;
+	energy_demand: number;
+	net_energy_supply: number;
+	/** Net supply / demand; industrial output is constrained below 1 */
+	energy_availability: number;
+	fossil_energy: number;
+	renewable_energy: number;
+	renewable_share: number;
+}
+
+export interface BiodiversityState {
+	/** Share of the original species abundance left [0..1] */
+	intactness: number;
+	indicated_intactness: number;
+	urban_land: number;
+	absorption_land: number;
+	/** Arable, urban and absorption land over Earth's biocapacity */
+	ecological_footprint: number;
+}
+
+export interface WelfareState {
+	gdp_per_capita: number;
+	life_expectancy_index: number;
+	education_index: number;
+	gdp_index: number;
+	human_welfare_index: number;
+}
+
+export interface IncomeGroup {
+	/** Share of total income [0..1] */
+	income_share: number;
+	industrial_output_per_capita: number;
+	food_per_capita: number;
+	life_expectancy: number;
+	fertility_rate: number;
+}
+
+export interface InequalityState {
+	gini: number;
+	/** Fraction of the population in groups fed below subsistence */
+	starving_fraction: number;
+	/** Equal population groups, poorest first (only with the `groups` detail) */
+	groups?: IncomeGroup[];
+}
+
+/** A region's exchanges with the other regions of a regional run; zero otherwise */
+export interface TradeState {
+	/** Net food imports [kg / year] */
+	food_imports: number;
+	/** Net resource imports [own 1900 endowment / year] */
+	resource_imports: number;
+	resource_fraction_remaining: number | null;
+	/** Net immigration [persons / year] */
+	net_migration: number;
+}
+
+/** Every stock in- and outflow at one state [stock units / year] */
+export interface Flows {
+	births: number;
+	deaths_0_14: number;
+	deaths_15_44: number;
+	deaths_45_64: number;
+	deaths_65_plus: number;
+	aging_0_to_15: number;
+	aging_15_to_45: number;
+	aging_45_to_65: number;
+	industrial_investment: number;
+	industrial_depreciation: number;
+	service_investment: number;
+	service_depreciation: number;
+	land_development: number;
+	land_erosion: number;
+	land_fertility_degradation: number;
+	land_fertility_regeneration: number;
+	resource_extraction: number;
+	pollution_generation: number;
+	pollution_assimilation: number;
+	labor_utilization_adjustment: number;
+	carbon_emissions: number;
+	carbon_uptake: number;
+	surface_warming: number;
+	deep_ocean_warming: number;
+	fossil_energy_investment: number;
+	fossil_energy_depreciation: number;
+	renewable_energy_investment: number;
+	renewable_energy_depreciation: number;
+	biodiversity_loss: number;
+	biodiversity_recovery: number;
+	net_migration: number;
+}
+
+/** Lookup multipliers and factors behind the auxiliaries at one state */
+export interface Multipliers {
+	lem_food: number;
+	lem_health: number;
+	lem_crowding: number;
+	lem_pollution: number;
+	lem_climate: number;
+	desired_family_size: number;
+	education_fertility: number;
+	fp_multiplier: number;
+	food_fertility: number;
+	land_fertility_ratio: number;
+	yield_multiplier_capital: number;
+	yield_multiplier_pollution: number;
+	yield_multiplier_climate: number;
+	agricultural_technology: number;
+	frac_to_agriculture: number;
+	land_development_cost: number;
+	erosion_multiplier: number;
+	frac_to_land_maintenance: number;
+	fertility_regeneration_time: number;
+	cor_multiplier: number;
+	tech_multiplier: number;
+	capital_fraction_resources: number;
+	frac_to_services: number;
+	frac_services_health: number;
+	pollution_gen_industry: number;
+	pollution_gen_agriculture: number;
+	assimilation_time: number;
+	labor_productivity: number;
+	fossil_eroi: number;
+	energy_output_multiplier: number;
+	frac_to_energy: number;
+}
+
 export interface WorldState {
 	time: number;
 	population: PopulationState;
@@ -52,6 +206,24 @@ export interface WorldState {
 	agriculture: AgricultureState;
 	resources: ResourceState;
 	pollution: PollutionState;
+	labor: LaborState;
+	climate: ClimateState;
+	energy: EnergyState;
+	biodiversity: BiodiversityState;
+	welfare: WelfareState;
+	inequality: InequalityState;
+	trade: TradeState;
+	/** Only with the `flows` detail */
+	flows?: Flows;
+	/** Only with the `multipliers` detail */
+	multipliers?: Multipliers;
+}
+
+/** Optional per-state blocks of `sim_step` states and run outputs */
+export interface StateDetail {
+	flows?: boolean;
+	multipliers?: boolean;
+	groups?: boolean;
 }
 
 // ---------------------------------------------------------------------------
@@ -66,11 +238,19 @@ export interface ScenarioMeta {
 	created_at: string;
 }
 
+export interface RegionScale {
+	population: number;
+	land: number;
+	resources: number;
+}
+
 export interface ScenarioParams {
 	meta: ScenarioMeta;
 	family_planning_year: number;
 	family_planning_efficacy: number;
 	health_investment_multiplier: number;
+	/** Shift of the health share of service output; the rest funds education */
+	health_share_shift: number;
 	industrial_depreciation_rate: number;
 	service_depreciation_rate: number;
 	technology_growth_rate: number;
@@ -81,9 +261,37 @@ export interface ScenarioParams {
 	resource_efficiency: number;
 	initial_nnr_fraction: number;
 	pollution_control: number;
+	carbon_intensity_decline: number;
+	carbon_capture: number;
+	/** Annual growth of the renewable share of energy investment */
+	energy_transition_rate: number;
+	/** Gini coefficient of income between the income groups */
+	income_inequality: number;
+	/** Number of equal-sized income groups (default 5) */
+	income_groups?: number;
+	/** Share of the world a regional run's region covers (default 1.0 each) */
+	region?: RegionScale;
 	start_year: number;
 	end_year: number;
 	time_step: number;
+	/** Spacing of reported states [years]; defaults to time_step */
+	output_interval?: number | null;
+	solver?: SolverKind;
+	/** Time-varying levers by field name; overrides the constant value */
+	schedules?: Record<string, Schedule>;
+}
+
+export type Schedule =
+	| { kind: 'step'; year: number; before: number; after: number }
+	| { kind: 'ramp'; start: number; end: number; from: number; to: number }
+	| { kind: 'piecewise'; points: [number, number][] };
+
+export type SolverKind = 'rk4' | 'rk45' | 'euler';
+
+/** Saved simulation state; POST it back to /scenarios/:id/run to resume. */
+export interface Checkpoint {
+	state: WorldState;
+	step_size?: number | null;
 }
 
 export interface Scenario {
@@ -111,6 +319,52 @@ export interface SimulationOutput {
 	states: WorldState[];
 	params: ScenarioParams;
 	computed_at: string;
+	/** Events located during the run, in order of occurrence */
+	events?: SimEvent[];
+	/** Set when a partial run stopped early on a solver error */
+	error?: string;
+	ledger: FlowLedger;
+}
+
+/** Cumulative flows over a run and per-stock accounting */
+export interface FlowLedger {
+	cumulative: Flows[];
+	balance: StockBalance[];
+}
+
+export interface StockBalance {
+	sector: string;
+	variable: string;
+	change: number;
+	net_flow: number;
+	residual: number;
+}
+
+/** Body of POST /scenarios/:id/elasticities; empty lists mean all */
+export interface ElasticitySpec {
+	parameters?: string[];
+	variables?: string[];
+	relative_step?: number;
+}
+
+/** Finite-difference sensitivities, indexed [year][variable][parameter] */
+export interface ElasticityTable {
+	timeline: number[];
+	variables: string[];
+	parameters: string[];
+	/** (∂y/y) / (∂p/p); null where y is zero or a perturbed run failed */
+	elasticities: (number | null)[][][];
+	/** ∂y/∂p */
+	derivatives: (number | null)[][][];
+	runs: number;
+	failed: number;
+}
+
+export interface SimEvent {
+	name: string;
+	year: number;
+	value: number;
+	terminal: boolean;
 }
 
 // ---------------------------------------------------------------------------
@@ -127,6 +381,8 @@ export interface ParameterDescriptor {
 	step: number;
 	sector: string;
 	description: string;
+	/** Whether the lever accepts a schedule in ScenarioParams.schedules */
+	schedulable: boolean;
 }
 
 // ---------------------------------------------------------------------------
@@ -134,8 +390,8 @@ export interface ParameterDescriptor {
 // ---------------------------------------------------------------------------
 
 export type WsClientMsg =
-	| { type: 'start_simulation'; scenario_id: string; params?: ScenarioParams }
-	| { type: 'update_params'; scenario_id: string; params: ScenarioParams }
+	| { type: 'start_simulation'; scenario_id: string; params?: ScenarioParams; detail?: StateDetail }
+	| { type: 'update_params'; scenario_id: string; params: ScenarioParams; detail?: StateDetail }
 	| { type: 'stop_simulation' };
 
 export type WsServerMsg =
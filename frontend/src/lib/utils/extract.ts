@@ -1,4 +1,4 @@
-import type { WorldState } from '../types';
+import type { Flows, WorldState } from '../types';
 
 export interface DataPoint {
 	year: number;
@@ -45,6 +45,10 @@ function extractField(state: WorldState, path: string): number | null {
 		case 'pollution.pollution_index':
 			return state.pollution.pollution_index;
 		default:
+			if (path.startsWith('flows.') && state.flows) {
+				const value = state.flows[path.slice('flows.'.length) as keyof Flows];
+				return value ?? null;
+			}
 			return null;
 	}
 }